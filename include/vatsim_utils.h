@@ -0,0 +1,48 @@
+/*
+ * C interface to vatsim_utils, built with the `ffi` feature.
+ *
+ * See the `ffi` module's documentation for ownership rules.
+ */
+
+#ifndef VATSIM_UTILS_H
+#define VATSIM_UTILS_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum VatsimErrorCode {
+    VatsimOk = 0,
+    VatsimNullPointer = 1,
+    VatsimNetwork = 2,
+    VatsimClientError = 3,
+    VatsimServerError = 4,
+    VatsimRateLimited = 5,
+    VatsimInvalidData = 6,
+    VatsimOther = 7,
+    VatsimPanic = 8,
+} VatsimErrorCode;
+
+typedef struct VatsimHandle VatsimHandle;
+
+const char *vatsim_last_error(void);
+void vatsim_string_free(char *s);
+
+VatsimErrorCode vatsim_client_new(VatsimHandle **out);
+void vatsim_client_free(VatsimHandle *handle);
+VatsimErrorCode vatsim_get_v3_data(const VatsimHandle *handle, char **out);
+VatsimErrorCode vatsim_get_transceivers_data(const VatsimHandle *handle, char **out);
+
+VatsimErrorCode vatsim_user_ratings(uint64_t cid, char **out);
+VatsimErrorCode vatsim_get_ratings_times(uint64_t cid, char **out);
+VatsimErrorCode vatsim_get_connections(uint64_t cid, uint64_t page_number, char **out);
+VatsimErrorCode vatsim_get_flight_plans(uint64_t cid, uint64_t page_number, char **out);
+VatsimErrorCode vatsim_get_online_facilities(char **out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,1353 @@
+//! Reports and statistics built from a single snapshot of live data.
+//!
+//! These functions take the data returned from
+//! [`get_v3_data`](crate::live_api::Vatsim::get_v3_data) and summarize it
+//! in the shapes commonly shown on dashboards and websites.
+
+use crate::{
+    boundaries::{Boundary, Geofence},
+    distance::{distance_nm, AirportDatabase},
+    geojson::{Feature, FeatureCollection, Geometry},
+    models::{Atis, Controller, FlightPlan, Observers, Pilot, V3ResponseData},
+    spatial::PilotIndex,
+    tracking::AIRBORNE_GROUNDSPEED_KTS,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+};
+
+/// Departure and arrival counts for a single airport.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AirportTraffic {
+    /// Airport identifier, as filed in flight plans.
+    pub identifier: String,
+    /// Number of pilots with flight plans departing the airport.
+    pub departures: usize,
+    /// Number of pilots with flight plans arriving at the airport.
+    pub arrivals: usize,
+}
+
+impl AirportTraffic {
+    /// Total of departures and arrivals.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.departures + self.arrivals
+    }
+}
+
+/// Count departures and arrivals per airport from filed flight plans.
+///
+/// Airports are filtered by the start of their identifiers: if any
+/// prefixes are supplied, only airports starting with one of them are
+/// included, and an empty slice includes every airport. VATSIM regions
+/// and divisions don't map exactly onto identifiers, so to look at one,
+/// pass the prefixes of the countries it covers, like `&["EG"]` for the
+/// UK or `&["K", "PA", "PH"]` for the US. Results are sorted
+/// busiest-first.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{analysis::busiest_airports, live_api::Vatsim};
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let data = api.get_v3_data().await.unwrap();
+/// for airport in busiest_airports(&data, &["EG", "EI"]).iter().take(10) {
+///     println!("{}: {}", airport.identifier, airport.total());
+/// }
+/// # }
+/// ```
+///
+/// ```rust
+/// # #[cfg(feature = "fixtures")] {
+/// use vatsim_utils::{analysis::busiest_airports, fixtures};
+///
+/// let data = fixtures::v3_data();
+/// assert_eq!(busiest_airports(&data, &[]).len(), 6);
+///
+/// let mut airports: Vec<_> = busiest_airports(&data, &["EG", "KS"])
+///     .into_iter()
+///     .map(|a| a.identifier)
+///     .collect();
+/// airports.sort();
+/// assert_eq!(airports, ["EGLL", "KSAN", "KSEE", "KSFO"]);
+/// # }
+/// ```
+#[must_use]
+pub fn busiest_airports(data: &V3ResponseData, prefixes: &[&str]) -> Vec<AirportTraffic> {
+    count_airport_traffic(data, prefixes, |_, _| true)
+}
+
+/// Count departures and arrivals per airport, only including pilots
+/// who are within the distance (in nautical miles) of the airport.
+///
+/// This excludes pilots who have long since departed or are still far
+/// from their destination, giving a better picture of what is happening
+/// at the airport right now. Airports that aren't in
+/// [`AIRPORTS_MAP`](crate::distance::AIRPORTS_MAP) are not counted.
+///
+/// See [`busiest_airports`] for the prefixes and sorting.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{analysis::busiest_airports_nearby, live_api::Vatsim};
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let data = api.get_v3_data().await.unwrap();
+/// let airports = busiest_airports_nearby(&data, &[], 50.0);
+/// # }
+/// ```
+#[cfg(feature = "airports")]
+#[must_use]
+pub fn busiest_airports_nearby(
+    data: &V3ResponseData,
+    prefixes: &[&str],
+    within_nm: f64,
+) -> Vec<AirportTraffic> {
+    use crate::distance::{haversine, AIRPORTS_MAP};
+
+    count_airport_traffic(data, prefixes, |pilot, identifier| {
+        AIRPORTS_MAP.get(identifier).is_some_and(|airport| {
+            haversine(
+                pilot.latitude,
+                pilot.longitude,
+                airport.latitude,
+                airport.longitude,
+            ) <= within_nm
+        })
+    })
+}
+
+/// Count flight plan departures and arrivals for pilots passing the check.
+fn count_airport_traffic<F>(
+    data: &V3ResponseData,
+    prefixes: &[&str],
+    include: F,
+) -> Vec<AirportTraffic>
+where
+    F: Fn(&Pilot, &str) -> bool,
+{
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    let wanted = |identifier: &str| {
+        !identifier.is_empty()
+            && (prefixes.is_empty() || prefixes.iter().any(|p| identifier.starts_with(p)))
+    };
+    for pilot in &data.pilots {
+        let Some(plan) = &pilot.flight_plan else {
+            continue;
+        };
+        if wanted(&plan.departure) && include(pilot, &plan.departure) {
+            counts.entry(&plan.departure).or_default().0 += 1;
+        }
+        if wanted(&plan.arrival) && include(pilot, &plan.arrival) {
+            counts.entry(&plan.arrival).or_default().1 += 1;
+        }
+    }
+    let mut airports: Vec<_> = counts
+        .into_iter()
+        .map(|(identifier, (departures, arrivals))| AirportTraffic {
+            identifier: identifier.to_owned(),
+            departures,
+            arrivals,
+        })
+        .collect();
+    airports.sort_by(|a, b| {
+        b.total()
+            .cmp(&a.total())
+            .then_with(|| a.identifier.cmp(&b.identifier))
+    });
+    airports
+}
+
+/// Options for [`pilots_by_departure`] and [`pilots_by_arrival`].
+#[derive(Debug, Clone, Default)]
+pub struct AirportGroupOptions {
+    /// Whether to upper-case airport identifiers, so that "ksan" and
+    /// "KSAN" are grouped together.
+    pub case_insensitive: bool,
+    /// Key to group pilots without a flight plan, or with no airport
+    /// filed, under; they're left out if not set.
+    pub missing_key: Option<String>,
+}
+
+impl AirportGroupOptions {
+    /// Set whether airport identifiers are matched case-insensitively.
+    #[must_use]
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Group pilots without a filed airport under the key.
+    #[must_use]
+    pub fn missing_key(mut self, key: &str) -> Self {
+        self.missing_key = Some(key.to_owned());
+        self
+    }
+}
+
+/// Group pilots by the departure airport in their flight plan.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{
+///     analysis::{pilots_by_departure, AirportGroupOptions},
+///     live_api::Vatsim,
+/// };
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let data = api.get_v3_data().await.unwrap();
+/// let options = AirportGroupOptions::default().case_insensitive(true);
+/// let departures = pilots_by_departure(&data, &options);
+/// for pilot in departures.get("KSAN").into_iter().flatten() {
+///     println!("{}", pilot.callsign);
+/// }
+/// # }
+/// ```
+#[must_use]
+pub fn pilots_by_departure<'a>(
+    data: &'a V3ResponseData,
+    options: &AirportGroupOptions,
+) -> HashMap<String, Vec<&'a Pilot>> {
+    group_pilots(data, options, |plan| &plan.departure)
+}
+
+/// Group pilots by the arrival airport in their flight plan.
+///
+/// See [`pilots_by_departure`] for an example.
+#[must_use]
+pub fn pilots_by_arrival<'a>(
+    data: &'a V3ResponseData,
+    options: &AirportGroupOptions,
+) -> HashMap<String, Vec<&'a Pilot>> {
+    group_pilots(data, options, |plan| &plan.arrival)
+}
+
+/// Group pilots by an airport from their flight plan.
+fn group_pilots<'a, F>(
+    data: &'a V3ResponseData,
+    options: &AirportGroupOptions,
+    airport: F,
+) -> HashMap<String, Vec<&'a Pilot>>
+where
+    F: Fn(&FlightPlan) -> &str,
+{
+    let mut groups: HashMap<String, Vec<&Pilot>> = HashMap::new();
+    for pilot in &data.pilots {
+        let identifier = pilot
+            .flight_plan
+            .as_ref()
+            .map(|plan| airport(plan).trim())
+            .filter(|identifier| !identifier.is_empty());
+        let key = match identifier {
+            Some(identifier) if options.case_insensitive => identifier.to_uppercase(),
+            Some(identifier) => identifier.to_owned(),
+            None => match &options.missing_key {
+                Some(key) => key.clone(),
+                None => continue,
+            },
+        };
+        groups.entry(key).or_default().push(pilot);
+    }
+    groups
+}
+
+/// A group of online controllers.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CoverageGroup {
+    /// Name of the group, like "TWR" or "SAN".
+    pub name: String,
+    /// Callsigns of the controllers in the group, sorted.
+    pub callsigns: Vec<String>,
+}
+
+impl CoverageGroup {
+    /// Number of controllers in the group.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.callsigns.len()
+    }
+}
+
+/// Group online controllers by their facility type.
+///
+/// Groups are named by the facility's short name from the snapshot's
+/// `facilities` reference list (e.g. "TWR", "CTR"), falling back to the
+/// facility number if it isn't listed. Groups are returned in facility
+/// number order, and only facility types with controllers are included.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{analysis::controllers_by_facility, live_api::Vatsim, models::Observers};
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let data = api.get_v3_data().await.unwrap();
+/// for group in controllers_by_facility(&data, Observers::Exclude) {
+///     println!("{}: {}", group.name, group.callsigns.join(", "));
+/// }
+/// # }
+/// ```
+#[must_use]
+pub fn controllers_by_facility(data: &V3ResponseData, observers: Observers) -> Vec<CoverageGroup> {
+    let mut groups: BTreeMap<i64, Vec<String>> = BTreeMap::new();
+    for controller in data.controllers_filtered(observers) {
+        groups
+            .entry(controller.facility)
+            .or_default()
+            .push(controller.callsign.clone());
+    }
+    groups
+        .into_iter()
+        .map(|(facility, callsigns)| {
+            let name = data
+                .facilities
+                .iter()
+                .find(|f| i64::from(f.id) == facility)
+                .map_or_else(|| facility.to_string(), |f| f.short.clone());
+            coverage_group(name, callsigns)
+        })
+        .collect()
+}
+
+/// Group online controllers by their callsign prefix.
+///
+/// The prefix is the part of the callsign before the first underscore,
+/// which is generally the airport or FIR/ARTCC the controller is working,
+/// like "SAN" for "`SAN_TWR`" or "ZLA" for "`ZLA_33_CTR`". Groups are
+/// returned sorted by prefix.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{analysis::controllers_by_prefix, live_api::Vatsim, models::Observers};
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let data = api.get_v3_data().await.unwrap();
+/// let groups = controllers_by_prefix(&data, Observers::Exclude);
+/// # }
+/// ```
+#[must_use]
+pub fn controllers_by_prefix(data: &V3ResponseData, observers: Observers) -> Vec<CoverageGroup> {
+    let mut groups: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for controller in data.controllers_filtered(observers) {
+        let prefix = controller
+            .callsign
+            .split('_')
+            .next()
+            .unwrap_or(&controller.callsign);
+        groups
+            .entry(prefix)
+            .or_default()
+            .push(controller.callsign.clone());
+    }
+    groups
+        .into_iter()
+        .map(|(prefix, callsigns)| coverage_group(prefix.to_owned(), callsigns))
+        .collect()
+}
+
+/// Group online controllers by the boundary, like a FIR, that they're
+/// working in.
+///
+/// Controllers are placed by their callsign prefix: a prefix that's a
+/// boundary's identifier, like "EGTT" for "`EGTT_CTR`", puts them in that
+/// boundary, and otherwise the prefix is looked up as an airport, also
+/// trying it with a "K" in front for three-letter US identifiers like
+/// "SAN", and they're put in the first boundary containing the airport.
+/// Controllers that can't be placed, like those on regional approach
+/// callsigns, are left out. Groups are named by boundary identifier and
+/// returned sorted by it.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "fixtures")] {
+/// use vatsim_utils::{
+///     analysis::controllers_by_boundary,
+///     boundaries::Boundary,
+///     distance::AirportDatabase,
+///     fixtures,
+///     models::Observers,
+/// };
+///
+/// let data = fixtures::v3_data();
+/// let boundaries = [
+///     Boundary::new(
+///         "KZLA",
+///         vec![vec![vec![[-120.0, 32.0], [-116.0, 32.0], [-116.0, 36.0], [-120.0, 36.0], [-120.0, 32.0]]]],
+///     ),
+///     Boundary::new(
+///         "EGTT",
+///         vec![vec![vec![[-2.0, 50.0], [2.0, 50.0], [2.0, 53.0], [-2.0, 53.0], [-2.0, 50.0]]]],
+///     ),
+/// ];
+/// let airports = AirportDatabase::from_csv("KLAX,33.9416,-118.4085\nEGLL,51.47,-0.4543").unwrap();
+/// let groups = controllers_by_boundary(&data, Observers::Exclude, &boundaries, &airports);
+///
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[0].name, "EGTT");
+/// assert_eq!(groups[0].callsigns, ["EGLL_N_TWR"]);
+/// assert_eq!(groups[1].name, "KZLA");
+/// assert_eq!(groups[1].callsigns, ["LAX_CTR", "LAX_TWR"]);
+/// # }
+/// ```
+#[must_use]
+pub fn controllers_by_boundary(
+    data: &V3ResponseData,
+    observers: Observers,
+    boundaries: &[Boundary],
+    airports: &AirportDatabase,
+) -> Vec<CoverageGroup> {
+    let mut groups: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for controller in data.controllers_filtered(observers) {
+        let prefix = controller
+            .callsign
+            .split('_')
+            .next()
+            .unwrap_or(&controller.callsign);
+        if let Some(boundary) = boundary_for_prefix(prefix, boundaries, airports) {
+            groups
+                .entry(&boundary.id)
+                .or_default()
+                .push(controller.callsign.clone());
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(id, callsigns)| coverage_group(id.to_owned(), callsigns))
+        .collect()
+}
+
+/// The boundary a callsign prefix belongs to, by identifier or by the
+/// location of the airport it names.
+fn boundary_for_prefix<'a>(
+    prefix: &str,
+    boundaries: &'a [Boundary],
+    airports: &AirportDatabase,
+) -> Option<&'a Boundary> {
+    if let Some(boundary) = boundaries
+        .iter()
+        .find(|boundary| boundary.id.eq_ignore_ascii_case(prefix))
+    {
+        return Some(boundary);
+    }
+    let prefix = prefix.to_uppercase();
+    let (lat, lon) = airports.location(&prefix).or_else(|| {
+        (prefix.len() == 3)
+            .then(|| airports.location(&format!("K{prefix}")))
+            .flatten()
+    })?;
+    boundaries
+        .iter()
+        .find(|boundary| boundary.contains(lat, lon))
+}
+
+/// Build a group, sorting its callsigns.
+fn coverage_group(name: String, mut callsigns: Vec<String>) -> CoverageGroup {
+    callsigns.sort();
+    CoverageGroup { name, callsigns }
+}
+
+/// A single cell of a [`traffic_grid`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct GridCell {
+    /// Latitude of the cell's southern edge.
+    pub min_latitude: f64,
+    /// Longitude of the cell's western edge.
+    pub min_longitude: f64,
+    /// Width and height of the cell, in degrees.
+    pub size: f64,
+    /// Number of pilots in the cell.
+    pub count: usize,
+}
+
+impl GridCell {
+    /// The cell as a `GeoJSON` polygon with `count` and `intensity`
+    /// properties, where intensity is the count relative to `max_count`.
+    #[must_use]
+    pub fn to_feature(&self, max_count: usize) -> Feature {
+        let (s, w) = (self.min_latitude, self.min_longitude);
+        let (n, e) = (s + self.size, w + self.size);
+        #[allow(clippy::cast_precision_loss)]
+        let intensity = if max_count == 0 {
+            0.0
+        } else {
+            self.count as f64 / max_count as f64
+        };
+        Feature::new(Geometry::Polygon(vec![vec![
+            [w, s],
+            [e, s],
+            [e, n],
+            [w, n],
+            [w, s],
+        ]]))
+        .property("count", self.count)
+        .property("intensity", intensity)
+    }
+}
+
+/// Bin pilot positions into a latitude/longitude grid.
+///
+/// Cells are `cell_size` degrees square, aligned to 0°/0°. Only cells
+/// containing pilots are returned, sorted busiest-first. Pilots with
+/// impossible coordinates are skipped, and no cells are returned if
+/// the cell size isn't a positive number.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{analysis::{traffic_grid, traffic_grid_geojson}, live_api::Vatsim};
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let data = api.get_v3_data().await.unwrap();
+/// let cells = traffic_grid(&data, 1.0);
+/// let geojson = serde_json::to_string(&traffic_grid_geojson(&cells)).unwrap();
+/// # }
+/// ```
+#[must_use]
+pub fn traffic_grid(data: &V3ResponseData, cell_size: f64) -> Vec<GridCell> {
+    if !(cell_size.is_finite() && cell_size > 0.0) {
+        return Vec::new();
+    }
+    let mut counts: HashMap<(i64, i64), usize> = HashMap::new();
+    for pilot in &data.pilots {
+        if !(pilot.latitude.abs() <= 90.0 && pilot.longitude.abs() <= 180.0) {
+            continue;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        let key = (
+            (pilot.latitude / cell_size).floor() as i64,
+            (pilot.longitude / cell_size).floor() as i64,
+        );
+        *counts.entry(key).or_default() += 1;
+    }
+    let mut cells: Vec<_> = counts
+        .into_iter()
+        .map(|((lat, lon), count)| {
+            #[allow(clippy::cast_precision_loss)]
+            GridCell {
+                min_latitude: lat as f64 * cell_size,
+                min_longitude: lon as f64 * cell_size,
+                size: cell_size,
+                count,
+            }
+        })
+        .collect();
+    cells.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.min_latitude.total_cmp(&b.min_latitude))
+            .then_with(|| a.min_longitude.total_cmp(&b.min_longitude))
+    });
+    cells
+}
+
+/// Convert grid cells into a `GeoJSON` collection for heatmap overlays.
+///
+/// See [`GridCell::to_feature`] for the properties included.
+#[must_use]
+pub fn traffic_grid_geojson(cells: &[GridCell]) -> FeatureCollection {
+    let max_count = cells
+        .iter()
+        .map(|cell| cell.count)
+        .max()
+        .unwrap_or_default();
+    FeatureCollection {
+        features: cells
+            .iter()
+            .map(|cell| cell.to_feature(max_count))
+            .collect(),
+    }
+}
+
+/// Build approximate coverage circles for each online controller.
+///
+/// Controller positions come from the transceivers data, as the V3 data
+/// doesn't include them; the position of a controller with multiple
+/// transceivers is the average of them. Controllers without transceivers
+/// or without a visual range are skipped. Each circle has `callsign`,
+/// `frequency`, and `visual_range` properties.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{analysis::controller_range_circles, live_api::Vatsim, models::Observers};
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let data = api.get_v3_data().await.unwrap();
+/// let transceivers = api.get_transceivers_data().await.unwrap();
+/// let circles = controller_range_circles(&data, &transceivers, Observers::Exclude, 64);
+/// # }
+/// ```
+#[must_use]
+pub fn controller_range_circles(
+    data: &V3ResponseData,
+    transceivers: &[crate::models::TransceiverResponseEntry],
+    observers: Observers,
+    points: usize,
+) -> FeatureCollection {
+    use crate::geojson::circle;
+
+    let positions: HashMap<&str, (f64, f64)> = transceivers
+        .iter()
+        .filter(|entry| !entry.transceivers.is_empty())
+        .map(|entry| {
+            #[allow(clippy::cast_precision_loss)]
+            let count = entry.transceivers.len() as f64;
+            let lat = entry.transceivers.iter().map(|t| t.lat_deg).sum::<f64>() / count;
+            let lon = entry.transceivers.iter().map(|t| t.lon_deg).sum::<f64>() / count;
+            (entry.callsign.as_str(), (lat, lon))
+        })
+        .collect();
+    let features = data
+        .controllers_filtered(observers)
+        .filter(|controller| controller.visual_range > 0)
+        .filter_map(|controller| {
+            let (lat, lon) = positions.get(controller.callsign.as_str())?;
+            #[allow(clippy::cast_precision_loss)]
+            let radius = controller.visual_range as f64;
+            Some(
+                Feature::new(circle(*lat, *lon, radius, points))
+                    .property("callsign", controller.callsign.as_str())
+                    .property("frequency", controller.frequency.as_str())
+                    .property("visual_range", controller.visual_range),
+            )
+        })
+        .collect();
+    FeatureCollection { features }
+}
+
+/// Connected clients on a single FSD server.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ServerLoad {
+    /// Server identifier, like "USA-EAST".
+    pub ident: String,
+    /// Server name, if the server is in the snapshot's server list.
+    pub name: Option<String>,
+    /// Server location, if the server is in the snapshot's server list.
+    pub location: Option<String>,
+    /// Whether the server is a sweatbox (training) server.
+    pub is_sweatbox: bool,
+    /// Number of connected pilots.
+    pub pilots: usize,
+    /// Number of connected controllers.
+    pub controllers: usize,
+    /// Number of connected ATIS stations.
+    pub atis: usize,
+}
+
+impl ServerLoad {
+    /// Total number of connected clients.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.pilots + self.controllers + self.atis
+    }
+}
+
+/// Summarize the connected clients on each server.
+///
+/// Every server in the snapshot's server list is included, even with no
+/// clients, as are any servers that clients are connected to but that
+/// aren't in the list. Results are sorted by most clients first.
+///
+/// To look at only live or only sweatbox servers, filter the results on
+/// [`ServerLoad::is_sweatbox`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{analysis::server_load, live_api::Vatsim, models::Observers};
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let data = api.get_v3_data().await.unwrap();
+/// for server in server_load(&data, Observers::Include).iter().filter(|s| !s.is_sweatbox) {
+///     println!("{}: {}", server.ident, server.total());
+/// }
+/// # }
+/// ```
+#[must_use]
+pub fn server_load(data: &V3ResponseData, observers: Observers) -> Vec<ServerLoad> {
+    let mut servers: BTreeMap<String, ServerLoad> = data
+        .servers
+        .iter()
+        .map(|server| {
+            (
+                server.ident.clone(),
+                ServerLoad {
+                    ident: server.ident.clone(),
+                    name: Some(server.name.clone()),
+                    location: Some(server.location.clone()),
+                    is_sweatbox: server.is_sweatbox,
+                    pilots: 0,
+                    controllers: 0,
+                    atis: 0,
+                },
+            )
+        })
+        .collect();
+    for pilot in &data.pilots {
+        server_entry(&mut servers, &pilot.server).pilots += 1;
+    }
+    for controller in data.controllers_filtered(observers) {
+        server_entry(&mut servers, &controller.server).controllers += 1;
+    }
+    for atis in &data.atis {
+        server_entry(&mut servers, &atis.server).atis += 1;
+    }
+    let mut servers: Vec<_> = servers.into_values().collect();
+    servers.sort_by(|a, b| {
+        b.total()
+            .cmp(&a.total())
+            .then_with(|| a.ident.cmp(&b.ident))
+    });
+    servers
+}
+
+/// Get a server's entry, adding an empty one if it doesn't exist.
+fn server_entry<'a>(
+    servers: &'a mut BTreeMap<String, ServerLoad>,
+    ident: &str,
+) -> &'a mut ServerLoad {
+    servers
+        .entry(ident.to_owned())
+        .or_insert_with(|| ServerLoad {
+            ident: ident.to_owned(),
+            name: None,
+            location: None,
+            is_sweatbox: false,
+            pilots: 0,
+            controllers: 0,
+            atis: 0,
+        })
+}
+
+/// A single connection to the network in a snapshot.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(tag = "kind", content = "connection", rename_all = "snake_case")]
+pub enum Connection<'a> {
+    /// A pilot.
+    Pilot(&'a Pilot),
+    /// A controller or observer.
+    Controller(&'a Controller),
+    /// An ATIS station.
+    Atis(&'a Atis),
+}
+
+impl Connection<'_> {
+    /// CID of the user making the connection.
+    #[must_use]
+    pub fn cid(&self) -> u64 {
+        match self {
+            Self::Pilot(pilot) => pilot.cid,
+            Self::Controller(controller) => controller.cid,
+            Self::Atis(atis) => atis.cid,
+        }
+    }
+
+    /// Callsign the connection is using.
+    #[must_use]
+    pub fn callsign(&self) -> &str {
+        match self {
+            Self::Pilot(pilot) => &pilot.callsign,
+            Self::Controller(controller) => &controller.callsign,
+            Self::Atis(atis) => &atis.callsign,
+        }
+    }
+
+    /// Server the connection is on.
+    #[must_use]
+    pub fn server(&self) -> &str {
+        match self {
+            Self::Pilot(pilot) => &pilot.server,
+            Self::Controller(controller) => &controller.server,
+            Self::Atis(atis) => &atis.server,
+        }
+    }
+
+    /// When the connection was made.
+    #[must_use]
+    pub fn logon_time(&self) -> &str {
+        match self {
+            Self::Pilot(pilot) => &pilot.logon_time,
+            Self::Controller(controller) => &controller.logon_time,
+            Self::Atis(atis) => &atis.logon_time,
+        }
+    }
+}
+
+/// A CID with more than one simultaneous connection.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateConnections<'a> {
+    /// The user's CID.
+    pub cid: u64,
+    /// The user's connections, pilots first, then controllers, then
+    /// ATIS stations.
+    pub connections: Vec<Connection<'a>>,
+}
+
+impl DuplicateConnections<'_> {
+    /// Whether the connections are a single controller and their ATIS
+    /// stations, which controllers are allowed to run alongside their
+    /// position.
+    #[must_use]
+    pub fn is_controller_with_atis(&self) -> bool {
+        let controllers = self
+            .connections
+            .iter()
+            .filter(|c| matches!(c, Connection::Controller(_)))
+            .count();
+        controllers == 1
+            && self
+                .connections
+                .iter()
+                .all(|c| !matches!(c, Connection::Pilot(_)))
+    }
+}
+
+/// Find CIDs connected more than once across the pilots, controllers, and
+/// ATIS stations, like shared accounts or ghost connections that didn't
+/// drop.
+///
+/// A controller running their own ATIS counts as a duplicate; skip those
+/// with [`DuplicateConnections::is_controller_with_atis`]. Results are
+/// sorted by CID.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{analysis::duplicate_connections, live_api::Vatsim};
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let data = api.get_v3_data().await.unwrap();
+/// for duplicate in duplicate_connections(&data)
+///     .iter()
+///     .filter(|d| !d.is_controller_with_atis())
+/// {
+///     let callsigns: Vec<_> = duplicate.connections.iter().map(|c| c.callsign()).collect();
+///     println!("{}: {}", duplicate.cid, callsigns.join(", "));
+/// }
+/// # }
+/// ```
+#[must_use]
+pub fn duplicate_connections(data: &V3ResponseData) -> Vec<DuplicateConnections<'_>> {
+    let connections = data
+        .pilots
+        .iter()
+        .map(Connection::Pilot)
+        .chain(data.controllers.iter().map(Connection::Controller))
+        .chain(data.atis.iter().map(Connection::Atis));
+    let mut by_cid: BTreeMap<u64, Vec<Connection<'_>>> = BTreeMap::new();
+    for connection in connections {
+        by_cid.entry(connection.cid()).or_default().push(connection);
+    }
+    by_cid
+        .into_iter()
+        .filter(|(_, connections)| connections.len() > 1)
+        .map(|(cid, connections)| DuplicateConnections { cid, connections })
+        .collect()
+}
+
+/// Two connections by different users with the same or similar callsigns.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CallsignConflict<'a> {
+    /// The connection with the alphabetically-first callsign.
+    pub first: Connection<'a>,
+    /// The other connection.
+    pub second: Connection<'a>,
+    /// Number of single-character edits, or swaps of neighbouring
+    /// characters, between the callsigns, ignoring case; 0 for exact
+    /// duplicates.
+    pub distance: usize,
+}
+
+/// Find pilots and controllers with different CIDs using the same
+/// callsign, or callsigns within `max_distance` single-character edits
+/// of each other, ignoring case. Edits are insertions, deletions,
+/// substitutions, and swaps of two neighbouring characters
+/// ([optimal string alignment distance]).
+///
+/// A `max_distance` of 0 finds only exact duplicates; 1 also catches
+/// callsigns like "AAL123" and "AAL132", or "AAL123" and "AAL124", that
+/// are easy to confuse on frequency. ATIS stations aren't included.
+/// Results are sorted by distance, then callsign.
+///
+/// [optimal string alignment distance]: https://en.wikipedia.org/wiki/Damerau%E2%80%93Levenshtein_distance#Optimal_string_alignment_distance
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{analysis::callsign_conflicts, live_api::Vatsim, models::Observers};
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let data = api.get_v3_data().await.unwrap();
+/// for conflict in callsign_conflicts(&data, Observers::Exclude, 1) {
+///     println!(
+///         "{} and {}",
+///         conflict.first.callsign(),
+///         conflict.second.callsign()
+///     );
+/// }
+/// # }
+/// ```
+///
+/// Transposed digits are a single edit:
+///
+/// ```rust
+/// # #[cfg(feature = "fixtures")] {
+/// use vatsim_utils::{analysis::callsign_conflicts, fixtures, models::Observers};
+///
+/// let mut data = fixtures::v3_data();
+/// data.pilots[0].callsign = String::from("AAL123");
+/// data.pilots[1].callsign = String::from("AAL132");
+///
+/// let conflicts = callsign_conflicts(&data, Observers::Exclude, 1);
+/// assert_eq!(conflicts.len(), 1);
+/// assert_eq!(conflicts[0].first.callsign(), "AAL123");
+/// assert_eq!(conflicts[0].second.callsign(), "AAL132");
+/// assert_eq!(conflicts[0].distance, 1);
+/// # }
+/// ```
+#[must_use]
+pub fn callsign_conflicts(
+    data: &V3ResponseData,
+    observers: Observers,
+    max_distance: usize,
+) -> Vec<CallsignConflict<'_>> {
+    let mut connections: Vec<(String, Connection<'_>)> = data
+        .pilots
+        .iter()
+        .map(Connection::Pilot)
+        .chain(
+            data.controllers_filtered(observers)
+                .map(Connection::Controller),
+        )
+        .map(|c| (c.callsign().to_uppercase(), c))
+        .collect();
+    connections.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut conflicts = Vec::new();
+    for (index, (callsign, connection)) in connections.iter().enumerate() {
+        for (other_callsign, other) in &connections[index + 1..] {
+            if connection.cid() == other.cid()
+                || callsign.len().abs_diff(other_callsign.len()) > max_distance
+            {
+                continue;
+            }
+            let distance = edit_distance(callsign, other_callsign);
+            if distance <= max_distance {
+                conflicts.push(CallsignConflict {
+                    first: *connection,
+                    second: *other,
+                    distance,
+                });
+            }
+        }
+    }
+    conflicts.sort_by(|a, b| {
+        a.distance
+            .cmp(&b.distance)
+            .then_with(|| a.first.callsign().cmp(b.first.callsign()))
+    });
+    conflicts
+}
+
+/// How close two aircraft can be before they're in conflict.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Separation {
+    /// Lateral distance, in nautical miles. Defaults to 5, the usual
+    /// radar separation en route.
+    pub lateral_nm: f64,
+    /// Vertical distance, in feet. Defaults to 1,000.
+    pub vertical_ft: i64,
+}
+
+impl Default for Separation {
+    fn default() -> Self {
+        Self {
+            lateral_nm: 5.0,
+            vertical_ft: 1000,
+        }
+    }
+}
+
+impl Separation {
+    /// Set the lateral separation, in nautical miles.
+    #[must_use]
+    pub fn lateral_nm(mut self, lateral_nm: f64) -> Self {
+        self.lateral_nm = lateral_nm;
+        self
+    }
+
+    /// Set the vertical separation, in feet.
+    #[must_use]
+    pub fn vertical_ft(mut self, vertical_ft: i64) -> Self {
+        self.vertical_ft = vertical_ft;
+        self
+    }
+}
+
+/// Two airborne aircraft closer than the [separation](Separation) both
+/// laterally and vertically.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ProximityConflict<'a> {
+    /// The pilot with the alphabetically-first callsign.
+    pub first: &'a Pilot,
+    /// The other pilot.
+    pub second: &'a Pilot,
+    /// Lateral distance between them, in nautical miles.
+    pub lateral_nm: f64,
+    /// Vertical distance between them, in feet.
+    pub vertical_ft: i64,
+}
+
+/// Find pairs of airborne aircraft closer than the separation, both
+/// laterally and vertically.
+///
+/// Pilots are airborne if their groundspeed is over 50 knots. Altitudes
+/// are as reported, without correcting for the altimeter setting, so
+/// close calls near transition levels are approximate. Results are sorted
+/// closest-first.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "fixtures")] {
+/// use vatsim_utils::{analysis::{proximity_conflicts, Separation}, fixtures};
+///
+/// let mut data = fixtures::v3_data();
+/// data.pilots.truncate(2);
+/// for pilot in &mut data.pilots {
+///     pilot.groundspeed = 250;
+///     pilot.altitude = 10000;
+/// }
+/// data.pilots[1].latitude = data.pilots[0].latitude + 0.05;
+/// data.pilots[1].longitude = data.pilots[0].longitude;
+/// data.pilots[1].altitude = 10500;
+///
+/// let conflicts = proximity_conflicts(&data, Separation::default());
+/// assert_eq!(conflicts.len(), 1);
+/// assert_eq!(conflicts[0].vertical_ft, 500);
+/// assert!(proximity_conflicts(&data, Separation::default().vertical_ft(500)).is_empty());
+/// # }
+/// ```
+#[must_use]
+pub fn proximity_conflicts(
+    data: &V3ResponseData,
+    separation: Separation,
+) -> Vec<ProximityConflict<'_>> {
+    let index = PilotIndex::new(&data.pilots);
+    let airborne = |pilot: &Pilot| pilot.groundspeed > AIRBORNE_GROUNDSPEED_KTS;
+    let mut conflicts = Vec::new();
+    for pilot in index.pilots().filter(|p| airborne(p)) {
+        for other in index.within(pilot.latitude, pilot.longitude, separation.lateral_nm) {
+            let vertical_ft = pilot.altitude.abs_diff(other.altitude);
+            if !airborne(other)
+                || (pilot.callsign.as_str(), pilot.cid) >= (other.callsign.as_str(), other.cid)
+                || vertical_ft >= separation.vertical_ft.unsigned_abs()
+            {
+                continue;
+            }
+            conflicts.push(ProximityConflict {
+                first: pilot,
+                second: other,
+                lateral_nm: distance_nm(
+                    pilot.latitude,
+                    pilot.longitude,
+                    other.latitude,
+                    other.longitude,
+                ),
+                vertical_ft: i64::try_from(vertical_ft).unwrap_or(i64::MAX),
+            });
+        }
+    }
+    conflicts.sort_by(|a, b| a.lateral_nm.total_cmp(&b.lateral_nm));
+    conflicts
+}
+
+/// The area a controller position is responsible for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sector {
+    /// Start of the callsigns of the positions that work the area, like
+    /// "ZLA" for all of Los Angeles Center, or "`ZLA_33`" for one split.
+    pub callsign_prefix: String,
+    /// The area.
+    pub area: Geofence,
+}
+
+impl Sector {
+    /// Create a new sector.
+    #[must_use]
+    pub fn new(callsign_prefix: &str, area: Geofence) -> Self {
+        Self {
+            callsign_prefix: callsign_prefix.to_owned(),
+            area,
+        }
+    }
+
+    /// Whether the controller works this sector: their callsign is the
+    /// prefix followed by an underscore, ignoring case.
+    fn worked_by(&self, controller: &Controller) -> bool {
+        let prefix_len = self.callsign_prefix.len();
+        controller.callsign.len() > prefix_len
+            && controller.callsign.is_char_boundary(prefix_len)
+            && controller.callsign[..prefix_len].eq_ignore_ascii_case(&self.callsign_prefix)
+            && controller.callsign[prefix_len..].starts_with('_')
+    }
+}
+
+/// The aircraft an online controller is responsible for.
+#[derive(Debug, Clone, Serialize)]
+pub struct ControllerLoad<'a> {
+    /// The controller.
+    pub controller: &'a Controller,
+    /// Pilots in the controller's sectors that no more specific position
+    /// is responsible for.
+    pub pilots: Vec<&'a Pilot>,
+}
+
+impl ControllerLoad<'_> {
+    /// Number of aircraft the controller is responsible for.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.pilots.len()
+    }
+}
+
+/// Estimate how many aircraft each online controller is responsible for.
+///
+/// Each pilot is assigned to one controller, following top-down rules:
+/// of the controllers working a [sector](Sector) the pilot is inside, the
+/// most specific facility type wins, from delivery, ground, tower, and
+/// approach up to center, then flight service. Delivery and ground only
+/// take pilots on the ground. Among positions of the same type, the one
+/// with the longest matching callsign prefix wins, so a split takes
+/// traffic from its parent sector.
+///
+/// Every online controller apart from observers is included, including
+/// those with no traffic, sorted busiest-first. Pilots not in any
+/// staffed sector aren't counted.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "fixtures")] {
+/// use vatsim_utils::{
+///     analysis::{sector_load, Sector},
+///     boundaries::Geofence,
+///     fixtures,
+/// };
+///
+/// let mut data = fixtures::v3_data();
+/// data.controllers.truncate(1);
+/// data.controllers[0].callsign = String::from("SAN_APP");
+/// data.controllers[0].facility = 5;
+/// let pilot = &data.pilots[0];
+/// let sectors = [Sector::new(
+///     "SAN",
+///     Geofence::Radius { lat: pilot.latitude, lon: pilot.longitude, nm: 40.0 },
+/// )];
+///
+/// let load = sector_load(&data, &sectors);
+/// assert_eq!(load[0].controller.callsign, "SAN_APP");
+/// assert!(load[0].count() >= 1);
+/// # }
+/// ```
+#[must_use]
+pub fn sector_load<'a>(data: &'a V3ResponseData, sectors: &[Sector]) -> Vec<ControllerLoad<'a>> {
+    let mut loads: Vec<ControllerLoad<'a>> = data
+        .controllers_filtered(Observers::Exclude)
+        .map(|controller| ControllerLoad {
+            controller,
+            pilots: Vec::new(),
+        })
+        .collect();
+    let index = PilotIndex::new(&data.pilots);
+    // the position each pilot is assigned to, by index into `loads`
+    let mut assigned: HashMap<(u64, &str), (TopDownPriority<'_>, usize)> = HashMap::new();
+    for sector in sectors {
+        let workers: Vec<usize> = (0..loads.len())
+            .filter(|&i| sector.worked_by(loads[i].controller))
+            .collect();
+        if workers.is_empty() {
+            continue;
+        }
+        let pilots: Vec<&Pilot> = match &sector.area {
+            Geofence::Radius { lat, lon, nm } => index.within(*lat, *lon, *nm),
+            area => index.pilots().filter(|p| area.contains_pilot(p)).collect(),
+        };
+        for pilot in pilots {
+            let on_ground = pilot.groundspeed <= AIRBORNE_GROUNDSPEED_KTS;
+            for &worker in &workers {
+                let controller = loads[worker].controller;
+                let Some(rank) = top_down_rank(controller.facility, on_ground) else {
+                    continue;
+                };
+                let priority = (
+                    rank,
+                    Reverse(sector.callsign_prefix.len()),
+                    controller.callsign.as_str(),
+                );
+                let _ = assigned
+                    .entry((pilot.cid, pilot.callsign.as_str()))
+                    .and_modify(|current| {
+                        if priority < current.0 {
+                            *current = (priority, worker);
+                        }
+                    })
+                    .or_insert((priority, worker));
+            }
+        }
+    }
+    for pilot in &data.pilots {
+        if let Some((_, worker)) = assigned.get(&(pilot.cid, pilot.callsign.as_str())) {
+            loads[*worker].pilots.push(pilot);
+        }
+    }
+    loads.sort_by(|a, b| {
+        b.count()
+            .cmp(&a.count())
+            .then_with(|| a.controller.callsign.cmp(&b.controller.callsign))
+    });
+    loads
+}
+
+/// Order in which positions take a pilot, lowest first: the top-down
+/// rank, then the longest sector prefix, then the callsign.
+type TopDownPriority<'a> = (u8, Reverse<usize>, &'a str);
+
+/// Pilots and controllers in one [sector](Sector).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SectorStaffing {
+    /// The sector's callsign prefix.
+    pub name: String,
+    /// Number of pilots inside the sector.
+    pub pilots: usize,
+    /// Number of controllers working the sector.
+    pub controllers: usize,
+}
+
+impl SectorStaffing {
+    /// Pilots per controller, or `None` if no one is working the sector.
+    #[must_use]
+    pub fn pilots_per_controller(&self) -> Option<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        (self.controllers > 0).then(|| self.pilots as f64 / self.controllers as f64)
+    }
+}
+
+/// Count the pilots inside each sector and the controllers working it,
+/// to find the areas that most need controllers.
+///
+/// Sectors can be whole FIRs from boundary data, or anything else with a
+/// callsign prefix; see [`Sector`] for how controllers are matched.
+/// Observers aren't counted. Results are sorted neediest-first: sectors
+/// with pilots and no controllers, by number of pilots, then by pilots
+/// per controller.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "fixtures")] {
+/// use vatsim_utils::{
+///     analysis::{sector_staffing, Sector},
+///     boundaries::Geofence,
+///     fixtures,
+/// };
+///
+/// let data = fixtures::v3_data();
+/// let everywhere = Geofence::BoundingBox {
+///     min_lat: -90.0,
+///     min_lon: -180.0,
+///     max_lat: 90.0,
+///     max_lon: 180.0,
+/// };
+/// let staffing = sector_staffing(&data, &[Sector::new("NOWHERE", everywhere)]);
+///
+/// assert_eq!(staffing[0].pilots, data.pilots.len());
+/// assert_eq!(staffing[0].controllers, 0);
+/// assert_eq!(staffing[0].pilots_per_controller(), None);
+/// # }
+/// ```
+#[must_use]
+pub fn sector_staffing(data: &V3ResponseData, sectors: &[Sector]) -> Vec<SectorStaffing> {
+    let index = PilotIndex::new(&data.pilots);
+    let mut staffing: Vec<SectorStaffing> = sectors
+        .iter()
+        .map(|sector| SectorStaffing {
+            name: sector.callsign_prefix.clone(),
+            pilots: match &sector.area {
+                Geofence::Radius { lat, lon, nm } => index.within(*lat, *lon, *nm).len(),
+                area => index.pilots().filter(|p| area.contains_pilot(p)).count(),
+            },
+            controllers: data
+                .controllers_filtered(Observers::Exclude)
+                .filter(|c| sector.worked_by(c))
+                .count(),
+        })
+        .collect();
+    staffing.sort_by(|a, b| {
+        let unstaffed = |s: &SectorStaffing| s.controllers == 0 && s.pilots > 0;
+        unstaffed(b)
+            .cmp(&unstaffed(a))
+            .then_with(
+                || match (a.pilots_per_controller(), b.pilots_per_controller()) {
+                    (Some(a), Some(b)) => b.total_cmp(&a),
+                    _ => b.pilots.cmp(&a.pilots),
+                },
+            )
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    staffing
+}
+
+/// Where a facility type comes in the top-down order, lowest first, or
+/// `None` if it doesn't take the pilot.
+fn top_down_rank(facility: i64, on_ground: bool) -> Option<u8> {
+    match facility {
+        2 if on_ground => Some(0),
+        3 if on_ground => Some(1),
+        4 => Some(2),
+        5 => Some(3),
+        6 => Some(4),
+        1 => Some(5),
+        _ => None,
+    }
+}
+
+/// Number of single-character insertions, deletions, substitutions, and
+/// swaps of neighbouring characters to turn one string into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut before_previous = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            let mut distance = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && *a_char == b[j - 1] && a[i - 1] == *b_char {
+                distance = distance.min(before_previous[j - 1] + 1);
+            }
+            current[j + 1] = distance;
+        }
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,187 @@
+//! `vatsim` - a command-line tool for looking at VATSIM data, built on
+//! the `vatsim_utils` library.
+//!
+//! Requires the `cli` feature: `cargo install vatsim_utils --features cli`.
+
+#![deny(clippy::all, clippy::pedantic, unused_results)]
+
+use clap::{Parser, Subcommand};
+use std::{process::ExitCode, time::Duration};
+use vatsim_utils::{
+    diff::{diff, NetworkEvent},
+    errors::VatsimUtilError,
+    live_api::{PollInterval, Vatsim},
+};
+
+/// Look at live VATSIM data.
+#[derive(Debug, Parser)]
+#[command(name = "vatsim", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Show whether a user is connected, and as what.
+    Online {
+        /// The user's CID.
+        cid: u64,
+    },
+    /// Show the current METAR for an airport.
+    Metar {
+        /// The airport's ICAO code, like "KSAN".
+        icao: String,
+    },
+    /// List the connected controllers.
+    Controllers {
+        /// Only list controllers whose callsigns start with this, like "SAN_".
+        prefix: Option<String>,
+    },
+    /// Print connections, disconnections, and ATIS changes as they happen.
+    Watch {
+        /// Seconds between checks; defaults to the feed's advertised
+        /// reload interval.
+        #[arg(short, long)]
+        interval: Option<u64>,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse().command).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run the command.
+async fn run(command: Command) -> Result<ExitCode, VatsimUtilError> {
+    match command {
+        Command::Online { cid } => online(cid).await,
+        Command::Metar { icao } => metar(&icao).await,
+        Command::Controllers { prefix } => controllers(prefix.as_deref()).await,
+        Command::Watch { interval } => {
+            let interval = interval.map(|secs| Duration::from_secs(secs.max(1)));
+            watch(interval).await
+        }
+    }
+}
+
+/// Show whether a user is connected, exiting with failure if not.
+async fn online(cid: u64) -> Result<ExitCode, VatsimUtilError> {
+    let data = Vatsim::new().await?.get_v3_data().await?;
+    if let Some(pilot) = data.pilots.iter().find(|pilot| pilot.cid == cid) {
+        let route = match (pilot.departure(), pilot.arrival()) {
+            (Some(departure), Some(arrival)) => format!(" {departure} -> {arrival}"),
+            _ => String::new(),
+        };
+        println!(
+            "{cid} is flying as {}{route}, at {} ft and {} kts",
+            pilot.callsign, pilot.altitude, pilot.groundspeed
+        );
+        return Ok(ExitCode::SUCCESS);
+    }
+    if let Some(controller) = data.controllers.iter().find(|c| c.cid == cid) {
+        println!(
+            "{cid} is controlling as {} on {}",
+            controller.callsign, controller.frequency
+        );
+        return Ok(ExitCode::SUCCESS);
+    }
+    println!("{cid} is not online");
+    Ok(ExitCode::FAILURE)
+}
+
+/// Show the METAR for an airport.
+async fn metar(icao: &str) -> Result<ExitCode, VatsimUtilError> {
+    let Some(text) = Vatsim::new().await?.get_metar(icao).await? else {
+        println!("No METAR for {icao}");
+        return Ok(ExitCode::FAILURE);
+    };
+    println!("{text}");
+    Ok(ExitCode::SUCCESS)
+}
+
+/// List the connected controllers.
+async fn controllers(prefix: Option<&str>) -> Result<ExitCode, VatsimUtilError> {
+    let data = Vatsim::new().await?.get_v3_data().await?;
+    let prefix = prefix.unwrap_or_default().to_uppercase();
+    for controller in data
+        .controllers
+        .iter()
+        .filter(|c| c.callsign.to_uppercase().starts_with(&prefix))
+    {
+        let rating = data
+            .controller_rating(controller)
+            .map(|rating| rating.short.as_str())
+            .unwrap_or_default();
+        println!(
+            "{:<14} {:<8} {:<4} {}",
+            controller.callsign, controller.frequency, rating, controller.name
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Print changes between snapshots until interrupted, checking at the
+/// interval if one is given, or else as often as the feed advertises.
+async fn watch(interval: Option<Duration>) -> Result<ExitCode, VatsimUtilError> {
+    let api = Vatsim::new().await?;
+    let poll = PollInterval::default();
+    let mut previous = api.get_v3_data().await?;
+    loop {
+        let delay = interval.unwrap_or_else(|| poll.next_delay(&previous.general));
+        tokio::time::sleep(delay).await;
+        let current = match api.get_v3_data().await {
+            Ok(current) => current,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                continue;
+            }
+        };
+        for event in diff(&previous, &current) {
+            println!("{}", describe(&event));
+        }
+        previous = current;
+    }
+}
+
+/// A line describing the event.
+fn describe(event: &NetworkEvent) -> String {
+    match event {
+        NetworkEvent::PilotConnected(pilot) => {
+            format!("+ pilot      {} ({})", pilot.callsign, pilot.cid)
+        }
+        NetworkEvent::PilotDisconnected(pilot) => {
+            format!("- pilot      {} ({})", pilot.callsign, pilot.cid)
+        }
+        NetworkEvent::PilotUpdated { pilot, .. } => format!(
+            "~ pilot      {} at {} ft, {} kts",
+            pilot.callsign, pilot.altitude, pilot.groundspeed
+        ),
+        NetworkEvent::PilotEntered(pilot) => {
+            format!("> pilot      {} ({}) entered", pilot.callsign, pilot.cid)
+        }
+        NetworkEvent::PilotLeft(pilot) => {
+            format!("< pilot      {} ({}) left", pilot.callsign, pilot.cid)
+        }
+        NetworkEvent::ControllerConnected(controller) => format!(
+            "+ controller {} ({}) on {}",
+            controller.callsign, controller.cid, controller.frequency
+        ),
+        NetworkEvent::ControllerDisconnected(controller) => {
+            format!("- controller {} ({})", controller.callsign, controller.cid)
+        }
+        NetworkEvent::AtisLetterChanged { airport, atis, .. } => format!(
+            "~ atis       {airport} is now information {}",
+            atis.atis_code.as_deref().unwrap_or("?")
+        ),
+        NetworkEvent::AtisTextChanged { airport, .. } => {
+            format!("~ atis       {airport} text changed")
+        }
+    }
+}
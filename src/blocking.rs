@@ -0,0 +1,408 @@
+//! Blocking versions of the REST API functions and live data client.
+//!
+//! These make requests with [ureq] on the calling thread, so don't need an
+//! async runtime; they suit small command-line tools and scripts. Requests
+//! use the same [endpoints](crate::urls), errors, and
+//! [error hook](crate::errors::set_error_hook) as the async functions.
+//!
+//! Requires the `sync-ureq` feature. The async functions are in the
+//! default `async` feature, so applications that only use these can turn
+//! the default features off and build without reqwest and its async stack.
+//!
+//! [ureq]: https://docs.rs/ureq/latest/ureq/
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::blocking::{self, Vatsim};
+//!
+//! let api = Vatsim::new().unwrap();
+//! let data = api.get_v3_data().unwrap();
+//! let ratings = blocking::user_ratings(1234567890).unwrap();
+//! ```
+
+use crate::{
+    budget, config,
+    errors::{self, BatchResults, Endpoint, ErrorContext, Method, RequestId, VatsimUtilError},
+    http, memo,
+    models::{
+        AtcSessionEntry, ConnectionEntry, Division, Facility, PaginatedResponse, RatingsTimeData,
+        Region, RestFlightPlans, Server, Status, TransceiverResponseEntry, UserRatingsSimple,
+        V3ResponseData,
+    },
+    urls,
+};
+use log::debug;
+use rand::seq::SliceRandom;
+use serde::de::DeserializeOwned;
+use std::{io::Read, sync::LazyLock, time::Instant};
+use ureq::Agent;
+
+/// HTTP agent.
+static AGENT: LazyLock<Agent> = LazyLock::new(config::build_agent);
+
+/// Make a `GET` request and parse the response's body as JSON.
+///
+/// Errors are passed to the error hook before being returned.
+fn fetch_json<T: DeserializeOwned>(
+    endpoint: Endpoint,
+    url: &str,
+    query: &[(&str, String)],
+) -> Result<T, VatsimUtilError> {
+    let mut request = AGENT.get(url);
+    if let Some(encoding) = config::blocking_accept_encoding() {
+        request = request.set("Accept-Encoding", encoding);
+    }
+    if let Some(key) = config::api_key_for(request.url()) {
+        request = request.set("X-API-Key", &key);
+    }
+    for (key, value) in query {
+        request = request.query(key, value);
+    }
+    let id = RequestId::next();
+    let context = ErrorContext {
+        request_id: Some(id),
+        endpoint,
+        method: Some(Method::Get),
+        url: Some(request.url().to_owned()),
+    };
+    budget::acquire_blocking();
+    debug!("{id} GET {}", request.url());
+    let started = Instant::now();
+    let result = match request.call() {
+        Ok(response) => {
+            debug!(
+                "{id} {} after {}ms",
+                response.status(),
+                started.elapsed().as_millis()
+            );
+            let url = response.get_url().to_owned();
+            let mut bytes = Vec::new();
+            match response.into_reader().read_to_end(&mut bytes) {
+                Ok(_) => http::parse_json(endpoint, id, url, &bytes),
+                Err(e) => Err(e.into()),
+            }
+        }
+        Err(ureq::Error::Status(status, response)) => {
+            debug!("{id} {status} after {}ms", started.elapsed().as_millis());
+            let url = response.get_url().to_owned();
+            let retry_after = response.header("Retry-After").map(ToOwned::to_owned);
+            let body = response.into_string().unwrap_or_default();
+            Err(http::status_error(
+                endpoint,
+                id,
+                Method::Get,
+                url,
+                status,
+                retry_after.as_deref(),
+                body,
+            ))
+        }
+        Err(ureq::Error::Transport(e)) => {
+            debug!("{id} failed: {e}");
+            Err(Box::new(e).into())
+        }
+    };
+    result.inspect_err(|e| errors::report(e, &context))
+}
+
+/// Query parameters for the optional page number.
+fn page_query(page: Option<u64>) -> Vec<(&'static str, String)> {
+    page.map(|p| vec![("page", p.to_string())])
+        .unwrap_or_default()
+}
+
+/// Blocking version of [`rest_api::user_ratings`](crate::rest_api::user_ratings).
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+pub fn user_ratings(cid: u64) -> Result<UserRatingsSimple, VatsimUtilError> {
+    let url = format!("{}/api/ratings/{cid}/", urls::endpoints().api);
+    fetch_json(Endpoint::Ratings, &url, &[])
+}
+
+/// Blocking version of [`rest_api::get_ratings_times`](crate::rest_api::get_ratings_times).
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+pub fn get_ratings_times(cid: u64) -> Result<RatingsTimeData, VatsimUtilError> {
+    let url = format!("{}/api/ratings/{cid}/rating_times", urls::endpoints().api);
+    fetch_json(Endpoint::RatingTimes, &url, &[])
+}
+
+/// Blocking version of [`rest_api::get_connections`](crate::rest_api::get_connections).
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+pub fn get_connections(
+    cid: u64,
+    page: Option<u64>,
+) -> Result<PaginatedResponse<ConnectionEntry>, VatsimUtilError> {
+    let url = format!("{}/api/ratings/{cid}/connections", urls::endpoints().api);
+    fetch_json(Endpoint::Connections, &url, &page_query(page))
+}
+
+/// Blocking version of [`rest_api::get_connections_all`](crate::rest_api::get_connections_all).
+#[must_use]
+pub fn get_connections_all(cid: u64) -> BatchResults<u64, Vec<ConnectionEntry>> {
+    all_pages(|page| get_connections(cid, Some(page)))
+}
+
+/// Blocking version of [`rest_api::get_atc_sessions`](crate::rest_api::get_atc_sessions).
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+pub fn get_atc_sessions(
+    cid: u64,
+    page: Option<u64>,
+    specifier: Option<&str>,
+    start: Option<&str>,
+    date: Option<&str>,
+) -> Result<PaginatedResponse<AtcSessionEntry>, VatsimUtilError> {
+    let mut url = format!("{}/api/ratings/{cid}/atcsessions/", urls::endpoints().api);
+    if let Some(spec) = specifier {
+        url += spec;
+    }
+    let mut query = page_query(page);
+    if let Some(s) = start {
+        query.push(("start", s.to_owned()));
+    }
+    if let Some(d) = date {
+        query.push(("date", d.to_owned()));
+    }
+    fetch_json(Endpoint::AtcSessions, &url, &query)
+}
+
+/// Blocking version of [`rest_api::get_flight_plans`](crate::rest_api::get_flight_plans).
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+pub fn get_flight_plans(
+    cid: u64,
+    page: Option<u64>,
+) -> Result<PaginatedResponse<RestFlightPlans>, VatsimUtilError> {
+    let url = format!("{}/api/ratings/{cid}/flight_plans", urls::endpoints().api);
+    fetch_json(Endpoint::FlightPlans, &url, &page_query(page))
+}
+
+/// Blocking version of [`rest_api::get_regions`](crate::rest_api::get_regions).
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+pub fn get_regions() -> Result<Vec<Region>, VatsimUtilError> {
+    let url = format!("{}/api/regions/", urls::endpoints().api);
+    fetch_json(Endpoint::Regions, &url, &[])
+}
+
+/// Blocking version of [`rest_api::get_regions_cached`](crate::rest_api::get_regions_cached).
+///
+/// Shares the [memoized](crate::memo) list with the async function.
+///
+/// # Errors
+///
+/// This function can fail if the list isn't memoized and the HTTP request
+/// fails or if the returned data does not match the schemas of the models
+/// passed to the deserializer.
+pub fn get_regions_cached() -> Result<Vec<Region>, VatsimUtilError> {
+    memo::REGIONS.get_or_fetch_blocking(memo::memo_ttls().regions, get_regions)
+}
+
+/// Blocking version of [`rest_api::get_divisions`](crate::rest_api::get_divisions).
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+pub fn get_divisions() -> Result<Vec<Division>, VatsimUtilError> {
+    let url = format!("{}/api/divisions/", urls::endpoints().api);
+    fetch_json(Endpoint::Divisions, &url, &[])
+}
+
+/// Blocking version of [`rest_api::get_divisions_cached`](crate::rest_api::get_divisions_cached).
+///
+/// Shares the [memoized](crate::memo) list with the async function.
+///
+/// # Errors
+///
+/// This function can fail if the list isn't memoized and the HTTP request
+/// fails or if the returned data does not match the schemas of the models
+/// passed to the deserializer.
+pub fn get_divisions_cached() -> Result<Vec<Division>, VatsimUtilError> {
+    memo::DIVISIONS.get_or_fetch_blocking(memo::memo_ttls().divisions, get_divisions)
+}
+
+/// Blocking version of [`rest_api::get_online_facilities`](crate::rest_api::get_online_facilities).
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+pub fn get_online_facilities() -> Result<Vec<Facility>, VatsimUtilError> {
+    let url = format!("{}/api/facilities/", urls::endpoints().api);
+    fetch_json(Endpoint::Facilities, &url, &[])
+}
+
+/// Blocking version of [`rest_api::get_online_facilities_cached`](crate::rest_api::get_online_facilities_cached).
+///
+/// Shares the [memoized](crate::memo) list with the async function.
+///
+/// # Errors
+///
+/// This function can fail if the list isn't memoized and the HTTP request
+/// fails or if the returned data does not match the schemas of the models
+/// passed to the deserializer.
+pub fn get_online_facilities_cached() -> Result<Vec<Facility>, VatsimUtilError> {
+    memo::FACILITIES.get_or_fetch_blocking(memo::memo_ttls().facilities, get_online_facilities)
+}
+
+/// Blocking version of [`rest_api::get_facility_history`](crate::rest_api::get_facility_history).
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+pub fn get_facility_history(
+    specifier: &str,
+    page: Option<u64>,
+    start: Option<&str>,
+    date: Option<&str>,
+) -> Result<PaginatedResponse<AtcSessionEntry>, VatsimUtilError> {
+    let url = format!("{}/api/facilities/{specifier}", urls::endpoints().api);
+    let mut query = page_query(page);
+    if let Some(s) = start {
+        query.push(("start", s.to_owned()));
+    }
+    if let Some(d) = date {
+        query.push(("date", d.to_owned()));
+    }
+    fetch_json(Endpoint::FacilityHistory, &url, &query)
+}
+
+/// Blocking version of [`rest_api::get_facility_history_all`](crate::rest_api::get_facility_history_all).
+#[must_use]
+pub fn get_facility_history_all(
+    specifier: &str,
+    start: Option<&str>,
+    date: Option<&str>,
+) -> BatchResults<u64, Vec<AtcSessionEntry>> {
+    all_pages(|page| get_facility_history(specifier, Some(page), start, date))
+}
+
+/// Get the first page, then every other page that it says there are,
+/// keeping going past pages that fail.
+fn all_pages<T>(
+    fetch: impl Fn(u64) -> Result<PaginatedResponse<T>, VatsimUtilError>,
+) -> BatchResults<u64, Vec<T>> {
+    let first = match fetch(1) {
+        Ok(first) => first,
+        Err(error) => return [(1, Err(error))].into_iter().collect(),
+    };
+    let total_pages = first.total_pages();
+    std::iter::once((1, Ok(first.results)))
+        .chain((2..=total_pages).map(|page| (page, fetch(page).map(|response| response.results))))
+        .collect()
+}
+
+/// Blocking version of the [live data client](crate::live_api::Vatsim).
+#[derive(Debug)]
+pub struct Vatsim {
+    v3_url: String,
+    transceivers_url: String,
+    sweatbox_servers: Option<String>,
+}
+
+impl Vatsim {
+    /// Create a new API struct instance, getting the live data URLs
+    /// from the status endpoint.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the HTTP request to the VATSIM API status
+    /// endpoint fails or doesn't list any URLs.
+    pub fn new() -> Result<Self, VatsimUtilError> {
+        debug!("Creating blocking VATSIM struct instance");
+        let status: Status = fetch_json(Endpoint::Status, &urls::endpoints().status, &[])?;
+        let mut rng = rand::thread_rng();
+        let v3_url = status
+            .data
+            .v3
+            .choose(&mut rng)
+            .ok_or(VatsimUtilError::NoV3Url())?
+            .clone();
+        let transceivers_url = status
+            .data
+            .transceivers
+            .choose(&mut rng)
+            .ok_or(VatsimUtilError::NoTransceiversUrl())?
+            .clone();
+        let sweatbox_servers = status.data.servers_sweatbox.choose(&mut rng).cloned();
+        Ok(Self {
+            v3_url,
+            transceivers_url,
+            sweatbox_servers,
+        })
+    }
+
+    /// Query the stored V3 endpoint, sorting the pilots and controllers
+    /// by their callsigns.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the HTTP request fails or if the returned
+    /// data does not match the schemas of the models passed to the
+    /// deserializer.
+    pub fn get_v3_data(&self) -> Result<V3ResponseData, VatsimUtilError> {
+        let mut data: V3ResponseData = fetch_json(Endpoint::V3Feed, &self.v3_url, &[])?;
+        data.pilots.sort_by(|a, b| a.callsign.cmp(&b.callsign));
+        data.controllers.sort_by(|a, b| a.callsign.cmp(&b.callsign));
+        Ok(data)
+    }
+
+    /// Get pilot transceiver frequency data.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the HTTP request fails or if the returned
+    /// data does not match the schemas of the models passed to the
+    /// deserializer.
+    pub fn get_transceivers_data(&self) -> Result<Vec<TransceiverResponseEntry>, VatsimUtilError> {
+        fetch_json(Endpoint::Transceivers, &self.transceivers_url, &[])
+    }
+
+    /// Get the sweatbox servers.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the status endpoint didn't list a URL
+    /// for the sweatbox servers, if the HTTP request fails, or if the
+    /// returned data does not match the schemas of the models passed to
+    /// the deserializer.
+    pub fn get_sweatbox_servers(&self) -> Result<Vec<Server>, VatsimUtilError> {
+        let url = self
+            .sweatbox_servers
+            .as_deref()
+            .ok_or(VatsimUtilError::NoSweatboxServersUrl())?;
+        fetch_json(Endpoint::SweatboxServers, url, &[])
+    }
+}
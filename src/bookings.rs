@@ -0,0 +1,325 @@
+//! ATC bookings from [atc-bookings.vatsim.net].
+//!
+//! Anyone can read bookings with [`get_bookings`] and [`get_booking`].
+//! Creating, updating, and deleting them needs an API key issued to a
+//! division or facility, used through a [`BookingsClient`].
+//!
+//! The base URL can be changed with [`urls::set_endpoints`].
+//!
+//! [atc-bookings.vatsim.net]: https://atc-bookings.vatsim.net/
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::bookings::{BookingType, BookingsClient, NewBooking};
+//!
+//! # async fn _do() {
+//! let client = BookingsClient::new("api-key");
+//! let booking = NewBooking {
+//!     callsign: String::from("SAN_TWR"),
+//!     cid: 1234567,
+//!     booking_type: BookingType::Booking,
+//!     start: String::from("2024-03-02 18:00:00"),
+//!     end: String::from("2024-03-02 20:00:00"),
+//!     division: None,
+//!     subdivision: None,
+//! };
+//! let created = client.create(&booking).await.unwrap();
+//! client.delete(created.id).await.unwrap();
+//! # }
+//! ```
+
+use crate::errors::VatsimUtilError;
+#[cfg(feature = "async")]
+use crate::{config, errors::Endpoint, http, models::Booking, urls};
+#[cfg(feature = "async")]
+use log::debug;
+#[cfg(feature = "async")]
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+#[cfg(feature = "async")]
+use std::sync::LazyLock;
+
+/// HTTP client for reading bookings.
+#[cfg(feature = "async")]
+static CLIENT: LazyLock<Client> = LazyLock::new(config::build_client);
+
+/// Why a position is booked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookingType {
+    /// Regular controlling.
+    Booking,
+    /// Controlling for an event.
+    Event,
+    /// A controller's practical exam.
+    Exam,
+    /// A training session.
+    Training,
+    /// A type that this library doesn't know about yet.
+    #[serde(other)]
+    Unknown,
+}
+
+impl fmt::Display for BookingType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Booking => "booking",
+            Self::Event => "event",
+            Self::Exam => "exam",
+            Self::Training => "training",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+/// A booking to create, or the new details of an existing booking.
+///
+/// Times are in UTC, formatted like `2024-03-02 18:00:00`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NewBooking {
+    /// Callsign of the position, like "`SAN_TWR`".
+    pub callsign: String,
+    /// CID of the controller.
+    pub cid: u64,
+    /// Why the position is booked.
+    #[serde(rename = "type")]
+    pub booking_type: BookingType,
+    /// When the booking starts.
+    pub start: String,
+    /// When the booking ends.
+    pub end: String,
+    /// Division code, if not the API key's own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub division: Option<String>,
+    /// Subdivision code, if not the API key's own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subdivision: Option<String>,
+}
+
+impl NewBooking {
+    /// Check the booking before sending it, so that mistakes are caught
+    /// without a round trip to the server.
+    ///
+    /// The callsign must be an uppercase position like "`SAN_TWR`", the
+    /// times must be in the `YYYY-MM-DD HH:MM:SS` format, and the booking
+    /// must end after it starts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use vatsim_utils::bookings::{BookingType, NewBooking};
+    ///
+    /// let mut booking = NewBooking {
+    ///     callsign: String::from("SAN_TWR"),
+    ///     cid: 1234567,
+    ///     booking_type: BookingType::Training,
+    ///     start: String::from("2024-03-02 18:00:00"),
+    ///     end: String::from("2024-03-02 20:00:00"),
+    ///     division: None,
+    ///     subdivision: None,
+    /// };
+    /// assert!(booking.validate().is_ok());
+    ///
+    /// booking.end = String::from("2024-03-02 17:00:00");
+    /// assert!(booking.validate().is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VatsimUtilError::InvalidBooking`] describing the first
+    /// problem found.
+    pub fn validate(&self) -> Result<(), VatsimUtilError> {
+        let invalid = |reason: &str| Err(VatsimUtilError::InvalidBooking(reason.to_owned()));
+        let callsign_ok = self.callsign.contains('_')
+            && !self.callsign.starts_with('_')
+            && !self.callsign.ends_with('_')
+            && self
+                .callsign
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+        if !callsign_ok {
+            return invalid("callsign must be an uppercase position, like SAN_TWR");
+        }
+        if self.cid == 0 {
+            return invalid("CID must be set");
+        }
+        if !is_booking_time(&self.start) {
+            return invalid("start must be formatted like 2024-03-02 18:00:00");
+        }
+        if !is_booking_time(&self.end) {
+            return invalid("end must be formatted like 2024-03-02 18:00:00");
+        }
+        // with the format checked, the strings sort chronologically
+        if self.end <= self.start {
+            return invalid("end must be after start");
+        }
+        Ok(())
+    }
+}
+
+/// Whether the time is in the `YYYY-MM-DD HH:MM:SS` format, with each
+/// part in range.
+fn is_booking_time(time: &str) -> bool {
+    let bytes = time.as_bytes();
+    if bytes.len() != 19
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || bytes[10] != b' '
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return false;
+    }
+    let part = |range: std::ops::Range<usize>| time.get(range).and_then(|s| s.parse::<u32>().ok());
+    matches!(
+        (
+            part(0..4),
+            part(5..7),
+            part(8..10),
+            part(11..13),
+            part(14..16),
+            part(17..19)
+        ),
+        (
+            Some(_),
+            Some(1..=12),
+            Some(1..=31),
+            Some(0..=23),
+            Some(0..=59),
+            Some(0..=59)
+        )
+    )
+}
+
+/// Get all current and upcoming bookings.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::bookings::get_bookings;
+///
+/// # async fn _do() {
+/// let bookings = get_bookings().await.unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+#[cfg(feature = "async")]
+pub async fn get_bookings() -> Result<Vec<Booking>, VatsimUtilError> {
+    let url = format!("{}/booking", urls::endpoints().bookings);
+    http::fetch_json(Endpoint::Bookings, CLIENT.get(url)).await
+}
+
+/// Get a single booking by its ID.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::bookings::get_booking;
+///
+/// # async fn _do() {
+/// let booking = get_booking(12345).await.unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+#[cfg(feature = "async")]
+pub async fn get_booking(id: u64) -> Result<Booking, VatsimUtilError> {
+    let url = format!("{}/booking/{id}", urls::endpoints().bookings);
+    http::fetch_json(Endpoint::Bookings, CLIENT.get(url)).await
+}
+
+/// Client for managing bookings with an ATC bookings API key.
+#[cfg(feature = "async")]
+pub struct BookingsClient {
+    client: Client,
+    api_key: String,
+}
+
+#[cfg(feature = "async")]
+impl fmt::Debug for BookingsClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BookingsClient")
+            .field("api_key", &"<redacted>")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "async")]
+impl BookingsClient {
+    /// Create a new client using the API key.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the HTTP user agent cannot be constructed, which
+    /// should never happen.
+    #[must_use]
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            client: config::build_client(),
+            api_key: api_key.to_owned(),
+        }
+    }
+
+    /// Create a booking, after [validating](NewBooking::validate) it.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the booking isn't valid, if the HTTP
+    /// request fails, or if the returned data does not match the schemas
+    /// of the models passed to the deserializer.
+    pub async fn create(&self, booking: &NewBooking) -> Result<Booking, VatsimUtilError> {
+        booking.validate()?;
+        debug!("Creating booking for {}", booking.callsign);
+        let url = format!("{}/booking", urls::endpoints().bookings);
+        let request = self
+            .client
+            .post(url)
+            .bearer_auth(&self.api_key)
+            .json(booking);
+        http::fetch_json(Endpoint::Bookings, request).await
+    }
+
+    /// Replace the details of a booking, after
+    /// [validating](NewBooking::validate) them.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the booking isn't valid, if the HTTP
+    /// request fails, or if the returned data does not match the schemas
+    /// of the models passed to the deserializer.
+    pub async fn update(&self, id: u64, booking: &NewBooking) -> Result<Booking, VatsimUtilError> {
+        booking.validate()?;
+        debug!("Updating booking {id}");
+        let url = format!("{}/booking/{id}", urls::endpoints().bookings);
+        let request = self
+            .client
+            .put(url)
+            .bearer_auth(&self.api_key)
+            .json(booking);
+        http::fetch_json(Endpoint::Bookings, request).await
+    }
+
+    /// Delete a booking.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the HTTP request fails.
+    pub async fn delete(&self, id: u64) -> Result<(), VatsimUtilError> {
+        debug!("Deleting booking {id}");
+        let url = format!("{}/booking/{id}", urls::endpoints().bookings);
+        let request = self.client.delete(url).bearer_auth(&self.api_key);
+        let _ = http::send(Endpoint::Bookings, request).await?;
+        Ok(())
+    }
+}
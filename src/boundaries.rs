@@ -0,0 +1,376 @@
+//! Airspace boundaries, like FIRs and sectors, and finding the traffic
+//! inside them.
+//!
+//! This crate doesn't include any boundary data. Load it from a source
+//! like the [VATSpy data project]'s `Boundaries.geojson` with
+//! [`Boundary::from_feature`], or build boundaries from your own
+//! coordinates with [`Boundary::new`].
+//!
+//! Boundaries that cross the antimeridian aren't supported.
+//!
+//! [VATSpy data project]: https://github.com/vatsimnetwork/vatspy-data-project
+
+use crate::{
+    distance::{destination_point, distance_nm},
+    geojson::{Feature, Geometry, Position},
+    models::Pilot,
+    spatial::PilotIndex,
+};
+use std::time::Duration;
+
+/// Distance between the points checked along a track when looking for a
+/// boundary crossing, in nautical miles.
+const CROSSING_STEP_NM: f64 = 1.0;
+
+/// The most steps to take looking for a crossing, about once around the
+/// globe, past which the track only revisits the same points.
+const MAX_CROSSING_STEPS: u32 = 21_600;
+
+/// An area made up of one or more polygons.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Boundary {
+    /// Identifier of the boundary, like "KZLA".
+    pub id: String,
+    /// Polygons making up the area; the first ring of each is the
+    /// exterior, any others are holes.
+    pub polygons: Vec<Vec<Vec<Position>>>,
+    /// `[min_lon, min_lat, max_lon, max_lat]` of all the polygons.
+    bbox: [f64; 4],
+}
+
+impl Boundary {
+    /// Create a new boundary from its polygons.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use vatsim_utils::boundaries::Boundary;
+    ///
+    /// let square = Boundary::new(
+    ///     "TEST",
+    ///     vec![vec![vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]]]],
+    /// );
+    ///
+    /// assert!(square.contains(5.0, 5.0));
+    /// assert!(!square.contains(5.0, 15.0));
+    /// ```
+    #[must_use]
+    pub fn new(id: &str, polygons: Vec<Vec<Vec<Position>>>) -> Self {
+        let mut bbox = [f64::MAX, f64::MAX, f64::MIN, f64::MIN];
+        for [lon, lat] in polygons.iter().flatten().flatten() {
+            bbox[0] = bbox[0].min(*lon);
+            bbox[1] = bbox[1].min(*lat);
+            bbox[2] = bbox[2].max(*lon);
+            bbox[3] = bbox[3].max(*lat);
+        }
+        Self {
+            id: id.to_owned(),
+            polygons,
+            bbox,
+        }
+    }
+
+    /// Create a boundary from a `GeoJSON` polygon or multi-polygon feature.
+    ///
+    /// The identifier is taken from the feature's `id` property, which is
+    /// what the `VATSpy` boundary data uses. Returns `None` for other
+    /// geometries or if the property is missing.
+    #[must_use]
+    pub fn from_feature(feature: &Feature) -> Option<Self> {
+        let id = feature.properties.get("id")?.as_str()?;
+        let polygons = match &feature.geometry {
+            Geometry::Polygon(rings) => vec![rings.clone()],
+            Geometry::MultiPolygon(polygons) => polygons.clone(),
+            Geometry::Point(_) | Geometry::LineString(_) => return None,
+        };
+        Some(Self::new(id, polygons))
+    }
+
+    /// Whether the point is inside the boundary.
+    #[must_use]
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        let [min_lon, min_lat, max_lon, max_lat] = self.bbox;
+        if lon < min_lon || lon > max_lon || lat < min_lat || lat > max_lat {
+            return false;
+        }
+        self.polygons.iter().any(|rings| {
+            rings
+                .iter()
+                .filter(|ring| ring_contains(ring, lat, lon))
+                .count()
+                % 2
+                == 1
+        })
+    }
+}
+
+/// A geographic area to limit traffic to.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::boundaries::Geofence;
+///
+/// let near_ksan = Geofence::Radius {
+///     lat: 32.7338,
+///     lon: -117.1933,
+///     nm: 30.0,
+/// };
+/// assert!(near_ksan.contains(32.9, -117.1));
+/// assert!(!near_ksan.contains(33.9416, -118.4085));
+///
+/// let socal = Geofence::BoundingBox {
+///     min_lat: 32.0,
+///     min_lon: -121.0,
+///     max_lat: 35.0,
+///     max_lon: -114.0,
+/// };
+/// assert!(socal.contains(33.9416, -118.4085));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geofence {
+    /// Within a distance of a point.
+    Radius {
+        /// Latitude of the center.
+        lat: f64,
+        /// Longitude of the center.
+        lon: f64,
+        /// Radius, in nautical miles.
+        nm: f64,
+    },
+    /// Inside a latitude and longitude box.
+    BoundingBox {
+        /// Southern edge.
+        min_lat: f64,
+        /// Western edge.
+        min_lon: f64,
+        /// Northern edge.
+        max_lat: f64,
+        /// Eastern edge.
+        max_lon: f64,
+    },
+    /// Inside an airspace boundary.
+    Boundary(Boundary),
+}
+
+impl Geofence {
+    /// Whether the point is inside the area.
+    #[must_use]
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        match self {
+            Self::Radius {
+                lat: center_lat,
+                lon: center_lon,
+                nm,
+            } => distance_nm(*center_lat, *center_lon, lat, lon) <= *nm,
+            Self::BoundingBox {
+                min_lat,
+                min_lon,
+                max_lat,
+                max_lon,
+            } => (*min_lat..=*max_lat).contains(&lat) && (*min_lon..=*max_lon).contains(&lon),
+            Self::Boundary(boundary) => boundary.contains(lat, lon),
+        }
+    }
+
+    /// Whether the pilot is inside the area.
+    #[must_use]
+    pub fn contains_pilot(&self, pilot: &Pilot) -> bool {
+        self.contains(pilot.latitude, pilot.longitude)
+    }
+}
+
+/// Get the pilots that are inside the boundary, in no particular order.
+///
+/// Only the pilots in the index that are inside the boundary's bounding
+/// box are checked against its polygons, so one index can be reused to
+/// find the traffic in many boundaries cheaply.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{
+///     boundaries::{pilots_in_boundary, Boundary},
+///     geojson::FeatureCollection,
+///     live_api::Vatsim,
+///     spatial::PilotIndex,
+/// };
+///
+/// # async fn _do() {
+/// let raw = std::fs::read_to_string("Boundaries.geojson").unwrap();
+/// let collection: FeatureCollection = serde_json::from_str(&raw).unwrap();
+/// let zla = collection
+///     .features
+///     .iter()
+///     .filter_map(Boundary::from_feature)
+///     .find(|boundary| boundary.id == "KZLA")
+///     .unwrap();
+///
+/// let api = Vatsim::new().await.unwrap();
+/// let data = api.get_v3_data().await.unwrap();
+/// let index = PilotIndex::new(&data.pilots);
+/// for pilot in pilots_in_boundary(&index, &zla) {
+///     println!("{}", pilot.callsign);
+/// }
+/// # }
+/// ```
+///
+/// ```rust
+/// # #[cfg(feature = "fixtures")] {
+/// use vatsim_utils::{
+///     boundaries::{pilots_in_boundary, Boundary},
+///     fixtures,
+///     spatial::PilotIndex,
+/// };
+///
+/// let data = fixtures::v3_data();
+/// let pilot = &data.pilots[0];
+/// let (lat, lon) = (pilot.latitude, pilot.longitude);
+/// let around = Boundary::new(
+///     "TEST",
+///     vec![vec![vec![
+///         [lon - 0.1, lat - 0.1],
+///         [lon + 0.1, lat - 0.1],
+///         [lon + 0.1, lat + 0.1],
+///         [lon - 0.1, lat + 0.1],
+///         [lon - 0.1, lat - 0.1],
+///     ]]],
+/// );
+///
+/// let index = PilotIndex::new(&data.pilots);
+/// let inside = pilots_in_boundary(&index, &around);
+/// assert_eq!(inside.len(), 1);
+/// assert_eq!(inside[0].callsign, pilot.callsign);
+/// # }
+/// ```
+#[must_use]
+pub fn pilots_in_boundary<'a>(index: &PilotIndex<'a>, boundary: &Boundary) -> Vec<&'a Pilot> {
+    let [min_lon, min_lat, max_lon, max_lat] = boundary.bbox;
+    index
+        .within_box(min_lat, min_lon, max_lat, max_lon)
+        .into_iter()
+        .filter(|pilot| boundary.contains(pilot.latitude, pilot.longitude))
+        .collect()
+}
+
+/// Where an aircraft is forecast to leave one boundary for another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundaryCrossing {
+    /// Identifier of the boundary the aircraft is in now, if any.
+    pub from: Option<String>,
+    /// Identifier of the boundary the aircraft will be in next, if any.
+    pub to: Option<String>,
+    /// `(latitude, longitude)` of the crossing.
+    pub position: (f64, f64),
+    /// Distance to the crossing, in nautical miles.
+    pub distance_nm: f64,
+    /// Time until the crossing at the groundspeed, if the aircraft is
+    /// moving.
+    pub time: Option<Duration>,
+}
+
+/// Forecast where and when an aircraft will next cross from one boundary
+/// into another, by following its track along a great circle.
+///
+/// The track is in degrees true and the groundspeed in knots. Points are
+/// checked every mile up to `max_distance_nm` ahead, and the first change
+/// found is narrowed down to a fraction of a mile, so boundary corners
+/// thinner than a mile can be missed. Leaving all the boundaries, like
+/// flying out of a FIR into unlisted oceanic airspace, counts as a
+/// crossing into `None`. Where boundaries overlap, the first one listed
+/// that contains a point is used. A `max_distance_nm` that isn't finite
+/// and positive finds no crossing, and the search never goes further
+/// than about once around the globe.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::boundaries::{next_boundary_crossing, Boundary};
+///
+/// let west = Boundary::new(
+///     "WEST",
+///     vec![vec![vec![[-10.0, 0.0], [0.0, 0.0], [0.0, 10.0], [-10.0, 10.0], [-10.0, 0.0]]]],
+/// );
+/// let east = Boundary::new(
+///     "EAST",
+///     vec![vec![vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]]]],
+/// );
+///
+/// let boundaries = [west, east];
+///
+/// // a degree west of the line, flying east at 240 knots
+/// let crossing = next_boundary_crossing(5.0, -1.0, 90.0, 240, &boundaries, 500.0).unwrap();
+///
+/// assert_eq!(crossing.from.as_deref(), Some("WEST"));
+/// assert_eq!(crossing.to.as_deref(), Some("EAST"));
+/// assert_eq!(crossing.distance_nm.round(), 60.0);
+/// assert_eq!(crossing.time.unwrap().as_secs() / 60, 14);
+///
+/// assert!(next_boundary_crossing(5.0, -1.0, 90.0, 240, &boundaries, f64::NAN).is_none());
+/// ```
+#[must_use]
+pub fn next_boundary_crossing(
+    lat: f64,
+    lon: f64,
+    track: f64,
+    groundspeed: i64,
+    boundaries: &[Boundary],
+    max_distance_nm: f64,
+) -> Option<BoundaryCrossing> {
+    let boundary_at = |distance: f64| {
+        let (lat, lon) = destination_point(lat, lon, track, distance);
+        boundaries
+            .iter()
+            .position(|boundary| boundary.contains(lat, lon))
+    };
+    if !max_distance_nm.is_finite() || max_distance_nm <= 0.0 {
+        return None;
+    }
+    let current = boundary_at(0.0);
+    let mut before = 0.0;
+    let mut after = CROSSING_STEP_NM;
+    let mut steps = 0;
+    loop {
+        if after > max_distance_nm || steps >= MAX_CROSSING_STEPS {
+            return None;
+        }
+        if boundary_at(after) != current {
+            break;
+        }
+        before = after;
+        after += CROSSING_STEP_NM;
+        steps += 1;
+    }
+    while after - before > 0.01 {
+        let middle = f64::midpoint(before, after);
+        if boundary_at(middle) == current {
+            before = middle;
+        } else {
+            after = middle;
+        }
+    }
+    let id = |index: Option<usize>| index.map(|i| boundaries[i].id.clone());
+    #[allow(clippy::cast_precision_loss)]
+    let groundspeed = groundspeed as f64;
+    Some(BoundaryCrossing {
+        from: id(current),
+        to: id(boundary_at(after)),
+        position: destination_point(lat, lon, track, after),
+        distance_nm: after,
+        time: (groundspeed > 0.0).then(|| Duration::from_secs_f64(after / groundspeed * 3600.0)),
+    })
+}
+
+/// Whether the point is inside the ring, by ray casting.
+fn ring_contains(ring: &[Position], lat: f64, lon: f64) -> bool {
+    let mut inside = false;
+    for (a, b) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+        let ([lon_a, lat_a], [lon_b, lat_b]) = (*a, *b);
+        if (lat_a > lat) != (lat_b > lat)
+            && lon < (lon_b - lon_a) * (lat - lat_a) / (lat_b - lat_a) + lon_a
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
@@ -0,0 +1,113 @@
+//! A process-wide limit on the rate of requests to VATSIM.
+//!
+//! Applications made up of several components - a bot polling live data,
+//! a web page showing user stats - can each be polite on their own but
+//! together make more requests than they should. Setting a request budget
+//! with [`set_request_budget`] makes every request that the crate sends to
+//! VATSIM, from any module, take a token from one shared bucket, waiting
+//! for the bucket to refill if it's empty.
+//!
+//! There's no budget by default. Webhook deliveries don't count towards it,
+//! as they're sent to the application's own endpoints.
+
+use std::{
+    sync::{LazyLock, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// Token bucket state.
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    per_second: f64,
+    refilled: Instant,
+}
+
+impl Bucket {
+    /// Take a token if there is one, or else return how long until
+    /// there will be.
+    fn take(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.capacity);
+        self.refilled = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.per_second,
+            ))
+        }
+    }
+}
+
+/// The current budget.
+static BUCKET: LazyLock<Mutex<Option<Bucket>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Limit requests to VATSIM to `requests` per `period`, across the whole
+/// process.
+///
+/// Up to `requests` can be made at once, after which requests wait for
+/// the budget to refill at a steady rate. Setting a budget replaces any
+/// existing one, starting full.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use vatsim_utils::budget::{clear_request_budget, set_request_budget};
+///
+/// // 30 requests per minute, between all uses of the crate
+/// set_request_budget(30, Duration::from_secs(60));
+/// // ...
+/// clear_request_budget();
+/// ```
+///
+/// # Panics
+///
+/// Will panic if `requests` or `period` is zero.
+pub fn set_request_budget(requests: u32, period: Duration) {
+    assert!(requests > 0, "Request budget must allow some requests");
+    assert!(!period.is_zero(), "Request budget period must not be zero");
+    let capacity = f64::from(requests);
+    let bucket = Bucket {
+        capacity,
+        tokens: capacity,
+        per_second: capacity / period.as_secs_f64(),
+        refilled: Instant::now(),
+    };
+    *BUCKET.lock().unwrap_or_else(PoisonError::into_inner) = Some(bucket);
+}
+
+/// Remove the request budget, if one was set.
+pub fn clear_request_budget() {
+    *BUCKET.lock().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Take a token from the budget, or else how long to wait to try again.
+#[cfg(any(feature = "async", feature = "sync-ureq"))]
+fn try_take() -> Option<Duration> {
+    BUCKET
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+        .and_then(Bucket::take)
+}
+
+/// Wait until the budget allows a request.
+#[cfg(feature = "async")]
+pub(crate) async fn acquire() {
+    while let Some(wait) = try_take() {
+        futures_timer::Delay::new(wait).await;
+    }
+}
+
+/// Block the thread until the budget allows a request.
+#[cfg(feature = "sync-ureq")]
+pub(crate) fn acquire_blocking() {
+    while let Some(wait) = try_take() {
+        std::thread::sleep(wait);
+    }
+}
@@ -0,0 +1,410 @@
+//! Settings for the HTTP clients that the crate creates.
+//!
+//! Like the [DNS overrides](crate::dns), clients read the settings when
+//! they're created, so set them with [`set_client_config`] before
+//! making requests.
+//!
+//! VATSIM's data policy asks clients to identify themselves, so set the
+//! application's name and contact details with [`ClientConfig::contact`];
+//! the crate logs a warning the first time it creates a client without
+//! them.
+//!
+//! Deployments can instead tune the crate with environment variables,
+//! applied with [`init_from_env`]:
+//!
+//! | Variable | Setting |
+//! |---|---|
+//! | `VATSIM_APP_NAME`, `VATSIM_CONTACT` | [`ClientConfig::contact`], both required |
+//! | `VATSIM_USER_AGENT` | [`ClientConfig::user_agent`] |
+//! | `VATSIM_PROXY` | [`ClientConfig::proxy`] |
+//! | `VATSIM_TIMEOUT_SECS` | [`ClientConfig::timeout`] |
+//! | `VATSIM_CONNECT_TIMEOUT_SECS` | [`ClientConfig::connect_timeout`] |
+//! | `VATSIM_API_KEY` | [`ClientConfig::api_key`] |
+//! | `VATSIM_COMPRESSION` | [`ClientConfig::compression`], `true` or `false` |
+//! | `VATSIM_STATUS_URL`, `VATSIM_API_URL`, `VATSIM_METAR_URL`, `VATSIM_EVENTS_URL`, `VATSIM_BOOKINGS_URL`, `VATSIM_AUTH_URL`, `VATSIM_SLURPER_URL` | [`Endpoints`] |
+//!
+//! Unset or empty variables leave the default in place.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use vatsim_utils::config::{reset_client_config, set_client_config, ClientConfig};
+//!
+//! set_client_config(
+//!     ClientConfig::default()
+//!         .contact("my-discord-bot", "ops@example.com")
+//!         .timeout(Duration::from_secs(10)),
+//! );
+//! // ...
+//! reset_client_config();
+//! ```
+
+use crate::{
+    dns,
+    errors::VatsimUtilError,
+    urls::{self, Endpoints},
+};
+#[cfg(feature = "async")]
+use reqwest::{Client, ClientBuilder, Proxy};
+use std::{
+    env, fmt,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        LazyLock, PoisonError, RwLock,
+    },
+    time::Duration,
+};
+
+/// User agent sent with every request, after the application's details.
+const USER_AGENT: &str = "github.com/celeo/vatsim_utils";
+
+/// Whether the warning about a missing contact has been logged.
+static WARNED_ANONYMOUS: AtomicBool = AtomicBool::new(false);
+
+/// Currently-configured settings.
+static CONFIG: LazyLock<RwLock<ClientConfig>> =
+    LazyLock::new(|| RwLock::new(ClientConfig::default()));
+
+/// HTTP client settings.
+#[derive(Clone)]
+pub struct ClientConfig {
+    compression: bool,
+    user_agent: String,
+    identified: bool,
+    proxy: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    api_key: Option<String>,
+}
+
+impl fmt::Debug for ClientConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientConfig")
+            .field("compression", &self.compression)
+            .field("user_agent", &self.user_agent)
+            .field("identified", &self.identified)
+            .field("proxy", &self.proxy)
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            compression: true,
+            user_agent: USER_AGENT.to_owned(),
+            identified: false,
+            proxy: None,
+            timeout: None,
+            connect_timeout: None,
+            api_key: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Read the settings from the environment variables listed in the
+    /// [module docs](self), using the defaults for any that aren't set.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if a variable is set to a value that can't
+    /// be parsed.
+    pub fn from_env() -> Result<Self, VatsimUtilError> {
+        let mut config = Self::default();
+        if let Some(compression) = parsed_var("VATSIM_COMPRESSION")? {
+            config = config.compression(compression);
+        }
+        match (var("VATSIM_APP_NAME"), var("VATSIM_CONTACT")) {
+            (Some(app), Some(contact)) => config = config.contact(&app, &contact),
+            (None, None) => {}
+            (Some(_), None) => {
+                return Err(VatsimUtilError::InvalidEnvVar {
+                    name: "VATSIM_CONTACT",
+                    value: String::new(),
+                })
+            }
+            (None, Some(_)) => {
+                return Err(VatsimUtilError::InvalidEnvVar {
+                    name: "VATSIM_APP_NAME",
+                    value: String::new(),
+                })
+            }
+        }
+        if let Some(user_agent) = var("VATSIM_USER_AGENT") {
+            config = config.user_agent(&user_agent);
+        }
+        if let Some(proxy) = var("VATSIM_PROXY") {
+            config = config
+                .proxy(&proxy)
+                .map_err(|_| VatsimUtilError::InvalidEnvVar {
+                    name: "VATSIM_PROXY",
+                    value: proxy,
+                })?;
+        }
+        if let Some(seconds) = parsed_var("VATSIM_TIMEOUT_SECS")? {
+            config = config.timeout(Duration::from_secs(seconds));
+        }
+        if let Some(seconds) = parsed_var("VATSIM_CONNECT_TIMEOUT_SECS")? {
+            config = config.connect_timeout(Duration::from_secs(seconds));
+        }
+        if let Some(key) = var("VATSIM_API_KEY") {
+            config = config.api_key(&key);
+        }
+        Ok(config)
+    }
+
+    /// Set whether to ask servers to gzip or brotli compress responses,
+    /// which is on by default.
+    ///
+    /// The V3 data feed is several megabytes, and compresses to a
+    /// fraction of that. Turn compression off if a proxy between the
+    /// application and VATSIM mangles compressed bodies.
+    ///
+    /// The async clients only support compression with the `compression`
+    /// feature; the blocking functions always support gzip.
+    #[must_use]
+    pub fn compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
+    /// Identify the application to VATSIM with its name and how to
+    /// contact its operator, like an email address or URL.
+    ///
+    /// The user agent becomes `{app} ({contact}) github.com/celeo/vatsim_utils`.
+    ///
+    /// ```rust
+    /// use vatsim_utils::config::ClientConfig;
+    ///
+    /// let config = ClientConfig::default().contact("atc-tracker/1.2", "https://example.com");
+    /// assert_eq!(
+    ///     config.get_user_agent(),
+    ///     "atc-tracker/1.2 (https://example.com) github.com/celeo/vatsim_utils"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn contact(mut self, app: &str, contact: &str) -> Self {
+        self.user_agent = format!("{} ({}) {USER_AGENT}", app.trim(), contact.trim());
+        self.identified = true;
+        self
+    }
+
+    /// Replace the whole user agent sent with requests.
+    ///
+    /// Prefer [`contact`](Self::contact), which keeps the crate's name
+    /// in the user agent.
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        user_agent.clone_into(&mut self.user_agent);
+        self.identified = true;
+        self
+    }
+
+    /// The user agent sent with requests.
+    #[must_use]
+    pub fn get_user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Send all requests through a proxy, like `http://proxy:3128` or
+    /// `socks5://proxy:1080`.
+    ///
+    /// Without this, the async clients use the proxy from the standard
+    /// `HTTPS_PROXY` and `HTTP_PROXY` variables, if set.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the URL can't be parsed.
+    pub fn proxy(mut self, url: &str) -> Result<Self, VatsimUtilError> {
+        #[cfg(feature = "async")]
+        let _ = Proxy::all(url).map_err(VatsimUtilError::ReqwestError)?;
+        #[cfg(all(feature = "sync-ureq", not(feature = "async")))]
+        let _ = ureq::Proxy::new(url).map_err(|e| match e {
+            ureq::Error::Transport(transport) => Box::new(transport),
+            ureq::Error::Status(..) => unreachable!("parsing a proxy doesn't make requests"),
+        })?;
+        self.proxy = Some(url.to_owned());
+        Ok(self)
+    }
+
+    /// Set the time limit for a whole request, from connecting until the
+    /// response's body has been read. There's no limit by default.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the time limit for connecting to the server.
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the key sent in the `X-API-Key` header of requests to the
+    /// [core API](crate::urls::Endpoints::api).
+    ///
+    /// The key isn't sent to any other services.
+    #[must_use]
+    pub fn api_key(mut self, key: &str) -> Self {
+        self.api_key = Some(key.to_owned());
+        self
+    }
+}
+
+/// Apply the client settings and endpoints from the environment.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::{config::init_from_env, urls::endpoints};
+///
+/// std::env::set_var("VATSIM_API_URL", "http://localhost:8080/");
+/// init_from_env().unwrap();
+/// assert_eq!(endpoints().api, "http://localhost:8080");
+///
+/// std::env::set_var("VATSIM_TIMEOUT_SECS", "soon");
+/// assert!(init_from_env().is_err());
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if a variable is set to a value that can't
+/// be parsed, in which case nothing is changed.
+pub fn init_from_env() -> Result<(), VatsimUtilError> {
+    let config = ClientConfig::from_env()?;
+    set_client_config(config);
+    urls::set_endpoints(Endpoints::from_env());
+    Ok(())
+}
+
+/// Get the current settings.
+#[must_use]
+pub fn client_config() -> ClientConfig {
+    CONFIG
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Replace the settings for clients created from now on.
+pub fn set_client_config(config: ClientConfig) {
+    *CONFIG.write().unwrap_or_else(PoisonError::into_inner) = config;
+}
+
+/// Go back to the default settings.
+pub fn reset_client_config() {
+    set_client_config(ClientConfig::default());
+}
+
+/// Get a non-empty environment variable.
+pub(crate) fn var(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
+
+/// Get and parse a non-empty environment variable.
+fn parsed_var<T: FromStr>(name: &'static str) -> Result<Option<T>, VatsimUtilError> {
+    var(name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| VatsimUtilError::InvalidEnvVar { name, value })
+        })
+        .transpose()
+}
+
+/// The API key to send with a request to `url`, if there is one.
+#[cfg(any(feature = "async", feature = "sync-ureq"))]
+pub(crate) fn api_key_for(url: &str) -> Option<String> {
+    let key = client_config().api_key?;
+    let api = urls::endpoints().api;
+    let rest = url.strip_prefix(&api)?;
+    (rest.is_empty() || rest.starts_with('/') || rest.starts_with('?')).then_some(key)
+}
+
+/// Log a warning, once, if the application hasn't identified itself.
+#[cfg(any(feature = "async", feature = "sync-ureq"))]
+fn warn_if_anonymous(config: &ClientConfig) {
+    if !config.identified && !WARNED_ANONYMOUS.swap(true, Ordering::Relaxed) {
+        log::warn!(
+            "No contact details set for requests to VATSIM; \
+             set them with vatsim_utils::config::ClientConfig::contact"
+        );
+    }
+}
+
+/// Create an async client with the current settings.
+///
+/// # Panics
+///
+/// Will panic if the HTTP user agent cannot be constructed, which
+/// should never happen.
+#[cfg(feature = "async")]
+pub(crate) fn build_client() -> Client {
+    build_client_with(&client_config())
+}
+
+/// Create an async client with the settings.
+///
+/// # Panics
+///
+/// Will panic if the HTTP user agent cannot be constructed, which
+/// should never happen.
+#[cfg(feature = "async")]
+pub(crate) fn build_client_with(config: &ClientConfig) -> Client {
+    warn_if_anonymous(config);
+    let mut builder = ClientBuilder::new().user_agent(&config.user_agent);
+    #[cfg(feature = "compression")]
+    {
+        builder = builder.gzip(config.compression).brotli(config.compression);
+    }
+    if let Some(proxy) = config.proxy.as_deref().and_then(|url| Proxy::all(url).ok()) {
+        builder = builder.proxy(proxy);
+    }
+    if let Some(timeout) = config.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(timeout) = config.connect_timeout {
+        builder = builder.connect_timeout(timeout);
+    }
+    dns::apply(builder).build().expect("Invalid HTTP Agent")
+}
+
+/// Create a blocking agent with the current settings.
+#[cfg(feature = "sync-ureq")]
+pub(crate) fn build_agent() -> ureq::Agent {
+    let config = client_config();
+    warn_if_anonymous(&config);
+    let mut builder = dns::apply_blocking(ureq::AgentBuilder::new()).user_agent(&config.user_agent);
+    if let Some(url) = &config.proxy {
+        match ureq::Proxy::new(url) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => log::warn!("Proxy {url} not supported by blocking functions: {e}"),
+        }
+    }
+    if let Some(timeout) = config.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(timeout) = config.connect_timeout {
+        builder = builder.timeout_connect(timeout);
+    }
+    builder.build()
+}
+
+/// The `Accept-Encoding` header value for blocking requests.
+///
+/// The agent asks for compressed responses unless the header is set.
+#[cfg(feature = "sync-ureq")]
+pub(crate) fn blocking_accept_encoding() -> Option<&'static str> {
+    (!client_config().compression).then_some("identity")
+}
@@ -0,0 +1,136 @@
+//! Estimating where a pilot should start their descent.
+//!
+//! The estimate assumes a constant descent angle, from the cruise altitude
+//! to the target altitude at the end of the route. Wind, speed restrictions,
+//! and level segments aren't accounted for, so it's a planning aid for
+//! dispatchers and controllers, not a substitute for the pilot's own
+//! calculation.
+
+use crate::distance::{destination_point, distance_nm, initial_bearing};
+use std::time::Duration;
+
+/// Feet in a nautical mile.
+const FEET_PER_NM: f64 = 6076.12;
+
+/// How steeply an aircraft descends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DescentProfile {
+    /// Descent angle, in degrees.
+    pub angle_degrees: f64,
+}
+
+impl Default for DescentProfile {
+    /// The typical 3° path.
+    fn default() -> Self {
+        Self { angle_degrees: 3.0 }
+    }
+}
+
+/// Estimated top of descent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TopOfDescent {
+    /// Distance needed to descend, in nautical miles.
+    pub descent_distance_nm: f64,
+    /// Distance from the route's start to the top of descent, in nautical
+    /// miles. Negative when the pilot is already past it.
+    pub distance_to_tod_nm: f64,
+    /// `(latitude, longitude)` of the top of descent along the route,
+    /// if the pilot hasn't passed it.
+    pub position: Option<(f64, f64)>,
+    /// Rate of descent needed to hold the profile at the groundspeed,
+    /// in feet per minute.
+    pub descent_rate_fpm: f64,
+    /// Time until reaching the top of descent at the groundspeed, if
+    /// the pilot is moving and hasn't passed it.
+    pub time_to_tod: Option<Duration>,
+}
+
+/// Estimate the top of descent along the remaining route.
+///
+/// The route is a list of `(latitude, longitude)` points, starting at the
+/// pilot's current position and ending where they should reach the target
+/// altitude. Altitudes are in feet and groundspeed in knots.
+///
+/// Returns `None` if the descent angle isn't between 0° and 90°, or if
+/// any of the route's points aren't finite numbers.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::descent::{top_of_descent, DescentProfile};
+///
+/// // from a point east of KSAN, direct to the airport
+/// let route = [(32.7338, -114.0), (32.7338, -117.1933)];
+/// let tod = top_of_descent(&route, 35_000, 0, 450, DescentProfile::default()).unwrap();
+///
+/// assert_eq!(tod.descent_distance_nm.round() as i64, 110);
+/// assert_eq!(tod.distance_to_tod_nm.round() as i64, 51);
+/// assert!(tod.position.is_some());
+///
+/// let flat = DescentProfile { angle_degrees: 0.0 };
+/// assert!(top_of_descent(&route, 35_000, 0, 450, flat).is_none());
+/// ```
+#[must_use]
+pub fn top_of_descent(
+    route: &[(f64, f64)],
+    cruise_altitude: i64,
+    target_altitude: i64,
+    groundspeed: i64,
+    profile: DescentProfile,
+) -> Option<TopOfDescent> {
+    if !(profile.angle_degrees > 0.0 && profile.angle_degrees < 90.0)
+        || route
+            .iter()
+            .any(|(lat, lon)| !lat.is_finite() || !lon.is_finite())
+    {
+        return None;
+    }
+    let tan = profile.angle_degrees.to_radians().tan();
+    #[allow(clippy::cast_precision_loss)]
+    let altitude_to_lose = (cruise_altitude - target_altitude).max(0) as f64;
+    #[allow(clippy::cast_precision_loss)]
+    let groundspeed = groundspeed.max(0) as f64;
+    let descent_distance_nm = altitude_to_lose / tan / FEET_PER_NM;
+    let descent_rate_fpm = groundspeed * FEET_PER_NM / 60.0 * tan;
+
+    let legs: Vec<_> = route
+        .windows(2)
+        .map(|leg| {
+            (
+                leg[0],
+                leg[1],
+                distance_nm(leg[0].0, leg[0].1, leg[1].0, leg[1].1),
+            )
+        })
+        .collect();
+    let route_length: f64 = legs.iter().map(|(_, _, length)| length).sum();
+    let distance_to_tod_nm = route_length - descent_distance_nm;
+
+    let position = if distance_to_tod_nm < 0.0 {
+        None
+    } else {
+        let mut remaining = distance_to_tod_nm;
+        legs.iter()
+            .find_map(|&(from, to, length)| {
+                if remaining <= length {
+                    let bearing = initial_bearing(from.0, from.1, to.0, to.1);
+                    Some(destination_point(from.0, from.1, bearing, remaining))
+                } else {
+                    remaining -= length;
+                    None
+                }
+            })
+            .or_else(|| route.last().copied())
+    };
+    let seconds_to_tod = distance_to_tod_nm / groundspeed * 3600.0;
+    let time_to_tod = (position.is_some() && groundspeed > 0.0 && seconds_to_tod.is_finite())
+        .then(|| Duration::from_secs_f64(seconds_to_tod));
+
+    Some(TopOfDescent {
+        descent_distance_nm,
+        distance_to_tod_nm,
+        position,
+        descent_rate_fpm,
+        time_to_tod,
+    })
+}
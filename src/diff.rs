@@ -0,0 +1,422 @@
+//! Comparing two snapshots of live data to find what changed.
+//!
+//! Pass the previously-fetched and newly-fetched V3 data to [`diff`] to get
+//! a list of [`NetworkEvent`]s describing the clients that connected and
+//! disconnected in-between, and any ATIS stations that changed. Use
+//! [`diff_with_options`] to control what is compared, like excluding
+//! observers, reporting pilots that moved further than
+//! [thresholds](UpdateThresholds), or only reporting traffic in an
+//! [area](Geofence).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::{diff::diff, live_api::Vatsim};
+//!
+//! # async fn _do() {
+//! let api = Vatsim::new().await.unwrap();
+//! let previous = api.get_v3_data().await.unwrap();
+//! // wait for the feed to update ...
+//! let current = api.get_v3_data().await.unwrap();
+//! for event in diff(&previous, &current) {
+//!     println!("{:?}: {}", event.kind(), event.callsign());
+//! }
+//! # }
+//! ```
+
+use crate::{
+    boundaries::Geofence,
+    distance::distance_nm,
+    models::{Atis, Controller, Observers, Pilot, V3ResponseData},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A change between two snapshots.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum NetworkEvent {
+    /// A pilot appeared in the newer snapshot.
+    PilotConnected(Pilot),
+    /// A pilot from the older snapshot is no longer present.
+    PilotDisconnected(Pilot),
+    /// A pilot in both snapshots moved, climbed, descended, or turned by
+    /// more than the [thresholds](UpdateThresholds). Only produced when
+    /// [`DiffOptions::pilot_updates`] is set.
+    PilotUpdated {
+        /// The pilot in the older snapshot.
+        previous: Box<Pilot>,
+        /// The pilot in the newer snapshot.
+        pilot: Pilot,
+    },
+    /// A pilot in both snapshots moved into the
+    /// [geofence](DiffOptions::geofence).
+    PilotEntered(Pilot),
+    /// A pilot in both snapshots moved out of the
+    /// [geofence](DiffOptions::geofence).
+    PilotLeft(Pilot),
+    /// A controller appeared in the newer snapshot.
+    ControllerConnected(Controller),
+    /// A controller from the older snapshot is no longer present.
+    ControllerDisconnected(Controller),
+    /// An ATIS station's information letter changed.
+    AtisLetterChanged {
+        /// The airport the ATIS is for.
+        airport: String,
+        /// The letter in the older snapshot.
+        previous: Option<String>,
+        /// The ATIS in the newer snapshot.
+        atis: Atis,
+    },
+    /// An ATIS station's text changed without the letter changing.
+    AtisTextChanged {
+        /// The airport the ATIS is for.
+        airport: String,
+        /// The text in the older snapshot.
+        previous: Option<Vec<String>>,
+        /// The ATIS in the newer snapshot.
+        atis: Atis,
+    },
+}
+
+/// The kind of a [`NetworkEvent`], without any of its data.
+///
+/// Useful for filtering events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// See [`NetworkEvent::PilotConnected`].
+    PilotConnected,
+    /// See [`NetworkEvent::PilotDisconnected`].
+    PilotDisconnected,
+    /// See [`NetworkEvent::PilotUpdated`].
+    PilotUpdated,
+    /// See [`NetworkEvent::PilotEntered`].
+    PilotEntered,
+    /// See [`NetworkEvent::PilotLeft`].
+    PilotLeft,
+    /// See [`NetworkEvent::ControllerConnected`].
+    ControllerConnected,
+    /// See [`NetworkEvent::ControllerDisconnected`].
+    ControllerDisconnected,
+    /// See [`NetworkEvent::AtisLetterChanged`].
+    AtisLetterChanged,
+    /// See [`NetworkEvent::AtisTextChanged`].
+    AtisTextChanged,
+}
+
+impl NetworkEvent {
+    /// The kind of this event.
+    #[must_use]
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Self::PilotConnected(_) => EventKind::PilotConnected,
+            Self::PilotDisconnected(_) => EventKind::PilotDisconnected,
+            Self::PilotUpdated { .. } => EventKind::PilotUpdated,
+            Self::PilotEntered(_) => EventKind::PilotEntered,
+            Self::PilotLeft(_) => EventKind::PilotLeft,
+            Self::ControllerConnected(_) => EventKind::ControllerConnected,
+            Self::ControllerDisconnected(_) => EventKind::ControllerDisconnected,
+            Self::AtisLetterChanged { .. } => EventKind::AtisLetterChanged,
+            Self::AtisTextChanged { .. } => EventKind::AtisTextChanged,
+        }
+    }
+
+    /// The CID of the user this event is about.
+    #[must_use]
+    pub fn cid(&self) -> u64 {
+        match self {
+            Self::PilotConnected(p)
+            | Self::PilotDisconnected(p)
+            | Self::PilotUpdated { pilot: p, .. }
+            | Self::PilotEntered(p)
+            | Self::PilotLeft(p) => p.cid,
+            Self::ControllerConnected(c) | Self::ControllerDisconnected(c) => c.cid,
+            Self::AtisLetterChanged { atis, .. } | Self::AtisTextChanged { atis, .. } => atis.cid,
+        }
+    }
+
+    /// The callsign this event is about.
+    #[must_use]
+    pub fn callsign(&self) -> &str {
+        match self {
+            Self::PilotConnected(p)
+            | Self::PilotDisconnected(p)
+            | Self::PilotUpdated { pilot: p, .. }
+            | Self::PilotEntered(p)
+            | Self::PilotLeft(p) => &p.callsign,
+            Self::ControllerConnected(c) | Self::ControllerDisconnected(c) => &c.callsign,
+            Self::AtisLetterChanged { atis, .. } | Self::AtisTextChanged { atis, .. } => {
+                &atis.callsign
+            }
+        }
+    }
+}
+
+/// How much a pilot has to change between snapshots to produce a
+/// [`NetworkEvent::PilotUpdated`].
+///
+/// Only the position, altitude, and heading are compared. The default
+/// thresholds report any change to them.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::diff::UpdateThresholds;
+///
+/// let thresholds = UpdateThresholds::default()
+///     .min_distance(1.0)
+///     .min_altitude(500)
+///     .ignore_heading_only(true);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UpdateThresholds {
+    /// Minimum distance moved, in nautical miles.
+    pub min_distance_nm: f64,
+    /// Minimum change in altitude, in feet.
+    pub min_altitude_ft: i64,
+    /// Whether a change in heading alone is ignored.
+    pub ignore_heading_only: bool,
+}
+
+impl UpdateThresholds {
+    /// Set the minimum distance moved, in nautical miles.
+    #[must_use]
+    pub fn min_distance(mut self, nm: f64) -> Self {
+        self.min_distance_nm = nm;
+        self
+    }
+
+    /// Set the minimum change in altitude, in feet.
+    #[must_use]
+    pub fn min_altitude(mut self, ft: i64) -> Self {
+        self.min_altitude_ft = ft;
+        self
+    }
+
+    /// Set whether a change in heading alone is ignored.
+    #[must_use]
+    pub fn ignore_heading_only(mut self, ignore: bool) -> Self {
+        self.ignore_heading_only = ignore;
+        self
+    }
+
+    /// Whether the change from `previous` to `current` passes any of the
+    /// thresholds.
+    #[must_use]
+    pub fn is_significant(&self, previous: &Pilot, current: &Pilot) -> bool {
+        let moved = distance_nm(
+            previous.latitude,
+            previous.longitude,
+            current.latitude,
+            current.longitude,
+        );
+        let climbed = previous.altitude.abs_diff(current.altitude);
+        (moved > 0.0 && moved >= self.min_distance_nm)
+            || (climbed > 0 && climbed >= self.min_altitude_ft.unsigned_abs())
+            || (!self.ignore_heading_only && previous.heading != current.heading)
+    }
+}
+
+/// Options for [`diff_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct DiffOptions {
+    /// Whether observers produce controller events.
+    pub observers: Observers,
+    /// Thresholds for reporting pilots that changed, if they should be.
+    pub pilot_updates: Option<UpdateThresholds>,
+    /// Area to limit pilot events to, if any.
+    pub geofence: Option<Geofence>,
+}
+
+impl DiffOptions {
+    /// Set whether observers produce controller events.
+    #[must_use]
+    pub fn observers(mut self, observers: Observers) -> Self {
+        self.observers = observers;
+        self
+    }
+
+    /// Report pilots that changed by more than the thresholds with
+    /// [`NetworkEvent::PilotUpdated`] events.
+    #[must_use]
+    pub fn pilot_updates(mut self, thresholds: UpdateThresholds) -> Self {
+        self.pilot_updates = Some(thresholds);
+        self
+    }
+
+    /// Only report pilots inside the area, along with
+    /// [`NetworkEvent::PilotEntered`] and [`NetworkEvent::PilotLeft`]
+    /// events for pilots crossing its edge.
+    ///
+    /// Pilots connecting are reported if they're inside the area in the
+    /// newer snapshot, pilots disconnecting if they were inside it in the
+    /// older one, and pilot updates if they were inside it in either.
+    /// Controllers and ATIS stations don't have positions, so their events
+    /// aren't affected.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::{
+    ///     boundaries::Geofence,
+    ///     diff::{diff_with_options, DiffOptions},
+    ///     live_api::Vatsim,
+    /// };
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap();
+    /// let previous = api.get_v3_data().await.unwrap();
+    /// // wait for the feed to update ...
+    /// let current = api.get_v3_data().await.unwrap();
+    /// let options = DiffOptions::default().geofence(Geofence::Radius {
+    ///     lat: 32.7338,
+    ///     lon: -117.1933,
+    ///     nm: 50.0,
+    /// });
+    /// for event in diff_with_options(&previous, &current, &options) {
+    ///     println!("{:?}: {}", event.kind(), event.callsign());
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn geofence(mut self, geofence: Geofence) -> Self {
+        self.geofence = Some(geofence);
+        self
+    }
+
+    /// Whether the pilot is inside the geofence, or there isn't one.
+    fn in_area(&self, pilot: &Pilot) -> bool {
+        self.geofence
+            .as_ref()
+            .is_none_or(|geofence| geofence.contains_pilot(pilot))
+    }
+}
+
+/// Compare two snapshots, returning the events that happened between them.
+///
+/// Clients are matched between the snapshots by their CID and callsign,
+/// so a user reconnecting under a different callsign results in both
+/// a disconnect and a connect event.
+///
+/// ATIS stations present in both snapshots are compared: a different
+/// information letter gives a [`NetworkEvent::AtisLetterChanged`], and
+/// otherwise different text gives a [`NetworkEvent::AtisTextChanged`].
+///
+/// This uses the default [`DiffOptions`].
+#[must_use]
+pub fn diff(old: &V3ResponseData, new: &V3ResponseData) -> Vec<NetworkEvent> {
+    diff_with_options(old, new, &DiffOptions::default())
+}
+
+/// Compare two snapshots with the options, returning the events that
+/// happened between them.
+///
+/// See [`diff`] for how clients are matched.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{
+///     diff::{diff_with_options, DiffOptions},
+///     live_api::Vatsim,
+///     models::Observers,
+/// };
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let previous = api.get_v3_data().await.unwrap();
+/// // wait for the feed to update ...
+/// let current = api.get_v3_data().await.unwrap();
+/// let options = DiffOptions::default().observers(Observers::Exclude);
+/// let events = diff_with_options(&previous, &current, &options);
+/// # }
+/// ```
+#[must_use]
+pub fn diff_with_options(
+    old: &V3ResponseData,
+    new: &V3ResponseData,
+    options: &DiffOptions,
+) -> Vec<NetworkEvent> {
+    let mut events = Vec::new();
+
+    let old_pilots = index(&old.pilots, |p| (p.cid, p.callsign.as_str()));
+    let new_pilots = index(&new.pilots, |p| (p.cid, p.callsign.as_str()));
+    events.extend(
+        new.pilots
+            .iter()
+            .filter(|p| !old_pilots.contains_key(&(p.cid, p.callsign.as_str())))
+            .filter(|p| options.in_area(p))
+            .map(|p| NetworkEvent::PilotConnected(p.clone())),
+    );
+    events.extend(
+        old.pilots
+            .iter()
+            .filter(|p| !new_pilots.contains_key(&(p.cid, p.callsign.as_str())))
+            .filter(|p| options.in_area(p))
+            .map(|p| NetworkEvent::PilotDisconnected(p.clone())),
+    );
+    for pilot in &new.pilots {
+        let Some(previous) = old_pilots.get(&(pilot.cid, pilot.callsign.as_str())) else {
+            continue;
+        };
+        let (was_inside, is_inside) = (options.in_area(previous), options.in_area(pilot));
+        if !was_inside && is_inside {
+            events.push(NetworkEvent::PilotEntered(pilot.clone()));
+        } else if was_inside && !is_inside {
+            events.push(NetworkEvent::PilotLeft(pilot.clone()));
+        }
+        let Some(thresholds) = &options.pilot_updates else {
+            continue;
+        };
+        if (was_inside || is_inside) && thresholds.is_significant(previous, pilot) {
+            events.push(NetworkEvent::PilotUpdated {
+                previous: Box::new((*previous).clone()),
+                pilot: pilot.clone(),
+            });
+        }
+    }
+
+    let old_controllers = index(&old.controllers, |c| (c.cid, c.callsign.as_str()));
+    let new_controllers = index(&new.controllers, |c| (c.cid, c.callsign.as_str()));
+    events.extend(
+        new.controllers_filtered(options.observers)
+            .filter(|c| !old_controllers.contains_key(&(c.cid, c.callsign.as_str())))
+            .map(|c| NetworkEvent::ControllerConnected(c.clone())),
+    );
+    events.extend(
+        old.controllers_filtered(options.observers)
+            .filter(|c| !new_controllers.contains_key(&(c.cid, c.callsign.as_str())))
+            .map(|c| NetworkEvent::ControllerDisconnected(c.clone())),
+    );
+
+    let old_atis = index(&old.atis, |a| (a.cid, a.callsign.as_str()));
+    for atis in &new.atis {
+        let Some(previous) = old_atis.get(&(atis.cid, atis.callsign.as_str())) else {
+            continue;
+        };
+        if previous.atis_code != atis.atis_code {
+            events.push(NetworkEvent::AtisLetterChanged {
+                airport: atis.airport().to_owned(),
+                previous: previous.atis_code.clone(),
+                atis: atis.clone(),
+            });
+        } else if previous.text_atis != atis.text_atis {
+            events.push(NetworkEvent::AtisTextChanged {
+                airport: atis.airport().to_owned(),
+                previous: previous.text_atis.clone(),
+                atis: atis.clone(),
+            });
+        }
+    }
+
+    events
+}
+
+/// Build a lookup map of the items by the key function.
+fn index<'a, T, F>(items: &'a [T], key: F) -> HashMap<(u64, &'a str), &'a T>
+where
+    F: Fn(&'a T) -> (u64, &'a str),
+{
+    items.iter().map(|item| (key(item), item)).collect()
+}
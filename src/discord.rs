@@ -0,0 +1,360 @@
+//! Formatting [`NetworkEvent`]s, emergency squawks, and myVATSIM events
+//! starting as [Discord webhook] messages.
+//!
+//! The structs here serialize to the JSON body that Discord expects,
+//! so they can be sent with any HTTP client.
+//!
+//! [Discord webhook]: https://discord.com/developers/docs/resources/webhook#execute-webhook
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::{diff::diff, discord::payloads_for_events, live_api::Vatsim};
+//!
+//! # async fn _do() {
+//! let api = Vatsim::new().await.unwrap();
+//! let previous = api.get_v3_data().await.unwrap();
+//! // wait for the feed to update ...
+//! let current = api.get_v3_data().await.unwrap();
+//! let client = reqwest::Client::new();
+//! for payload in payloads_for_events(&diff(&previous, &current)) {
+//!     client
+//!         .post("https://discord.com/api/webhooks/...")
+//!         .json(&payload)
+//!         .send()
+//!         .await
+//!         .unwrap();
+//! }
+//! # }
+//! ```
+
+use crate::{diff::NetworkEvent, emergencies::EmergencyEvent, models::Event};
+use serde::{Deserialize, Serialize};
+
+/// Discord's limit on the number of embeds in a single message.
+const MAX_EMBEDS: usize = 10;
+
+/// Embed color for clients connecting.
+const COLOR_CONNECTED: u32 = 0x002E_CC71;
+/// Embed color for clients disconnecting.
+const COLOR_DISCONNECTED: u32 = 0x0095_A5A6;
+/// Embed color for changes to connected clients.
+const COLOR_CHANGED: u32 = 0x0034_98DB;
+/// Embed color for pilots in an emergency.
+const COLOR_EMERGENCY: u32 = 0x00E7_4C3C;
+/// Embed color for events starting.
+const COLOR_EVENT: u32 = 0x009B_59B6;
+
+/// Body of a request to a Discord webhook.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DiscordWebhookPayload {
+    /// Plain message text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Override of the webhook's configured username.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Rich embeds; Discord allows at most 10.
+    pub embeds: Vec<DiscordEmbed>,
+}
+
+/// A single rich embed in a Discord message.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DiscordEmbed {
+    /// Embed title.
+    pub title: String,
+    /// Embed body text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Link the title points to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Sidebar color, as a RGB integer.
+    pub color: u32,
+    /// Name & value pairs shown in the embed.
+    pub fields: Vec<DiscordEmbedField>,
+    /// Large image shown below the fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<DiscordEmbedImage>,
+}
+
+/// An image in an embed.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DiscordEmbedImage {
+    /// URL of the image.
+    pub url: String,
+}
+
+/// A name & value pair in an embed.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DiscordEmbedField {
+    /// Field name.
+    pub name: String,
+    /// Field value.
+    pub value: String,
+    /// Whether Discord can show this field beside others.
+    pub inline: bool,
+}
+
+impl DiscordEmbedField {
+    /// Create a new inline field.
+    fn inline(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            value: value.to_owned(),
+            inline: true,
+        }
+    }
+}
+
+/// Build an embed describing the event.
+#[must_use]
+pub fn embed_for_event(event: &NetworkEvent) -> DiscordEmbed {
+    match event {
+        NetworkEvent::PilotConnected(pilot) => {
+            let mut fields = vec![DiscordEmbedField::inline("CID", &pilot.cid.to_string())];
+            if let Some(plan) = &pilot.flight_plan {
+                fields.push(DiscordEmbedField::inline("Aircraft", &plan.aircraft_short));
+                fields.push(DiscordEmbedField::inline(
+                    "Route",
+                    &format!("{} → {}", plan.departure, plan.arrival),
+                ));
+            }
+            DiscordEmbed {
+                title: format!("{} connected", pilot.callsign),
+                description: None,
+                url: None,
+                image: None,
+                color: COLOR_CONNECTED,
+                fields,
+            }
+        }
+        NetworkEvent::PilotDisconnected(pilot) => DiscordEmbed {
+            title: format!("{} disconnected", pilot.callsign),
+            description: None,
+            url: None,
+            image: None,
+            color: COLOR_DISCONNECTED,
+            fields: vec![DiscordEmbedField::inline("CID", &pilot.cid.to_string())],
+        },
+        NetworkEvent::PilotUpdated { pilot, .. } => DiscordEmbed {
+            title: format!("{} updated", pilot.callsign),
+            description: None,
+            url: None,
+            image: None,
+            color: COLOR_CHANGED,
+            fields: vec![
+                DiscordEmbedField::inline("Altitude", &format!("{} ft", pilot.altitude)),
+                DiscordEmbedField::inline("Groundspeed", &format!("{} kts", pilot.groundspeed)),
+                DiscordEmbedField::inline("Heading", &pilot.heading.to_string()),
+            ],
+        },
+        NetworkEvent::PilotEntered(pilot) => DiscordEmbed {
+            title: format!("{} entered the area", pilot.callsign),
+            description: None,
+            url: None,
+            image: None,
+            color: COLOR_CONNECTED,
+            fields: vec![DiscordEmbedField::inline("CID", &pilot.cid.to_string())],
+        },
+        NetworkEvent::PilotLeft(pilot) => DiscordEmbed {
+            title: format!("{} left the area", pilot.callsign),
+            description: None,
+            url: None,
+            image: None,
+            color: COLOR_DISCONNECTED,
+            fields: vec![DiscordEmbedField::inline("CID", &pilot.cid.to_string())],
+        },
+        NetworkEvent::ControllerConnected(controller) => DiscordEmbed {
+            title: format!("{} is now online", controller.callsign),
+            description: None,
+            url: None,
+            image: None,
+            color: COLOR_CONNECTED,
+            fields: vec![
+                DiscordEmbedField::inline("Controller", &controller.name),
+                DiscordEmbedField::inline("Frequency", &controller.frequency),
+            ],
+        },
+        NetworkEvent::ControllerDisconnected(controller) => DiscordEmbed {
+            title: format!("{} is now offline", controller.callsign),
+            description: None,
+            url: None,
+            image: None,
+            color: COLOR_DISCONNECTED,
+            fields: vec![DiscordEmbedField::inline("Controller", &controller.name)],
+        },
+        NetworkEvent::AtisLetterChanged { airport, atis, .. } => DiscordEmbed {
+            title: match &atis.atis_code {
+                Some(code) => format!("New ATIS at {airport}: information {code}"),
+                None => format!("New ATIS at {airport}"),
+            },
+            description: atis.text(),
+            url: None,
+            image: None,
+            color: COLOR_CHANGED,
+            fields: vec![DiscordEmbedField::inline("Frequency", &atis.frequency)],
+        },
+        NetworkEvent::AtisTextChanged { airport, atis, .. } => DiscordEmbed {
+            title: format!("ATIS updated at {airport}"),
+            description: atis.text(),
+            url: None,
+            image: None,
+            color: COLOR_CHANGED,
+            fields: vec![DiscordEmbedField::inline("Frequency", &atis.frequency)],
+        },
+    }
+}
+
+/// Build an embed describing a pilot starting or stopping squawking an
+/// emergency code.
+#[must_use]
+pub fn embed_for_emergency(event: &EmergencyEvent) -> DiscordEmbed {
+    match event {
+        EmergencyEvent::Started {
+            pilot,
+            code,
+            nearest_airport,
+        } => {
+            let mut fields = vec![
+                DiscordEmbedField::inline("CID", &pilot.cid.to_string()),
+                DiscordEmbedField::inline("Altitude", &format!("{} ft", pilot.altitude)),
+                DiscordEmbedField::inline("Groundspeed", &format!("{} kts", pilot.groundspeed)),
+            ];
+            if let Some(plan) = &pilot.flight_plan {
+                fields.push(DiscordEmbedField::inline(
+                    "Route",
+                    &format!("{} → {}", plan.departure, plan.arrival),
+                ));
+            }
+            if let Some(airport) = nearest_airport {
+                fields.push(DiscordEmbedField::inline(
+                    "Nearest airport",
+                    &format!("{} ({:.0} nm)", airport.identifier, airport.distance_nm),
+                ));
+            }
+            DiscordEmbed {
+                title: format!("{} is squawking {code}", pilot.callsign),
+                description: None,
+                url: None,
+                image: None,
+                color: COLOR_EMERGENCY,
+                fields,
+            }
+        }
+        EmergencyEvent::Stopped { pilot, code } => DiscordEmbed {
+            title: format!("{} is no longer squawking {code}", pilot.callsign),
+            description: None,
+            url: None,
+            image: None,
+            color: COLOR_DISCONNECTED,
+            fields: vec![DiscordEmbedField::inline("CID", &pilot.cid.to_string())],
+        },
+    }
+}
+
+/// Build an embed announcing a [myVATSIM event](crate::events) starting,
+/// linking to the event and showing its banner.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::{discord::embed_for_event_starting, models::Event};
+///
+/// let event: Event = serde_json::from_value(serde_json::json!({
+///     "id": 1,
+///     "type": "Event",
+///     "name": "Friday Night Ops",
+///     "link": "https://my.vatsim.net/events/friday-night-ops",
+///     "organisers": [],
+///     "airports": [{ "icao": "KSAN" }, { "icao": "KLAX" }],
+///     "routes": [],
+///     "start_time": "2024-05-03T23:00:00.000000Z",
+///     "end_time": "2024-05-04T03:00:00.000000Z",
+///     "short_description": "Busy skies over SoCal",
+///     "description": "",
+///     "banner": "https://example.com/banner.png"
+/// }))
+/// .unwrap();
+///
+/// let embed = embed_for_event_starting(&event);
+/// assert_eq!(embed.title, "Friday Night Ops is starting");
+/// assert_eq!(embed.url.as_deref(), Some(event.link.as_str()));
+/// assert_eq!(embed.fields[0].value, "KSAN, KLAX");
+/// assert_eq!(embed.fields[1].value, "2024-05-03 23:00 UTC");
+/// assert_eq!(embed.image.unwrap().url, "https://example.com/banner.png");
+/// ```
+#[must_use]
+pub fn embed_for_event_starting(event: &Event) -> DiscordEmbed {
+    let mut fields = Vec::new();
+    let airports = event.airport_icaos();
+    if !airports.is_empty() {
+        fields.push(DiscordEmbedField::inline("Airports", &airports.join(", ")));
+    }
+    fields.push(DiscordEmbedField::inline(
+        "Starts",
+        &event_time(&event.start_time),
+    ));
+    fields.push(DiscordEmbedField::inline(
+        "Ends",
+        &event_time(&event.end_time),
+    ));
+    DiscordEmbed {
+        title: format!("{} is starting", event.name),
+        description: Some(event.short_description.clone()).filter(|d| !d.is_empty()),
+        url: Some(event.link.clone()).filter(|link| !link.is_empty()),
+        image: event
+            .banner
+            .clone()
+            .filter(|banner| !banner.is_empty())
+            .map(|url| DiscordEmbedImage { url }),
+        color: COLOR_EVENT,
+        fields,
+    }
+}
+
+/// Build webhook payloads for the events.
+///
+/// Events are grouped into as few payloads as Discord's embed
+/// limit allows; no payloads are returned for no events.
+#[must_use]
+pub fn payloads_for_events(events: &[NetworkEvent]) -> Vec<DiscordWebhookPayload> {
+    payloads(events, embed_for_event)
+}
+
+/// Build webhook payloads for emergency squawk events, grouped like
+/// [`payloads_for_events`].
+#[must_use]
+pub fn payloads_for_emergencies(events: &[EmergencyEvent]) -> Vec<DiscordWebhookPayload> {
+    payloads(events, embed_for_emergency)
+}
+
+/// Build webhook payloads announcing myVATSIM events starting, grouped
+/// like [`payloads_for_events`].
+#[must_use]
+pub fn payloads_for_events_starting(events: &[Event]) -> Vec<DiscordWebhookPayload> {
+    payloads(events, embed_for_event_starting)
+}
+
+/// An event's `YYYY-MM-DDThh:mm:ss...Z` time as `YYYY-MM-DD hh:mm UTC`,
+/// or as it is if it's in another format.
+fn event_time(time: &str) -> String {
+    match (time.get(..10), time.get(10..11), time.get(11..16)) {
+        (Some(date), Some("T"), Some(clock)) if time.ends_with('Z') => {
+            format!("{date} {clock} UTC")
+        }
+        _ => time.to_owned(),
+    }
+}
+
+/// Build embeds for the events, grouped into as few payloads as Discord's
+/// embed limit allows.
+fn payloads<T>(events: &[T], embed: fn(&T) -> DiscordEmbed) -> Vec<DiscordWebhookPayload> {
+    events
+        .chunks(MAX_EMBEDS)
+        .map(|chunk| DiscordWebhookPayload {
+            embeds: chunk.iter().map(embed).collect(),
+            ..Default::default()
+        })
+        .collect()
+}
@@ -1,32 +1,112 @@
 //! Utilities for distance calculations and airport locations.
 //!
-//! This module includes a long collection of airport identifiers and lat/long
-//! values, available in a list [`AIRPORTS`] and `HashMap` [`AIRPORTS_MAP`].
-//!
 //! The included [haversine] function can be used to get
 //! the distance between two points' lat/long, wether those points
 //! be airports, pilots via the [`get_v3_data`] function, or a combination.
 //!
+//! With the `airports` feature (on by default), this module also includes
+//! a long collection of airport identifiers and lat/long values, available
+//! in a list [`AIRPORTS`] and `HashMap` [`AIRPORTS_MAP`]. Where binary size
+//! matters, disable the feature and load airport data at runtime with
+//! [`AirportDatabase::from_csv`] instead.
+//!
+//! Airports loaded at runtime can carry their IANA time zone, and with the
+//! `timezones` feature, [`Airport::local_time`] converts times to the
+//! airport's local time.
+//!
 //! [`get_v3_data`]: crate::live_api::Vatsim::get_v3_data
 
-use once_cell::sync::Lazy;
+use crate::errors::VatsimUtilError;
+#[cfg(feature = "timezones")]
+use chrono::{DateTime, FixedOffset, Offset, TimeZone, Utc};
+#[cfg(feature = "timezones")]
+use chrono_tz::Tz;
+#[cfg(feature = "airports")]
+use std::sync::LazyLock;
 use std::{collections::HashMap, f64::consts::PI};
 
+/// Mean radius of the Earth, in meters.
+const EARTH_RADIUS_M: f64 = 6371e3;
+
+/// Meters in a foot.
+const METERS_PER_FOOT: f64 = 0.3048;
+
 /// Raw airport data from the CSV file.
+#[cfg(feature = "airports")]
 const AIRPORT_DATA: &str = include_str!("airport_data.csv");
 
-/// Static airport data. Includes latitude and longitude.
+/// Airport data. Includes latitude and longitude, and the time zone
+/// where it's known.
 ///
 /// Primarily for use in determining pilot distance to airport
 /// via use of the `haversine` function in this module.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Airport {
     /// Airport identifier
-    pub identifier: &'static str,
+    pub identifier: String,
     /// Airport decimal latitude
     pub latitude: f64,
     /// Airport decimal longitude
     pub longitude: f64,
+    /// Name of the airport's IANA time zone, like `America/Los_Angeles`,
+    /// if it's known. Always `None` for the bundled airports, which don't
+    /// include time zones.
+    pub time_zone: Option<String>,
+}
+
+impl Airport {
+    /// The airport's time zone, if it's known and a valid IANA name.
+    #[cfg(feature = "timezones")]
+    #[must_use]
+    pub fn tz(&self) -> Option<Tz> {
+        self.time_zone.as_deref()?.parse().ok()
+    }
+
+    /// The time at the airport, if its time zone is known.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chrono::{TimeZone, Timelike, Utc};
+    /// use vatsim_utils::distance::AirportDatabase;
+    ///
+    /// let db = AirportDatabase::from_csv(
+    ///     "KSAN,32.7338,-117.1933,America/Los_Angeles\nKLAX,33.9416,-118.4085",
+    /// )
+    /// .unwrap();
+    /// let now = Utc.with_ymd_and_hms(2024, 7, 1, 18, 0, 0).unwrap();
+    ///
+    /// let ksan = db.airport("KSAN").unwrap();
+    /// assert_eq!(ksan.local_time(now).unwrap().hour(), 11);
+    /// assert_eq!(ksan.utc_offset(now).unwrap().local_minus_utc(), -7 * 3600);
+    /// assert!(db.airport("KLAX").unwrap().local_time(now).is_none());
+    /// ```
+    #[cfg(feature = "timezones")]
+    #[must_use]
+    pub fn local_time(&self, now: DateTime<Utc>) -> Option<DateTime<Tz>> {
+        Some(now.with_timezone(&self.tz()?))
+    }
+
+    /// The airport's offset from UTC at the time, including daylight
+    /// saving, if its time zone is known.
+    #[cfg(feature = "timezones")]
+    #[must_use]
+    pub fn utc_offset(&self, at: DateTime<Utc>) -> Option<FixedOffset> {
+        let time_zone = self.tz()?;
+        Some(time_zone.offset_from_utc_datetime(&at.naive_utc()).fix())
+    }
+}
+
+/// Parse a line of the bundled airport data.
+#[cfg(feature = "airports")]
+fn bundled_airport(line: &str) -> Airport {
+    let parts: Vec<_> = line.split(',').collect();
+    Airport {
+        identifier: (*parts.first().unwrap()).to_owned(),
+        latitude: parts.get(1).unwrap().parse().unwrap(),
+        longitude: parts.get(2).unwrap().parse().unwrap(),
+        time_zone: None,
+    }
 }
 
 /// List of included airport identifiers and locations.
@@ -43,18 +123,12 @@ pub struct Airport {
 ///
 /// println!("{}", AIRPORTS.get(0).unwrap().identifier);
 /// ```
-pub static AIRPORTS: Lazy<Vec<Airport>> = Lazy::new(|| {
+#[cfg(feature = "airports")]
+pub static AIRPORTS: LazyLock<Vec<Airport>> = LazyLock::new(|| {
     AIRPORT_DATA
         .split('\n')
         .filter(|line| !line.is_empty())
-        .map(|line| {
-            let parts: Vec<_> = line.split(',').collect();
-            Airport {
-                identifier: parts.first().unwrap(),
-                latitude: parts.get(1).unwrap().parse().unwrap(),
-                longitude: parts.get(2).unwrap().parse().unwrap(),
-            }
-        })
+        .map(bundled_airport)
         .collect()
 });
 
@@ -72,23 +146,188 @@ pub static AIRPORTS: Lazy<Vec<Airport>> = Lazy::new(|| {
 ///
 /// println!("{}", AIRPORTS_MAP.get("KSAN").unwrap().identifier);
 /// ```
-pub static AIRPORTS_MAP: Lazy<HashMap<&'static str, Airport>> = Lazy::new(|| {
+#[cfg(feature = "airports")]
+pub static AIRPORTS_MAP: LazyLock<HashMap<&'static str, Airport>> = LazyLock::new(|| {
     let mut m = HashMap::new();
     AIRPORT_DATA
         .split('\n')
         .filter(|line| !line.is_empty())
         .for_each(|line| {
-            let parts: Vec<_> = line.split(',').collect();
-            let airport = Airport {
-                identifier: parts.first().unwrap(),
-                latitude: parts.get(1).unwrap().parse().unwrap(),
-                longitude: parts.get(2).unwrap().parse().unwrap(),
-            };
-            let _ = m.insert(*parts.first().unwrap(), airport);
+            let identifier = line.split(',').next().unwrap();
+            let _ = m.insert(identifier, bundled_airport(line));
         });
     m
 });
 
+/// Airport locations loaded at runtime.
+///
+/// Data is in the same format as the bundled [`airport_data.csv`]: one
+/// airport per line, as `identifier,latitude,longitude`, optionally
+/// followed by the airport's IANA time zone, like `America/Los_Angeles`.
+/// The bundled data doesn't include time zones, so its airports'
+/// [`time_zone`](Airport::time_zone) is always `None`.
+///
+/// [`airport_data.csv`]: https://github.com/Celeo/vatsim_utils/blob/master/src/airport_data.csv
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::distance::AirportDatabase;
+///
+/// let db = AirportDatabase::from_csv("KSAN,32.7338,-117.1933\nKLAX,33.9416,-118.4085\n").unwrap();
+///
+/// assert_eq!(db.location("KSAN"), Some((32.7338, -117.1933)));
+/// assert_eq!(db.distance("KSAN", "KLAX"), Some(95.0));
+/// assert!(AirportDatabase::from_csv("KSAN,north,west").is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AirportDatabase {
+    airports: HashMap<String, Airport>,
+}
+
+impl AirportDatabase {
+    /// Parse airport data from CSV.
+    ///
+    /// Empty lines are skipped, as are empty time zones.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if a line doesn't have an identifier
+    /// followed by a numeric latitude and longitude, and at most a time
+    /// zone after them. Time zone names aren't checked.
+    pub fn from_csv(data: &str) -> Result<Self, VatsimUtilError> {
+        let mut airports = HashMap::new();
+        for (index, line) in data.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut parts = line.split(',').map(str::trim);
+            let (Some(identifier), Some(Ok(latitude)), Some(Ok(longitude)), time_zone, None) = (
+                parts.next(),
+                parts.next().map(str::parse),
+                parts.next().map(str::parse),
+                parts.next(),
+                parts.next(),
+            ) else {
+                return Err(VatsimUtilError::InvalidAirportData(index + 1));
+            };
+            let airport = Airport {
+                identifier: identifier.to_owned(),
+                latitude,
+                longitude,
+                time_zone: time_zone.filter(|tz| !tz.is_empty()).map(str::to_owned),
+            };
+            let _ = airports.insert(identifier.to_owned(), airport);
+        }
+        Ok(Self { airports })
+    }
+
+    /// The airport data bundled with the crate.
+    #[cfg(feature = "airports")]
+    #[must_use]
+    pub fn bundled() -> Self {
+        Self {
+            airports: AIRPORTS_MAP
+                .iter()
+                .map(|(identifier, airport)| ((*identifier).to_owned(), airport.clone()))
+                .collect(),
+        }
+    }
+
+    /// The airport, if it's known.
+    #[must_use]
+    pub fn airport(&self, identifier: &str) -> Option<&Airport> {
+        self.airports.get(identifier)
+    }
+
+    /// The `(latitude, longitude)` of the airport, if it's known.
+    #[must_use]
+    pub fn location(&self, identifier: &str) -> Option<(f64, f64)> {
+        self.airport(identifier).map(|a| (a.latitude, a.longitude))
+    }
+
+    /// The [haversine] distance between two airports, if both are known.
+    #[must_use]
+    pub fn distance(&self, from: &str, to: &str) -> Option<f64> {
+        let (lat1, lon1) = self.location(from)?;
+        let (lat2, lon2) = self.location(to)?;
+        Some(haversine(lat1, lon1, lat2, lon2))
+    }
+
+    /// The name of the airport's IANA time zone, if it's known.
+    #[must_use]
+    pub fn time_zone_name(&self, identifier: &str) -> Option<&str> {
+        self.airport(identifier)?.time_zone.as_deref()
+    }
+
+    /// The airport's time zone, if it's known and a valid IANA name.
+    #[cfg(feature = "timezones")]
+    #[must_use]
+    pub fn time_zone(&self, identifier: &str) -> Option<Tz> {
+        self.airport(identifier)?.tz()
+    }
+
+    /// The time at the airport, if it and its time zone are known.
+    ///
+    /// Shorthand for looking up the airport and calling
+    /// [`Airport::local_time`].
+    #[cfg(feature = "timezones")]
+    #[must_use]
+    pub fn local_time(&self, identifier: &str, now: DateTime<Utc>) -> Option<DateTime<Tz>> {
+        self.airport(identifier)?.local_time(now)
+    }
+
+    /// The airport's offset from UTC at the time, if it and its time zone
+    /// are known.
+    ///
+    /// Shorthand for looking up the airport and calling
+    /// [`Airport::utc_offset`].
+    #[cfg(feature = "timezones")]
+    #[must_use]
+    pub fn utc_offset(&self, identifier: &str, at: DateTime<Utc>) -> Option<FixedOffset> {
+        self.airport(identifier)?.utc_offset(at)
+    }
+
+    /// The airport closest to a point, with its distance in nautical
+    /// miles, if there are any airports.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use vatsim_utils::distance::AirportDatabase;
+    ///
+    /// let db = AirportDatabase::from_csv("KSAN,32.7338,-117.1933\nKLAX,33.9416,-118.4085\n").unwrap();
+    /// let (identifier, distance) = db.nearest(33.9, -118.4).unwrap();
+    ///
+    /// assert_eq!(identifier, "KLAX");
+    /// assert!(distance < 3.0);
+    /// ```
+    #[must_use]
+    pub fn nearest(&self, latitude: f64, longitude: f64) -> Option<(&str, f64)> {
+        self.airports
+            .values()
+            .map(|airport| {
+                (
+                    airport.identifier.as_str(),
+                    distance_nm(latitude, longitude, airport.latitude, airport.longitude),
+                )
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Number of airports.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.airports.len()
+    }
+
+    /// Whether there are no airports.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.airports.is_empty()
+    }
+}
+
 /// Calculate the Haversine Distance between two (lat & long) points.
 ///
 /// Originally from <https://www.movable-type.co.uk/scripts/latlong.html>.
@@ -108,6 +347,7 @@ pub static AIRPORTS_MAP: Lazy<HashMap<&'static str, Airport>> = Lazy::new(|| {
 /// Using lookups:
 ///
 /// ```rust
+/// # #[cfg(feature = "airports")] {
 /// use vatsim_utils::distance::{AIRPORTS, haversine};
 ///
 /// let ksan = AIRPORTS.iter().find(|airport| airport.identifier == "KSAN").unwrap();
@@ -115,10 +355,156 @@ pub static AIRPORTS_MAP: Lazy<HashMap<&'static str, Airport>> = Lazy::new(|| {
 /// let distance = haversine(ksan.latitude, ksan.longitude, klax.latitude, klax.longitude);
 ///
 /// assert_eq!(distance.round() as i64, 95);
+/// # }
 /// ```
 #[allow(clippy::must_use_candidate)]
 pub fn haversine(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
-    let r = 6371e3;
+    f64::round(distance_m(lat1, lon1, lat2, lon2) * 0.00054)
+}
+
+/// Unrounded great-circle distance between two points, in nautical miles.
+pub(crate) fn distance_nm(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    distance_m(lat1, lon1, lat2, lon2) / 1852.0
+}
+
+/// Calculate the straight-line distance, in nautical miles, between two
+/// points with altitudes in feet.
+///
+/// Unlike [haversine], this includes the difference in altitude, so it's
+/// the distance a radio signal or line of sight covers. The Earth's
+/// curvature is accounted for. Use [`slant_range_m`] for altitudes in
+/// meters.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::distance::slant_range;
+///
+/// // directly above a point, a mile up
+/// assert_eq!(slant_range(32.7338, -117.1933, 6076.0, 32.7338, -117.1933, 0.0).round(), 1.0);
+///
+/// // altitude matters less the further apart the points are: from
+/// // 35,000 ft over KSAN to KLAX is only a quarter mile further than
+/// // along the surface
+/// let range = slant_range(32.7338, -117.1933, 35_000.0, 33.9416, -118.4085, 0.0);
+/// assert_eq!(range.round(), 95.0);
+/// ```
+#[must_use]
+pub fn slant_range(
+    lat1: f64,
+    lon1: f64,
+    altitude1_ft: f64,
+    lat2: f64,
+    lon2: f64,
+    altitude2_ft: f64,
+) -> f64 {
+    slant_range_m(
+        lat1,
+        lon1,
+        altitude1_ft * METERS_PER_FOOT,
+        lat2,
+        lon2,
+        altitude2_ft * METERS_PER_FOOT,
+    ) / 1852.0
+}
+
+/// Calculate the straight-line distance, in meters, between two points
+/// with altitudes in meters.
+///
+/// See [`slant_range`] for feet and nautical miles.
+#[must_use]
+pub fn slant_range_m(
+    lat1: f64,
+    lon1: f64,
+    altitude1_m: f64,
+    lat2: f64,
+    lon2: f64,
+    altitude2_m: f64,
+) -> f64 {
+    let angle = distance_m(lat1, lon1, lat2, lon2) / EARTH_RADIUS_M;
+    let r1 = EARTH_RADIUS_M + altitude1_m;
+    let r2 = EARTH_RADIUS_M + altitude2_m;
+    (r1 * r1 + r2 * r2 - 2.0 * r1 * r2 * angle.cos())
+        .max(0.0)
+        .sqrt()
+}
+
+/// Calculate how far along the leg from `route_start` to `route_end` the
+/// point lies, in nautical miles: the distance from the start to the
+/// closest point to it on the leg's great circle. All points are
+/// `(latitude, longitude)`.
+///
+/// The result is negative when the point is behind the start, and more
+/// than the leg's length when it's past the end. Together with
+/// [`cross_track_distance`], it gives a pilot's progress along a single
+/// leg of their route.
+///
+/// Originally from <https://www.movable-type.co.uk/scripts/latlong.html>.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::distance::along_track_distance;
+///
+/// // due east along the equator, 30 NM along and just off the leg
+/// let along = along_track_distance((0.1, 0.5), (0.0, 0.0), (0.0, 1.0));
+///
+/// assert_eq!(along.round(), 30.0);
+/// ```
+#[must_use]
+pub fn along_track_distance(
+    point: (f64, f64),
+    route_start: (f64, f64),
+    route_end: (f64, f64),
+) -> f64 {
+    let (δ13, θ) = track_angles(point, route_start, route_end);
+    let δxt = f64::asin(δ13.sin() * θ.sin());
+    let δat = f64::acos((δ13.cos() / δxt.cos()).clamp(-1.0, 1.0));
+    (δat * EARTH_RADIUS_M / 1852.0).copysign(θ.cos())
+}
+
+/// Calculate how far the point is from the great circle through
+/// `route_start` and `route_end`, in nautical miles. All points are
+/// `(latitude, longitude)`.
+///
+/// The result is positive when the point is right of the track and
+/// negative when it's left.
+///
+/// Originally from <https://www.movable-type.co.uk/scripts/latlong.html>.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::distance::cross_track_distance;
+///
+/// // due east along the equator, 6 NM north of it
+/// let cross = cross_track_distance((0.1, 0.5), (0.0, 0.0), (0.0, 1.0));
+///
+/// assert_eq!(cross.round(), -6.0);
+/// ```
+#[must_use]
+pub fn cross_track_distance(
+    point: (f64, f64),
+    route_start: (f64, f64),
+    route_end: (f64, f64),
+) -> f64 {
+    let (δ13, θ) = track_angles(point, route_start, route_end);
+    f64::asin(δ13.sin() * θ.sin()) * EARTH_RADIUS_M / 1852.0
+}
+
+/// Angular distance from the start to the point, and the angle between
+/// the track and the bearing to the point, both in radians.
+fn track_angles(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> (f64, f64) {
+    let δ13 = distance_m(start.0, start.1, point.0, point.1) / EARTH_RADIUS_M;
+    let θ = (initial_bearing(start.0, start.1, point.0, point.1)
+        - initial_bearing(start.0, start.1, end.0, end.1))
+    .to_radians();
+    (δ13, θ)
+}
+
+/// Great-circle distance between two points, in meters.
+fn distance_m(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let r = EARTH_RADIUS_M;
     let φ1 = (lat1 * PI) / 180_f64;
     let φ2 = (lat2 * PI) / 180_f64;
     #[allow(non_snake_case)]
@@ -128,6 +514,60 @@ pub fn haversine(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     let a = f64::sin(Δφ / 2_f64) * f64::sin(Δφ / 2_f64)
         + f64::cos(φ1) * f64::cos(φ2) * f64::sin(Δλ / 2_f64) * f64::sin(Δλ / 2_f64);
     let c = 2_f64 * f64::atan2(f64::sqrt(a), f64::sqrt(1_f64 - a));
-    let d = r * c;
-    f64::round(d * 0.00054)
+    r * c
+}
+
+/// Calculate the initial bearing (in degrees true, 0..360) to follow
+/// along a great circle from the first point to the second.
+///
+/// Originally from <https://www.movable-type.co.uk/scripts/latlong.html>.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::distance::initial_bearing;
+///
+/// let bearing = initial_bearing(32.7338, -117.1933, 33.9416, -118.4085);
+///
+/// assert_eq!(bearing.round() as i64, 320);
+/// ```
+#[must_use]
+pub fn initial_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let φ1 = lat1.to_radians();
+    let φ2 = lat2.to_radians();
+    #[allow(non_snake_case)]
+    let Δλ = (lon2 - lon1).to_radians();
+    let y = Δλ.sin() * φ2.cos();
+    let x = φ1.cos() * φ2.sin() - φ1.sin() * φ2.cos() * Δλ.cos();
+    (f64::atan2(y, x).to_degrees() + 360.0) % 360.0
+}
+
+/// Calculate the point reached by travelling from a starting (lat & long)
+/// point along a great circle for the distance (in nautical miles) on the
+/// initial bearing (in degrees true).
+///
+/// Returns the destination's `(latitude, longitude)`, with the longitude
+/// normalized to -180..180.
+///
+/// Originally from <https://www.movable-type.co.uk/scripts/latlong.html>.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::distance::{destination_point, haversine};
+///
+/// let (lat, lon) = destination_point(32.7338, -117.1933, 90.0, 30.0);
+///
+/// assert_eq!(haversine(32.7338, -117.1933, lat, lon) as i64, 30);
+/// ```
+#[must_use]
+pub fn destination_point(lat: f64, lon: f64, bearing: f64, distance_nm: f64) -> (f64, f64) {
+    let δ = (distance_nm * 1852.0) / EARTH_RADIUS_M;
+    let θ = bearing.to_radians();
+    let φ1 = lat.to_radians();
+    let λ1 = lon.to_radians();
+    let φ2 = f64::asin(φ1.sin() * δ.cos() + φ1.cos() * δ.sin() * θ.cos());
+    let λ2 = λ1 + f64::atan2(θ.sin() * δ.sin() * φ1.cos(), δ.cos() - φ1.sin() * φ2.sin());
+    let lon2 = (λ2.to_degrees() + 540.0) % 360.0 - 180.0;
+    (φ2.to_degrees(), lon2)
 }
@@ -0,0 +1,155 @@
+//! Overriding how the crate's HTTP clients resolve host names.
+//!
+//! Useful in containers with broken DNS, or to pin `api.vatsim.net` and
+//! friends to particular addresses or a particular resolver. Set the
+//! overrides with [`set_dns_overrides`] before making requests: clients
+//! read them when they're created, which for the REST API functions is
+//! the first request, and for [`Vatsim`](crate::live_api::Vatsim) is
+//! when the struct is created.
+//!
+//! Static host mappings also apply to the blocking functions; a custom
+//! `Resolve` implementation, which needs the `async` feature, only
+//! applies to the async clients.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::net::SocketAddr;
+//! use vatsim_utils::dns::{reset_dns_overrides, set_dns_overrides, DnsOverrides};
+//!
+//! let addr: SocketAddr = "203.0.113.10:443".parse().unwrap();
+//! set_dns_overrides(DnsOverrides::default().host("api.vatsim.net", &[addr]));
+//! // ...
+//! reset_dns_overrides();
+//! ```
+
+#[cfg(feature = "async")]
+pub use hyper::client::connect::dns::Name;
+#[cfg(feature = "async")]
+pub use reqwest::dns::{Addrs, Resolve, Resolving};
+#[cfg(feature = "async")]
+use reqwest::ClientBuilder;
+#[cfg(feature = "async")]
+use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    sync::{LazyLock, PoisonError, RwLock},
+};
+
+/// Currently-configured overrides.
+static OVERRIDES: LazyLock<RwLock<DnsOverrides>> =
+    LazyLock::new(|| RwLock::new(DnsOverrides::default()));
+
+/// Host name resolution overrides.
+#[derive(Clone, Default)]
+pub struct DnsOverrides {
+    hosts: HashMap<String, Vec<SocketAddr>>,
+    #[cfg(feature = "async")]
+    resolver: Option<Arc<dyn Resolve>>,
+}
+
+impl fmt::Debug for DnsOverrides {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("DnsOverrides");
+        let _ = debug.field("hosts", &self.hosts);
+        #[cfg(feature = "async")]
+        let _ = debug.field("resolver", &self.resolver.as_ref().map(|_| "custom"));
+        debug.finish()
+    }
+}
+
+impl DnsOverrides {
+    /// Resolve `domain` to `addrs` instead of looking it up.
+    ///
+    /// The ports of the addresses are ignored; requests use the port
+    /// from the URL.
+    #[must_use]
+    pub fn host(mut self, domain: &str, addrs: &[SocketAddr]) -> Self {
+        let _ = self.hosts.insert(domain.to_owned(), addrs.to_vec());
+        self
+    }
+
+    /// Look up host names without a static mapping with `resolver`
+    /// instead of the system resolver.
+    #[cfg(feature = "async")]
+    #[must_use]
+    pub fn resolver<R: Resolve + 'static>(mut self, resolver: Arc<R>) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// The static mapping for `domain`, if there is one.
+    #[must_use]
+    pub fn addrs(&self, domain: &str) -> Option<&[SocketAddr]> {
+        self.hosts.get(domain).map(Vec::as_slice)
+    }
+}
+
+/// Get the current overrides.
+#[must_use]
+pub fn dns_overrides() -> DnsOverrides {
+    OVERRIDES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Replace the overrides for clients created from now on.
+pub fn set_dns_overrides(overrides: DnsOverrides) {
+    *OVERRIDES.write().unwrap_or_else(PoisonError::into_inner) = overrides;
+}
+
+/// Go back to resolving host names normally.
+pub fn reset_dns_overrides() {
+    set_dns_overrides(DnsOverrides::default());
+}
+
+/// Wrapper to pass the stored trait object to reqwest.
+#[cfg(feature = "async")]
+struct Shared(Arc<dyn Resolve>);
+
+#[cfg(feature = "async")]
+impl Resolve for Shared {
+    fn resolve(&self, name: Name) -> Resolving {
+        self.0.resolve(name)
+    }
+}
+
+/// Apply the current overrides to an async client.
+#[cfg(feature = "async")]
+pub(crate) fn apply(mut builder: ClientBuilder) -> ClientBuilder {
+    let overrides = dns_overrides();
+    for (domain, addrs) in &overrides.hosts {
+        builder = builder.resolve_to_addrs(domain, addrs);
+    }
+    if let Some(resolver) = overrides.resolver {
+        builder = builder.dns_resolver(Arc::new(Shared(resolver)));
+    }
+    builder
+}
+
+/// Apply the current static mappings to a blocking agent.
+#[cfg(feature = "sync-ureq")]
+pub(crate) fn apply_blocking(builder: ureq::AgentBuilder) -> ureq::AgentBuilder {
+    let overrides = dns_overrides();
+    if overrides.hosts.is_empty() {
+        return builder;
+    }
+    builder.resolver(move |netloc: &str| {
+        let mapped = netloc.rsplit_once(':').and_then(|(host, port)| {
+            let port = port.parse().ok()?;
+            let addrs = overrides.addrs(host)?;
+            Some(
+                addrs
+                    .iter()
+                    .map(move |addr| SocketAddr::new(addr.ip(), port)),
+            )
+        });
+        match mapped {
+            Some(addrs) => Ok(addrs.collect()),
+            None => std::net::ToSocketAddrs::to_socket_addrs(netloc).map(Iterator::collect),
+        }
+    })
+}
@@ -0,0 +1,224 @@
+//! Checking whether the crate's models still match VATSIM's APIs.
+//!
+//! [`check_schema_drift`] fetches each endpoint that the crate knows about
+//! and compares the fields in the raw JSON against those in the models,
+//! reporting any that VATSIM has added or removed. Run it from a periodic
+//! health check to find out that the crate needs updating before data
+//! goes missing.
+
+use crate::{
+    config,
+    errors::{Endpoint, VatsimUtilError},
+    http,
+    models::{
+        AtcSessionEntry, ConnectionEntry, Facility, PaginatedResponse, RatingsTimeData, Region,
+        RestFlightPlans, Status, TransceiverResponseEntry, UserRatingsSimple, V3ResponseData,
+    },
+    urls,
+};
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Differences between an endpoint's JSON and the crate's model for it.
+///
+/// Field paths are dot-separated, with `[]` marking array items, like
+/// `pilots[].flight_plan.route`.
+#[derive(Debug)]
+pub struct DriftReport {
+    /// The endpoint that was checked.
+    pub endpoint: Endpoint,
+    /// Fields in the JSON that the model doesn't have.
+    pub unexpected: BTreeSet<String>,
+    /// Fields in the model that weren't in the JSON.
+    pub missing: BTreeSet<String>,
+    /// Error fetching the JSON or parsing it into the model, in which
+    /// case the field sets are empty.
+    pub error: Option<VatsimUtilError>,
+}
+
+impl DriftReport {
+    /// Whether the model matches the JSON.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.unexpected.is_empty() && self.missing.is_empty() && self.error.is_none()
+    }
+}
+
+/// Fetch each known endpoint and compare its JSON to the crate's models.
+///
+/// The REST API endpoints for a single user are only checked if a CID is
+/// supplied; it should belong to a user with some history, as fields in
+/// empty lists can't be compared. The live data endpoints use the first
+/// URL listed in the status file.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::drift::check_schema_drift;
+///
+/// # async fn _do() {
+/// for report in check_schema_drift(Some(1234567890)).await {
+///     if !report.is_ok() {
+///         println!(
+///             "{}: unexpected {:?}, missing {:?}, error {:?}",
+///             report.endpoint, report.unexpected, report.missing, report.error
+///         );
+///     }
+/// }
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// Will panic if the HTTP user agent cannot be constructed, which
+/// should never happen.
+pub async fn check_schema_drift(cid: Option<u64>) -> Vec<DriftReport> {
+    let client = config::build_client();
+    let endpoints = urls::endpoints();
+    let mut reports = Vec::new();
+
+    let (report, status) =
+        check_parsed::<Status>(&client, Endpoint::Status, &endpoints.status).await;
+    reports.push(report);
+    if let Some(status) = status {
+        if let Some(url) = status.data.v3.first() {
+            reports.push(check::<V3ResponseData>(&client, Endpoint::V3Feed, url).await);
+        }
+        if let Some(url) = status.data.transceivers.first() {
+            reports.push(
+                check::<Vec<TransceiverResponseEntry>>(&client, Endpoint::Transceivers, url).await,
+            );
+        }
+    }
+
+    let api = &endpoints.api;
+    reports.push(
+        check::<Vec<Region>>(&client, Endpoint::Regions, &format!("{api}/api/regions/")).await,
+    );
+    reports.push(
+        check::<Vec<Facility>>(
+            &client,
+            Endpoint::Facilities,
+            &format!("{api}/api/facilities/"),
+        )
+        .await,
+    );
+    if let Some(cid) = cid {
+        let base = format!("{api}/api/ratings/{cid}");
+        reports.push(
+            check::<UserRatingsSimple>(&client, Endpoint::Ratings, &format!("{base}/")).await,
+        );
+        reports.push(
+            check::<RatingsTimeData>(
+                &client,
+                Endpoint::RatingTimes,
+                &format!("{base}/rating_times"),
+            )
+            .await,
+        );
+        reports.push(
+            check::<PaginatedResponse<ConnectionEntry>>(
+                &client,
+                Endpoint::Connections,
+                &format!("{base}/connections"),
+            )
+            .await,
+        );
+        reports.push(
+            check::<PaginatedResponse<AtcSessionEntry>>(
+                &client,
+                Endpoint::AtcSessions,
+                &format!("{base}/atcsessions/"),
+            )
+            .await,
+        );
+        reports.push(
+            check::<PaginatedResponse<RestFlightPlans>>(
+                &client,
+                Endpoint::FlightPlans,
+                &format!("{base}/flight_plans"),
+            )
+            .await,
+        );
+    }
+    reports
+}
+
+/// Fetch the URL and compare its JSON to the model.
+async fn check<T>(client: &Client, endpoint: Endpoint, url: &str) -> DriftReport
+where
+    T: DeserializeOwned + Serialize,
+{
+    check_parsed::<T>(client, endpoint, url).await.0
+}
+
+/// Fetch the URL and compare its JSON to the model, also returning the
+/// parsed model if it could be.
+async fn check_parsed<T>(client: &Client, endpoint: Endpoint, url: &str) -> (DriftReport, Option<T>)
+where
+    T: DeserializeOwned + Serialize,
+{
+    let mut report = DriftReport {
+        endpoint,
+        unexpected: BTreeSet::new(),
+        missing: BTreeSet::new(),
+        error: None,
+    };
+    let raw: Value = match http::fetch_json(endpoint, client.get(url)).await {
+        Ok(raw) => raw,
+        Err(e) => {
+            report.error = Some(e);
+            return (report, None);
+        }
+    };
+    let parsed: T = match serde_json::from_value(raw.clone()) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            report.error = Some(e.into());
+            return (report, None);
+        }
+    };
+    match serde_json::to_value(&parsed) {
+        Ok(modelled) => compare(&raw, &modelled, "", &mut report),
+        Err(e) => report.error = Some(e.into()),
+    }
+    (report, Some(parsed))
+}
+
+/// Record the differences in fields between the raw and modelled JSON.
+fn compare(raw: &Value, modelled: &Value, path: &str, report: &mut DriftReport) {
+    match (raw, modelled) {
+        (Value::Object(raw), Value::Object(modelled)) => {
+            for (key, value) in raw {
+                let field = join(path, key);
+                match modelled.get(key) {
+                    Some(modelled_value) => compare(value, modelled_value, &field, report),
+                    None => {
+                        let _ = report.unexpected.insert(field);
+                    }
+                }
+            }
+            for key in modelled.keys().filter(|key| !raw.contains_key(*key)) {
+                let _ = report.missing.insert(join(path, key));
+            }
+        }
+        (Value::Array(raw), Value::Array(modelled)) => {
+            let items = format!("{path}[]");
+            for (raw_item, modelled_item) in raw.iter().zip(modelled) {
+                compare(raw_item, modelled_item, &items, report);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Add a key to a field path.
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{path}.{key}")
+    }
+}
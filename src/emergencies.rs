@@ -0,0 +1,266 @@
+//! Watching for pilots squawking emergency codes.
+//!
+//! Feed each snapshot to [`EmergencyWatcher::update`] as it's fetched to
+//! get an [`EmergencyEvent`] when a pilot starts squawking 7500, 7600, or
+//! 7700, and another when they stop.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::{emergencies::{EmergencyEvent, EmergencyWatcher}, live_api::Vatsim};
+//!
+//! # async fn _do() {
+//! let api = Vatsim::new().await.unwrap();
+//! let mut watcher = EmergencyWatcher::new();
+//! loop {
+//!     let data = api.get_v3_data().await.unwrap();
+//!     for event in watcher.update(&data) {
+//!         if let EmergencyEvent::Started { pilot, code, .. } = event {
+//!             println!("{} is squawking {code}", pilot.callsign);
+//!         }
+//!     }
+//!     // wait for the feed to update ...
+//! }
+//! # }
+//! ```
+
+use crate::{
+    distance::AirportDatabase,
+    models::{Pilot, V3ResponseData},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt};
+
+/// One of the transponder codes reserved for emergencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmergencyCode {
+    /// 7500, unlawful interference.
+    Hijack,
+    /// 7600, lost communications.
+    RadioFailure,
+    /// 7700, general emergency.
+    Emergency,
+}
+
+impl EmergencyCode {
+    /// The emergency code a transponder is set to, if it's one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use vatsim_utils::emergencies::EmergencyCode;
+    ///
+    /// assert_eq!(EmergencyCode::from_squawk("7700"), Some(EmergencyCode::Emergency));
+    /// assert_eq!(EmergencyCode::from_squawk("2200"), None);
+    /// ```
+    #[must_use]
+    pub fn from_squawk(squawk: &str) -> Option<Self> {
+        match squawk.trim() {
+            "7500" => Some(Self::Hijack),
+            "7600" => Some(Self::RadioFailure),
+            "7700" => Some(Self::Emergency),
+            _ => None,
+        }
+    }
+
+    /// The four-digit transponder code.
+    #[must_use]
+    pub fn squawk(self) -> &'static str {
+        match self {
+            Self::Hijack => "7500",
+            Self::RadioFailure => "7600",
+            Self::Emergency => "7700",
+        }
+    }
+}
+
+impl fmt::Display for EmergencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let meaning = match self {
+            Self::Hijack => "hijack",
+            Self::RadioFailure => "radio failure",
+            Self::Emergency => "emergency",
+        };
+        write!(f, "{} ({meaning})", self.squawk())
+    }
+}
+
+/// The closest known airport to a pilot.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct NearestAirport {
+    /// The airport's identifier.
+    pub identifier: String,
+    /// Distance to the airport, in nautical miles.
+    pub distance_nm: f64,
+}
+
+/// A pilot starting or stopping squawking an emergency code.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EmergencyEvent {
+    /// A pilot started squawking an emergency code, or connected while
+    /// squawking one.
+    Started {
+        /// The pilot.
+        pilot: Pilot,
+        /// The code being squawked.
+        code: EmergencyCode,
+        /// The closest airport, if any airport locations are known.
+        nearest_airport: Option<NearestAirport>,
+    },
+    /// A pilot stopped squawking an emergency code, or disconnected while
+    /// squawking one.
+    Stopped {
+        /// The pilot, as last seen.
+        pilot: Pilot,
+        /// The code that was being squawked.
+        code: EmergencyCode,
+    },
+}
+
+impl EmergencyEvent {
+    /// The pilot this event is about.
+    #[must_use]
+    pub fn pilot(&self) -> &Pilot {
+        match self {
+            Self::Started { pilot, .. } | Self::Stopped { pilot, .. } => pilot,
+        }
+    }
+}
+
+/// Follows pilots' transponder codes across snapshots.
+///
+/// Pilots are identified by their CID and callsign. Pilots already
+/// squawking an emergency code in the first snapshot produce
+/// [`EmergencyEvent::Started`] events.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "fixtures")] {
+/// use vatsim_utils::{
+///     distance::AirportDatabase,
+///     emergencies::{EmergencyCode, EmergencyEvent, EmergencyWatcher},
+///     fixtures,
+/// };
+///
+/// let mut data = fixtures::v3_data();
+/// let (lat, lon) = (data.pilots[0].latitude, data.pilots[0].longitude);
+/// let airports = AirportDatabase::from_csv(&format!("TEST,{lat},{lon}")).unwrap();
+/// let mut watcher = EmergencyWatcher::new().airports(airports);
+/// for pilot in &mut data.pilots {
+///     pilot.transponder = String::from("2200");
+/// }
+/// assert!(watcher.update(&data).is_empty());
+///
+/// data.pilots[0].transponder = String::from("7600");
+/// let events = watcher.update(&data);
+/// assert!(matches!(
+///     &events[..],
+///     [EmergencyEvent::Started { code: EmergencyCode::RadioFailure, nearest_airport: Some(airport), .. }]
+///         if airport.identifier == "TEST",
+/// ));
+///
+/// data.pilots[0].transponder = String::from("2200");
+/// let events = watcher.update(&data);
+/// assert!(matches!(&events[..], [EmergencyEvent::Stopped { .. }]));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct EmergencyWatcher {
+    airports: AirportDatabase,
+    squawking: HashMap<(u64, String), (EmergencyCode, Pilot)>,
+}
+
+impl Default for EmergencyWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmergencyWatcher {
+    /// Create a new watcher.
+    ///
+    /// With the `airports` feature, the bundled airport data is used to
+    /// find the nearest airport to pilots in an emergency.
+    #[must_use]
+    pub fn new() -> Self {
+        #[cfg(feature = "airports")]
+        let airports = AirportDatabase::bundled();
+        #[cfg(not(feature = "airports"))]
+        let airports = AirportDatabase::default();
+        Self {
+            airports,
+            squawking: HashMap::new(),
+        }
+    }
+
+    /// Set the airport locations used to find the nearest airport.
+    #[must_use]
+    pub fn airports(mut self, airports: AirportDatabase) -> Self {
+        self.airports = airports;
+        self
+    }
+
+    /// Update from a new snapshot, returning pilots that started and
+    /// stopped squawking emergency codes since the last one.
+    ///
+    /// A pilot changing from one emergency code to another stops the old
+    /// one and starts the new one.
+    pub fn update(&mut self, data: &V3ResponseData) -> Vec<EmergencyEvent> {
+        let mut previous = std::mem::take(&mut self.squawking);
+        let mut started = Vec::new();
+        let mut stopped = Vec::new();
+        for pilot in &data.pilots {
+            let key = (pilot.cid, pilot.callsign.clone());
+            let was = previous.remove(&key);
+            let code = EmergencyCode::from_squawk(&pilot.transponder);
+            match (was, code) {
+                (Some((was_code, _)), Some(code)) if was_code == code => {}
+                (was, code) => {
+                    if let Some((was_code, _)) = was {
+                        stopped.push(EmergencyEvent::Stopped {
+                            pilot: pilot.clone(),
+                            code: was_code,
+                        });
+                    }
+                    if let Some(code) = code {
+                        started.push(EmergencyEvent::Started {
+                            pilot: pilot.clone(),
+                            code,
+                            nearest_airport: self.nearest_airport(pilot),
+                        });
+                    }
+                }
+            }
+            if let Some(code) = code {
+                let _ = self.squawking.insert(key, (code, pilot.clone()));
+            }
+        }
+        let mut disconnected: Vec<_> = previous.into_values().collect();
+        disconnected.sort_by(|a, b| a.1.callsign.cmp(&b.1.callsign));
+        stopped.extend(
+            disconnected
+                .into_iter()
+                .map(|(code, pilot)| EmergencyEvent::Stopped { pilot, code }),
+        );
+        stopped.extend(started);
+        stopped
+    }
+
+    /// Pilots squawking emergency codes in the latest snapshot.
+    pub fn squawking(&self) -> impl Iterator<Item = (&Pilot, EmergencyCode)> {
+        self.squawking.values().map(|(code, pilot)| (pilot, *code))
+    }
+
+    /// The closest known airport to the pilot.
+    fn nearest_airport(&self, pilot: &Pilot) -> Option<NearestAirport> {
+        self.airports
+            .nearest(pilot.latitude, pilot.longitude)
+            .map(|(identifier, distance_nm)| NearestAirport {
+                identifier: identifier.to_owned(),
+                distance_nm,
+            })
+    }
+}
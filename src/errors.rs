@@ -2,21 +2,268 @@
 //!
 //! Most commonly involved with HTTP API access issues.
 
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, LazyLock, PoisonError, RwLock,
+    },
+};
 use thiserror::Error;
 
+/// Callback for errors; see [`set_error_hook`].
+type ErrorHook = dyn Fn(&VatsimUtilError, &ErrorContext) + Send + Sync;
+
+/// Currently-registered error hook.
+static ERROR_HOOK: LazyLock<RwLock<Option<Arc<ErrorHook>>>> = LazyLock::new(|| RwLock::new(None));
+
+/// Last-used request ID.
+static LAST_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Identifier for a single HTTP request that the crate made, unique
+/// within the process.
+///
+/// The crate logs each request and its response at the debug level with
+/// the ID, and errors from a response carry it, so an error logged by the
+/// application can be matched up with the request that caused it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RequestId(u64);
+
+impl RequestId {
+    /// Get an unused ID.
+    pub(crate) fn next() -> Self {
+        Self(LAST_REQUEST_ID.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "req-{}", self.0)
+    }
+}
+
+/// The upstream API endpoints that the crate calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Endpoint {
+    /// The status file listing the live data URLs.
+    Status,
+    /// The V3 live data feed.
+    V3Feed,
+    /// The live transceivers feed.
+    Transceivers,
+    /// The sweatbox servers list.
+    SweatboxServers,
+    /// A user's ratings.
+    Ratings,
+    /// A user's time spent at each rating.
+    RatingTimes,
+    /// A user's connection history.
+    Connections,
+    /// A user's ATC session history.
+    AtcSessions,
+    /// A user's flight plan history.
+    FlightPlans,
+    /// The list of regions.
+    Regions,
+    /// The list of divisions.
+    Divisions,
+    /// The list of online facilities.
+    Facilities,
+    /// A facility's session history.
+    FacilityHistory,
+    /// The slurper's online check for a user.
+    Slurper,
+    /// METARs for a station.
+    Metar,
+    /// ATC bookings.
+    Bookings,
+    /// Events from myVATSIM.
+    Events,
+    /// A user-configured webhook.
+    Webhook,
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Status => "status",
+            Self::V3Feed => "v3_feed",
+            Self::Transceivers => "transceivers",
+            Self::SweatboxServers => "sweatbox_servers",
+            Self::Ratings => "ratings",
+            Self::RatingTimes => "rating_times",
+            Self::Connections => "connections",
+            Self::AtcSessions => "atc_sessions",
+            Self::FlightPlans => "flight_plans",
+            Self::Regions => "regions",
+            Self::Divisions => "divisions",
+            Self::Facilities => "facilities",
+            Self::FacilityHistory => "facility_history",
+            Self::Slurper => "slurper",
+            Self::Metar => "metar",
+            Self::Bookings => "bookings",
+            Self::Events => "events",
+            Self::Webhook => "webhook",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The HTTP methods of the requests that the crate makes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Method {
+    /// `GET`
+    Get,
+    /// `POST`
+    Post,
+    /// `PUT`
+    Put,
+    /// `PATCH`
+    Patch,
+    /// `DELETE`
+    Delete,
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+            Self::Put => "PUT",
+            Self::Patch => "PATCH",
+            Self::Delete => "DELETE",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Details of a failed HTTP request, for debugging.
+#[derive(Debug, Clone)]
+pub struct ResponseContext {
+    /// The request's ID.
+    pub request_id: RequestId,
+    /// The endpoint that was called.
+    pub endpoint: Endpoint,
+    /// The request's HTTP method.
+    pub method: Method,
+    /// The request's full URL.
+    pub url: String,
+    /// The response's body, truncated if long.
+    pub body: String,
+}
+
 /// Errors that can occur processing data in this crate.
+///
+/// New variants may be added in any release, so prefer the helper
+/// methods like [`is_retryable`](Self::is_retryable) over matching
+/// on specific variants where possible.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum VatsimUtilError {
     /// Error that can be returned by any function that makes HTTP
-    /// calls to external resources and receives an error response code.
-    #[error("Invalid HTTP status code received: {0}")]
-    InvalidStatusCode(u16),
-    /// Error for if the underlying `reqwest::Client` threw an error.
+    /// calls to external resources and receives a 4xx response code,
+    /// other than 429.
+    #[error("HTTP client error {status} from {} ({} {}) [{}]", .context.endpoint, .context.method, .context.url, .context.request_id)]
+    ClientError {
+        /// The response's status code.
+        status: u16,
+        /// Details of the request and response.
+        context: ResponseContext,
+    },
+    /// Error that can be returned by any function that makes HTTP
+    /// calls to external resources and receives a 5xx response code.
+    #[error("HTTP server error {status} from {} ({} {}) [{}]", .context.endpoint, .context.method, .context.url, .context.request_id)]
+    ServerError {
+        /// The response's status code.
+        status: u16,
+        /// Details of the request and response.
+        context: ResponseContext,
+    },
+    /// Error that can be returned by any function that makes HTTP
+    /// calls to external resources and receives a 3xx response code
+    /// that wasn't followed.
+    #[error("HTTP redirect {status} from {} ({} {}) [{}]", .context.endpoint, .context.method, .context.url, .context.request_id)]
+    Redirect {
+        /// The response's status code.
+        status: u16,
+        /// Details of the request and response.
+        context: ResponseContext,
+    },
+    /// Error that can be returned by any function that makes HTTP
+    /// calls to external resources and is told to slow down with a
+    /// 429 response code.
+    #[error("Rate limited by {endpoint} ({method} {url}) [{request_id}]")]
+    RateLimited {
+        /// The request's ID.
+        request_id: RequestId,
+        /// The endpoint that was called.
+        endpoint: Endpoint,
+        /// The request's HTTP method.
+        method: Method,
+        /// The request's full URL.
+        url: String,
+        /// How long the server asked to wait before trying again, from
+        /// the `Retry-After` header, if it was sent.
+        retry_after: Option<std::time::Duration>,
+    },
+    /// Error for live data that's older than the maximum age set with
+    /// [`Vatsim::max_age`](crate::live_api::Vatsim::max_age), as happens
+    /// when a mirror stops updating.
+    #[error("Live data last updated at {updated} is {} seconds old", .age.as_secs())]
+    StaleData {
+        /// The data's `general.update_timestamp`.
+        updated: String,
+        /// How old the data is.
+        age: std::time::Duration,
+    },
+    /// Error for if `reqwest` couldn't build a client or request, like
+    /// for an invalid proxy URL.
+    #[cfg(feature = "async")]
     #[error(transparent)]
-    ReqwestError(#[from] reqwest::Error),
+    ReqwestError(reqwest::Error),
+    /// Error from being unable to connect to or communicate with the
+    /// server, or to read its response, using the async functions.
+    #[cfg(feature = "async")]
+    #[error("Network error calling {endpoint}")]
+    Network {
+        /// The endpoint that was called.
+        endpoint: Endpoint,
+        /// The underlying transport error.
+        source: reqwest::Error,
+    },
     /// Error for being unable to parse JSON from anywhere.
     #[error("Failed to serialize/deserialize JSON")]
     FailedJsonParse(#[from] serde_json::Error),
+    /// Error for a response's JSON not matching the crate's models,
+    /// with the path to the field that couldn't be parsed, like
+    /// `pilots[231].qnh_mb`.
+    #[error("Failed to parse JSON from {endpoint} at {path} [{request_id}]")]
+    JsonSchemaMismatch {
+        /// The request's ID.
+        request_id: RequestId,
+        /// The endpoint that was called.
+        endpoint: Endpoint,
+        /// The request's full URL.
+        url: String,
+        /// Path to the field in the JSON that couldn't be parsed.
+        path: String,
+        /// The underlying parsing error.
+        source: serde_json::Error,
+    },
+    /// Error for being unable to read or write data, such as
+    /// when exporting to a file.
+    #[error("Failed to read/write data")]
+    IoError(#[from] std::io::Error),
+    /// Error for being unable to encode data as `MessagePack`.
+    #[cfg(feature = "msgpack")]
+    #[error("Failed to serialize MessagePack")]
+    FailedMsgpackEncode(#[from] rmp_serde::encode::Error),
+    /// Error for being unable to decode `MessagePack` data.
+    #[cfg(feature = "msgpack")]
+    #[error("Failed to deserialize MessagePack")]
+    FailedMsgpackDecode(#[from] rmp_serde::decode::Error),
     /// Error that could theoretically be returned from constructing
     /// an instance of the [`Vatsim`](crate::live_api::Vatsim) struct
     /// via it's `new` function.
@@ -27,4 +274,285 @@ pub enum VatsimUtilError {
     /// via it's `new` function.
     #[error("Could not retrieve a transceivers URL from the status page")]
     NoTransceiversUrl(),
+    /// Error for getting the sweatbox servers when the status page
+    /// didn't list a URL for them.
+    #[error("Could not retrieve a sweatbox servers URL from the status page")]
+    NoSweatboxServersUrl(),
+    /// Error for airport data that isn't in the `identifier,latitude,longitude`
+    /// format, with the line number that couldn't be parsed.
+    #[error("Invalid airport data on line {0}")]
+    InvalidAirportData(usize),
+    /// Error for a magnetic model coefficient file that couldn't be
+    /// parsed, with the line number.
+    #[cfg(feature = "magnetic")]
+    #[error("Invalid magnetic model data on line {0}")]
+    InvalidMagneticModel(usize),
+    /// Error for a booking that can't be sent, with the reason.
+    #[error("Invalid booking: {0}")]
+    InvalidBooking(String),
+    /// Error for a URL that couldn't be built, with the reason.
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+    /// Error for an environment variable read by
+    /// [`init_from_env`](crate::config::init_from_env) that couldn't
+    /// be parsed.
+    #[error("Invalid value {value:?} for environment variable {name}")]
+    InvalidEnvVar {
+        /// The variable's name.
+        name: &'static str,
+        /// The variable's value.
+        value: String,
+    },
+    /// Error from being unable to connect to or communicate with the
+    /// server, using the [`blocking`](crate::blocking) functions.
+    #[cfg(feature = "sync-ureq")]
+    #[error(transparent)]
+    UreqTransport(#[from] Box<ureq::Transport>),
+}
+
+impl VatsimUtilError {
+    /// Whether trying the same call again later might succeed.
+    ///
+    /// This is the case for server errors, rate limiting, stale data,
+    /// and network problems.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::ServerError { .. } | Self::RateLimited { .. } | Self::StaleData { .. }
+        ) || self.is_network()
+    }
+
+    /// Whether the error came from being unable to connect to or
+    /// communicate with the server.
+    #[must_use]
+    pub fn is_network(&self) -> bool {
+        match self {
+            #[cfg(feature = "async")]
+            Self::Network { .. } => true,
+            #[cfg(feature = "sync-ureq")]
+            Self::UreqTransport(_) => true,
+            _ => false,
+        }
+    }
+
+    /// The endpoint that the error came from, if it came from an HTTP
+    /// request.
+    ///
+    /// Errors from the blocking functions' transport don't carry the
+    /// endpoint; use the [error hook](set_error_hook) to get it for those.
+    #[must_use]
+    pub fn endpoint(&self) -> Option<Endpoint> {
+        match self {
+            Self::ClientError { context, .. }
+            | Self::ServerError { context, .. }
+            | Self::Redirect { context, .. } => Some(context.endpoint),
+            Self::RateLimited { endpoint, .. } | Self::JsonSchemaMismatch { endpoint, .. } => {
+                Some(*endpoint)
+            }
+            Self::StaleData { .. } => Some(Endpoint::V3Feed),
+            #[cfg(feature = "async")]
+            Self::Network { endpoint, .. } => Some(*endpoint),
+            _ => None,
+        }
+    }
+
+    /// The ID of the request that the error came from, if it came from
+    /// an HTTP response.
+    ///
+    /// Network errors don't carry the ID; use the
+    /// [error hook](set_error_hook) to get it for those.
+    #[must_use]
+    pub fn request_id(&self) -> Option<RequestId> {
+        match self {
+            Self::ClientError { context, .. }
+            | Self::ServerError { context, .. }
+            | Self::Redirect { context, .. } => Some(context.request_id),
+            Self::RateLimited { request_id, .. } | Self::JsonSchemaMismatch { request_id, .. } => {
+                Some(*request_id)
+            }
+            _ => None,
+        }
+    }
+
+    /// The HTTP status code of the response that caused the error,
+    /// if there was one.
+    #[must_use]
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            Self::ClientError { status, .. }
+            | Self::ServerError { status, .. }
+            | Self::Redirect { status, .. } => Some(*status),
+            Self::RateLimited { .. } => Some(429),
+            #[cfg(feature = "async")]
+            Self::Network { source, .. } => source.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+}
+
+/// The errors from the items of a batch operation that failed, each with
+/// the item it came from, like a CID.
+#[derive(Debug, Error)]
+#[error("{} item(s) in the batch failed", .failures.len())]
+pub struct MultiError<K: fmt::Debug> {
+    /// The failed items and their errors, in the order they were tried.
+    pub failures: Vec<(K, VatsimUtilError)>,
+}
+
+impl<K: fmt::Debug> MultiError<K> {
+    /// Whether no items failed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Whether trying every failed item again later might succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.failures.iter().all(|(_, e)| e.is_retryable())
+    }
+}
+
+/// The outcome of a batch operation, which keeps going past items that
+/// fail rather than stopping at the first error.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::errors::{BatchResults, VatsimUtilError};
+///
+/// let results: BatchResults<u64, &str> = [
+///     (1, Ok("first")),
+///     (2, Err(VatsimUtilError::InvalidAirportData(1))),
+///     (3, Ok("third")),
+/// ]
+/// .into_iter()
+/// .collect();
+/// assert_eq!(results.successes, vec![(1, "first"), (3, "third")]);
+/// assert_eq!(results.errors.failures.len(), 1);
+/// assert!(results.into_result().is_err());
+/// ```
+#[derive(Debug)]
+pub struct BatchResults<K: fmt::Debug, T> {
+    /// The items that succeeded and their values, in the order they
+    /// were tried.
+    pub successes: Vec<(K, T)>,
+    /// The items that failed.
+    pub errors: MultiError<K>,
+}
+
+impl<K: fmt::Debug, T> BatchResults<K, T> {
+    /// The values if every item succeeded, or the errors if any failed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors if any item failed.
+    pub fn into_result(self) -> Result<Vec<(K, T)>, MultiError<K>> {
+        if self.errors.is_empty() {
+            Ok(self.successes)
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    /// The values, in order and without their items, if every item
+    /// succeeded, or the first item's error if any failed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first failed item's error if any item failed.
+    pub fn into_values(self) -> Result<Vec<T>, VatsimUtilError> {
+        match self.errors.failures.into_iter().next() {
+            Some((_, error)) => Err(error),
+            None => Ok(self.successes.into_iter().map(|(_, value)| value).collect()),
+        }
+    }
+}
+
+impl<K: fmt::Debug, T> Default for BatchResults<K, T> {
+    fn default() -> Self {
+        Self {
+            successes: Vec::new(),
+            errors: MultiError {
+                failures: Vec::new(),
+            },
+        }
+    }
+}
+
+impl<K: fmt::Debug, T> Extend<(K, Result<T, VatsimUtilError>)> for BatchResults<K, T> {
+    fn extend<I: IntoIterator<Item = (K, Result<T, VatsimUtilError>)>>(&mut self, iter: I) {
+        for (key, result) in iter {
+            match result {
+                Ok(value) => self.successes.push((key, value)),
+                Err(error) => self.errors.failures.push((key, error)),
+            }
+        }
+    }
+}
+
+impl<K: fmt::Debug, T> FromIterator<(K, Result<T, VatsimUtilError>)> for BatchResults<K, T> {
+    fn from_iter<I: IntoIterator<Item = (K, Result<T, VatsimUtilError>)>>(iter: I) -> Self {
+        let mut results = Self::default();
+        results.extend(iter);
+        results
+    }
+}
+
+/// Details of the call that an error came from, passed to the
+/// [error hook](set_error_hook).
+#[derive(Debug, Clone)]
+pub struct ErrorContext {
+    /// The request's ID, if the request was sent.
+    pub request_id: Option<RequestId>,
+    /// The endpoint that was called.
+    pub endpoint: Endpoint,
+    /// The request's HTTP method, if the request could be built.
+    pub method: Option<Method>,
+    /// The request's full URL, if the request could be built.
+    pub url: Option<String>,
+}
+
+/// Register a callback to be called with every error that the crate
+/// returns from making an HTTP request, along with details of the
+/// request.
+///
+/// This is useful for counting, logging, and alerting on errors without
+/// wrapping every call. Only one hook can be registered; registering
+/// another replaces it. The hook is called on the task that made the
+/// request, so should return quickly.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::errors::{clear_error_hook, set_error_hook};
+///
+/// set_error_hook(|error, context| {
+///     eprintln!("{error} ({:?}, {:?})", context.request_id, context.url);
+/// });
+/// // ...
+/// clear_error_hook();
+/// ```
+pub fn set_error_hook<F>(hook: F)
+where
+    F: Fn(&VatsimUtilError, &ErrorContext) + Send + Sync + 'static,
+{
+    *ERROR_HOOK.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(hook));
+}
+
+/// Remove the registered error hook, if any.
+pub fn clear_error_hook() {
+    *ERROR_HOOK.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Pass the error to the registered hook, if any.
+pub(crate) fn report(error: &VatsimUtilError, context: &ErrorContext) {
+    let hook = ERROR_HOOK
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    if let Some(hook) = hook {
+        hook(error, context);
+    }
 }
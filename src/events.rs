@@ -0,0 +1,188 @@
+//! Events from [myVATSIM], and picking out the ones relevant to an
+//! airspace.
+//!
+//! The base URL can be changed with [`urls::set_endpoints`].
+//!
+//! [myVATSIM]: https://my.vatsim.net/events
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::events::{get_events, EventRelevance};
+//!
+//! # async fn _do() {
+//! let relevance = EventRelevance::default()
+//!     .airport("KSAN")
+//!     .division("USA");
+//! let events = get_events().await.unwrap();
+//! for event in relevance.apply(&events) {
+//!     println!("{} starts at {}", event.name, event.start_time);
+//! }
+//! # }
+//! ```
+
+use crate::{boundaries::Boundary, distance::AirportDatabase, models::Event};
+#[cfg(feature = "async")]
+use crate::{
+    config,
+    errors::{Endpoint, VatsimUtilError},
+    http,
+    models::EventsResponse,
+    urls,
+};
+#[cfg(feature = "async")]
+use reqwest::Client;
+#[cfg(feature = "async")]
+use std::sync::LazyLock;
+
+/// HTTP client.
+#[cfg(feature = "async")]
+static CLIENT: LazyLock<Client> = LazyLock::new(config::build_client);
+
+/// Get the current and upcoming events.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::events::get_events;
+///
+/// # async fn _do() {
+/// let events = get_events().await.unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+#[cfg(feature = "async")]
+pub async fn get_events() -> Result<Vec<Event>, VatsimUtilError> {
+    let url = format!("{}/latest", urls::endpoints().events);
+    let response: EventsResponse = http::fetch_json(Endpoint::Events, CLIENT.get(url)).await?;
+    Ok(response.data)
+}
+
+/// Which events are relevant to an airspace.
+///
+/// An event is relevant if any of its airports, including the ends of
+/// its routes, is one of the airports or inside one of the FIRs, or if
+/// one of its organisers is one of the divisions. A default filter
+/// matches every event.
+///
+/// Matching FIRs needs the locations of the events' airports; set them
+/// with [`EventRelevance::airport_locations`].
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::{
+///     boundaries::Boundary,
+///     distance::AirportDatabase,
+///     events::EventRelevance,
+///     models::Event,
+/// };
+///
+/// let event: Event = serde_json::from_str(r#"{
+///     "id": 1, "type": "Event", "name": "Fly-in", "link": "",
+///     "organisers": [{"region": "AMAS", "division": "USA", "subdivision": "ZLA", "organised_by_vatsim": false}],
+///     "airports": [{"icao": "KSAN"}],
+///     "routes": [{"departure": "KSAN", "arrival": "KLAX", "route": "DCT"}],
+///     "start_time": "2024-03-02T18:00:00.000000Z", "end_time": "2024-03-02T21:00:00.000000Z",
+///     "short_description": "", "description": "", "banner": null
+/// }"#).unwrap();
+///
+/// assert!(EventRelevance::default().airport("klax").matches(&event));
+/// assert!(EventRelevance::default().division("ZLA").matches(&event));
+/// assert!(!EventRelevance::default().airport("KSEA").matches(&event));
+///
+/// let box_around_san = Boundary::new(
+///     "TEST",
+///     vec![vec![vec![[-118.0, 32.0], [-117.0, 32.0], [-117.0, 33.0], [-118.0, 33.0], [-118.0, 32.0]]]],
+/// );
+/// let locations = AirportDatabase::from_csv("KSAN,32.7338,-117.1933").unwrap();
+/// let relevance = EventRelevance::default()
+///     .fir(box_around_san)
+///     .airport_locations(locations);
+/// assert!(relevance.matches(&event));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EventRelevance {
+    /// ICAO codes of airports to match, ignoring case.
+    pub airports: Vec<String>,
+    /// FIR boundaries to match airports inside of.
+    pub firs: Vec<Boundary>,
+    /// Regions, divisions, or subdivisions to match organisers against,
+    /// ignoring case.
+    pub divisions: Vec<String>,
+    /// Locations of airports, for matching FIRs.
+    pub airport_locations: AirportDatabase,
+}
+
+impl EventRelevance {
+    /// Add an airport to match.
+    #[must_use]
+    pub fn airport(mut self, icao: &str) -> Self {
+        self.airports.push(icao.to_owned());
+        self
+    }
+
+    /// Add a FIR to match.
+    #[must_use]
+    pub fn fir(mut self, boundary: Boundary) -> Self {
+        self.firs.push(boundary);
+        self
+    }
+
+    /// Add a region, division, or subdivision to match, like "USA".
+    #[must_use]
+    pub fn division(mut self, division: &str) -> Self {
+        self.divisions.push(division.to_owned());
+        self
+    }
+
+    /// Set where airports are, for matching FIRs.
+    #[must_use]
+    pub fn airport_locations(mut self, airports: AirportDatabase) -> Self {
+        self.airport_locations = airports;
+        self
+    }
+
+    /// Whether the event is relevant.
+    #[must_use]
+    pub fn matches(&self, event: &Event) -> bool {
+        if self.airports.is_empty() && self.firs.is_empty() && self.divisions.is_empty() {
+            return true;
+        }
+        let icaos = event.airport_icaos();
+        let by_airport = icaos
+            .iter()
+            .any(|icao| self.airports.iter().any(|a| a.eq_ignore_ascii_case(icao)));
+        let by_fir = || {
+            icaos.iter().any(|icao| {
+                self.airport_locations
+                    .location(&icao.to_uppercase())
+                    .is_some_and(|(lat, lon)| self.firs.iter().any(|fir| fir.contains(lat, lon)))
+            })
+        };
+        let by_division = || {
+            event.organisers.iter().any(|organiser| {
+                [
+                    &organiser.region,
+                    &organiser.division,
+                    &organiser.subdivision,
+                ]
+                .into_iter()
+                .flatten()
+                .any(|code| self.divisions.iter().any(|d| d.eq_ignore_ascii_case(code)))
+            })
+        };
+        by_airport || by_fir() || by_division()
+    }
+
+    /// The events that are relevant.
+    #[must_use]
+    pub fn apply<'a>(&self, events: &'a [Event]) -> Vec<&'a Event> {
+        events.iter().filter(|e| self.matches(e)).collect()
+    }
+}
@@ -0,0 +1,271 @@
+//! Exporting live data for storage and log-shipping pipelines.
+//!
+//! The [`JsonLinesExporter`] writes one JSON object per line, each wrapped
+//! in a [`JsonLinesRecord`] that carries a timestamp and a stable
+//! [`SCHEMA_VERSION`], which is the format expected by tools like
+//! [Vector] and [Fluent Bit].
+//!
+//! [Vector]: https://vector.dev/
+//! [Fluent Bit]: https://fluentbit.io/
+//!
+//! With the `msgpack` feature enabled, data can also be written in the
+//! compact binary [MessagePack] format via `write_msgpack`, which takes
+//! up a fraction of the space of JSON when archiving long feed histories.
+//!
+//! [MessagePack]: https://msgpack.org/
+//!
+//! With the `zstd` feature enabled, any of these can be wrapped in
+//! [zstd] compression by writing through `compress` and reading
+//! back through `decompress`.
+//!
+//! [zstd]: https://facebook.github.io/zstd/
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::{export::JsonLinesExporter, live_api::Vatsim};
+//! use std::fs::OpenOptions;
+//!
+//! # async fn _do() {
+//! let api = Vatsim::new().await.unwrap();
+//! let file = OpenOptions::new()
+//!     .create(true)
+//!     .append(true)
+//!     .open("snapshots.jsonl")
+//!     .unwrap();
+//! let mut exporter = JsonLinesExporter::new(file);
+//! let data = api.get_v3_data().await.unwrap();
+//! exporter.write(&data).unwrap();
+//! exporter.flush().unwrap();
+//! # }
+//! ```
+
+use crate::errors::VatsimUtilError;
+#[cfg(feature = "msgpack")]
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "msgpack", feature = "zstd"))]
+use std::io::Read;
+use std::{
+    io::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Version of the [`JsonLinesRecord`] layout.
+///
+/// This is incremented whenever the record layout changes in a way
+/// that consumers of exported data would need to handle.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A single exported line.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JsonLinesRecord<T> {
+    /// Version of the record layout; see [`SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// Milliseconds since the Unix epoch when the record was written.
+    pub recorded_at: u64,
+    /// The exported data.
+    pub data: T,
+}
+
+impl<T> JsonLinesRecord<T> {
+    /// Wrap the data in a record, stamped with the current time.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use vatsim_utils::export::{JsonLinesRecord, SCHEMA_VERSION};
+    ///
+    /// let record = JsonLinesRecord::new("some data");
+    ///
+    /// assert_eq!(record.schema_version, SCHEMA_VERSION);
+    /// assert!(record.recorded_at > 0);
+    /// ```
+    pub fn new(data: T) -> Self {
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+            .unwrap_or_default();
+        Self {
+            schema_version: SCHEMA_VERSION,
+            recorded_at,
+            data,
+        }
+    }
+}
+
+/// Writer that appends records as JSON Lines to any [`Write`] target.
+#[derive(Debug)]
+pub struct JsonLinesExporter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonLinesExporter<W> {
+    /// Create a new exporter, writing to the supplied target.
+    ///
+    /// For files, open the file in append mode so that existing
+    /// records are kept.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Write a single record to the target.
+    ///
+    /// The target isn't flushed after each record; call [`flush`] or
+    /// [`finish`] when done writing.
+    ///
+    /// [`flush`]: JsonLinesExporter::flush
+    /// [`finish`]: JsonLinesExporter::finish
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use vatsim_utils::export::JsonLinesExporter;
+    ///
+    /// let mut exporter = JsonLinesExporter::new(Vec::new());
+    /// exporter.write(&"first").unwrap();
+    /// exporter.write(&"second").unwrap();
+    /// let output = String::from_utf8(exporter.finish().unwrap()).unwrap();
+    ///
+    /// assert_eq!(output.lines().count(), 2);
+    /// assert!(output.starts_with(r#"{"schema_version":1,"#));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the data cannot be serialized
+    /// or if writing to the target fails.
+    pub fn write<T: Serialize>(&mut self, data: &T) -> Result<(), VatsimUtilError> {
+        let record = JsonLinesRecord::new(data);
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Flush everything written so far to the target.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if writing to the target fails.
+    pub fn flush(&mut self) -> Result<(), VatsimUtilError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Flush the target and consume the exporter, returning the target.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if writing to the target fails.
+    pub fn finish(mut self) -> Result<W, VatsimUtilError> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Consume the exporter, returning the underlying target without
+    /// flushing it.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Write the data to the target in the `MessagePack` format.
+///
+/// Multiple values can be written to the same target one after
+/// another, and read back in order with [`read_msgpack`].
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::export::{read_msgpack, write_msgpack};
+///
+/// let mut buffer = Vec::new();
+/// write_msgpack(&mut buffer, &vec![1, 2, 3]).unwrap();
+/// write_msgpack(&mut buffer, &vec![4, 5]).unwrap();
+///
+/// let mut reader = buffer.as_slice();
+/// let first: Vec<u8> = read_msgpack(&mut reader).unwrap();
+/// let second: Vec<u8> = read_msgpack(&mut reader).unwrap();
+///
+/// assert_eq!(first, vec![1, 2, 3]);
+/// assert_eq!(second, vec![4, 5]);
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the data cannot be serialized
+/// or if writing to the target fails.
+#[cfg(feature = "msgpack")]
+pub fn write_msgpack<W: Write, T: Serialize>(
+    writer: &mut W,
+    data: &T,
+) -> Result<(), VatsimUtilError> {
+    rmp_serde::encode::write_named(writer, data)?;
+    Ok(())
+}
+
+/// Read a single `MessagePack`-encoded value from the source.
+///
+/// See [`write_msgpack`] for an example.
+///
+/// # Errors
+///
+/// This function can fail if reading from the source fails or if the
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+#[cfg(feature = "msgpack")]
+pub fn read_msgpack<R: Read, T: DeserializeOwned>(reader: &mut R) -> Result<T, VatsimUtilError> {
+    let data = rmp_serde::decode::from_read(reader)?;
+    Ok(data)
+}
+
+/// Wrap the target so that everything written to it is compressed.
+///
+/// `level` is the zstd compression level, from 1 to 22; 3 is a
+/// reasonable default. The compressed stream must be completed by
+/// calling `finish` on the returned encoder, which reports any error
+/// writing the end of the stream and hands back the target.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::export::{compress, decompress, JsonLinesExporter};
+/// use std::io::Read;
+///
+/// let mut exporter = JsonLinesExporter::new(compress(Vec::new(), 3).unwrap());
+/// exporter.write(&"some data").unwrap();
+/// let buffer = exporter.finish().unwrap().finish().unwrap();
+///
+/// let mut output = String::new();
+/// decompress(buffer.as_slice())
+///     .unwrap()
+///     .read_to_string(&mut output)
+///     .unwrap();
+///
+/// assert!(output.contains("some data"));
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the compression level is invalid.
+#[cfg(feature = "zstd")]
+pub fn compress<W: Write>(
+    writer: W,
+    level: i32,
+) -> Result<zstd::Encoder<'static, W>, VatsimUtilError> {
+    let encoder = zstd::Encoder::new(writer, level)?;
+    Ok(encoder)
+}
+
+/// Wrap the source so that everything read from it is decompressed.
+///
+/// See [`compress`] for an example.
+///
+/// # Errors
+///
+/// This function can fail if the decompressor cannot be created.
+#[cfg(feature = "zstd")]
+pub fn decompress<R: Read>(reader: R) -> Result<impl Read, VatsimUtilError> {
+    let decoder = zstd::Decoder::new(reader)?;
+    Ok(decoder)
+}
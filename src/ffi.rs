@@ -0,0 +1,345 @@
+//! C-compatible interface for plugins written in C or C++.
+//!
+//! The interface wraps the [`blocking`] functions. The live
+//! data client is an opaque [`VatsimHandle`], and data is returned as
+//! NUL-terminated JSON strings matching the crate's models. Every function
+//! returns a [`VatsimErrorCode`]; on failure, [`vatsim_last_error`] gives a
+//! description of the error. Panics are caught at the boundary rather than
+//! unwinding into the caller, and reported the same way.
+//!
+//! Strings returned by the functions are owned by the caller and must be
+//! freed with [`vatsim_string_free`]; handles must be freed with
+//! [`vatsim_client_free`].
+//!
+//! Requires the `ffi` feature. Build a `cdylib` or `staticlib` that depends
+//! on this crate to link against; the declarations are in
+//! `include/vatsim_utils.h` in the repository.
+//!
+//! ```c
+//! VatsimHandle *client = NULL;
+//! char *json = NULL;
+//! if (vatsim_client_new(&client) != VatsimOk) {
+//!     fprintf(stderr, "%s\n", vatsim_last_error());
+//!     return;
+//! }
+//! if (vatsim_get_v3_data(client, &json) == VatsimOk) {
+//!     // parse json ...
+//!     vatsim_string_free(json);
+//! }
+//! vatsim_client_free(client);
+//! ```
+
+#![allow(unsafe_code)]
+
+use crate::{blocking, errors::VatsimUtilError};
+use serde::Serialize;
+use std::{
+    any::Any,
+    cell::RefCell,
+    ffi::{c_char, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+thread_local! {
+    /// Description of the last error on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Result of a call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VatsimErrorCode {
+    /// The call succeeded.
+    VatsimOk = 0,
+    /// A required pointer was null.
+    VatsimNullPointer = 1,
+    /// The server couldn't be reached.
+    VatsimNetwork = 2,
+    /// The server responded with a 4xx status code.
+    VatsimClientError = 3,
+    /// The server responded with a 5xx status code.
+    VatsimServerError = 4,
+    /// The server rate limited the request.
+    VatsimRateLimited = 5,
+    /// The response didn't match the crate's models.
+    VatsimInvalidData = 6,
+    /// Any other error.
+    VatsimOther = 7,
+    /// The library panicked; this is a bug.
+    VatsimPanic = 8,
+}
+
+impl From<&VatsimUtilError> for VatsimErrorCode {
+    fn from(error: &VatsimUtilError) -> Self {
+        match error {
+            VatsimUtilError::ClientError { .. } => Self::VatsimClientError,
+            VatsimUtilError::ServerError { .. } => Self::VatsimServerError,
+            VatsimUtilError::RateLimited { .. } => Self::VatsimRateLimited,
+            VatsimUtilError::FailedJsonParse(_) | VatsimUtilError::JsonSchemaMismatch { .. } => {
+                Self::VatsimInvalidData
+            }
+            e if e.is_network() => Self::VatsimNetwork,
+            _ => Self::VatsimOther,
+        }
+    }
+}
+
+/// Opaque handle to a live data client.
+#[derive(Debug)]
+pub struct VatsimHandle {
+    inner: blocking::Vatsim,
+}
+
+/// Store the error's description for [`vatsim_last_error`].
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last| {
+        if let Ok(mut last) = last.try_borrow_mut() {
+            *last = Some(message);
+        }
+    });
+}
+
+/// Run the body of an exported function, returning `on_panic` and
+/// storing the panic's message for [`vatsim_last_error`] if it panics,
+/// rather than unwinding into the caller.
+fn guard<T>(on_panic: T, body: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        set_last_error(&format!("Panicked: {}", panic_message(payload.as_ref())));
+        on_panic
+    })
+}
+
+/// The message a panic was raised with, if it was a string.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause")
+}
+
+/// Write the result of a call to `out` as JSON.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+unsafe fn write_json<T: Serialize>(
+    result: Result<T, VatsimUtilError>,
+    out: *mut *mut c_char,
+) -> VatsimErrorCode {
+    if out.is_null() {
+        set_last_error("Output pointer is null");
+        return VatsimErrorCode::VatsimNullPointer;
+    }
+    let json = result.and_then(|value| Ok(serde_json::to_string(&value)?));
+    match json {
+        Ok(json) => {
+            let json = CString::new(json).unwrap_or_default();
+            // SAFETY: checked for null above, and the caller guarantees validity
+            unsafe { *out = json.into_raw() };
+            VatsimErrorCode::VatsimOk
+        }
+        Err(e) => {
+            set_last_error(&e.to_string());
+            VatsimErrorCode::from(&e)
+        }
+    }
+}
+
+/// Convert a page number, where 0 means none.
+fn page(page: u64) -> Option<u64> {
+    (page > 0).then_some(page)
+}
+
+/// Description of the last error on the calling thread, or null if there
+/// hasn't been one.
+///
+/// The string is owned by the library and valid until the next call on
+/// the same thread.
+#[no_mangle]
+pub extern "C" fn vatsim_last_error() -> *const c_char {
+    panic::catch_unwind(|| {
+        LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+    })
+    .unwrap_or(ptr::null())
+}
+
+/// Free a string returned by one of the other functions.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library that hasn't
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn vatsim_string_free(s: *mut c_char) {
+    guard((), || {
+        if !s.is_null() {
+            // SAFETY: the caller guarantees that the string came from `CString::into_raw`
+            drop(unsafe { CString::from_raw(s) });
+        }
+    });
+}
+
+/// Create a live data client, writing its handle to `out`.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vatsim_client_new(out: *mut *mut VatsimHandle) -> VatsimErrorCode {
+    guard(VatsimErrorCode::VatsimPanic, || {
+        if out.is_null() {
+            set_last_error("Output pointer is null");
+            return VatsimErrorCode::VatsimNullPointer;
+        }
+        match blocking::Vatsim::new() {
+            Ok(inner) => {
+                // SAFETY: checked for null above, and the caller guarantees validity
+                unsafe { *out = Box::into_raw(Box::new(VatsimHandle { inner })) };
+                VatsimErrorCode::VatsimOk
+            }
+            Err(e) => {
+                set_last_error(&e.to_string());
+                VatsimErrorCode::from(&e)
+            }
+        }
+    })
+}
+
+/// Free a live data client.
+///
+/// # Safety
+///
+/// `handle` must be null or a handle returned by [`vatsim_client_new`]
+/// that hasn't already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn vatsim_client_free(handle: *mut VatsimHandle) {
+    guard((), || {
+        if !handle.is_null() {
+            // SAFETY: the caller guarantees that the handle came from `Box::into_raw`
+            drop(unsafe { Box::from_raw(handle) });
+        }
+    });
+}
+
+/// Get the V3 live data as JSON.
+///
+/// # Safety
+///
+/// `handle` must be null or a valid handle, and `out` must be null or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vatsim_get_v3_data(
+    handle: *const VatsimHandle,
+    out: *mut *mut c_char,
+) -> VatsimErrorCode {
+    guard(VatsimErrorCode::VatsimPanic, || {
+        // SAFETY: the caller guarantees that the handle is null or valid
+        let Some(handle) = (unsafe { handle.as_ref() }) else {
+            set_last_error("Handle is null");
+            return VatsimErrorCode::VatsimNullPointer;
+        };
+        // SAFETY: the caller guarantees that `out` is null or valid
+        unsafe { write_json(handle.inner.get_v3_data(), out) }
+    })
+}
+
+/// Get the transceivers live data as JSON.
+///
+/// # Safety
+///
+/// `handle` must be null or a valid handle, and `out` must be null or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vatsim_get_transceivers_data(
+    handle: *const VatsimHandle,
+    out: *mut *mut c_char,
+) -> VatsimErrorCode {
+    guard(VatsimErrorCode::VatsimPanic, || {
+        // SAFETY: the caller guarantees that the handle is null or valid
+        let Some(handle) = (unsafe { handle.as_ref() }) else {
+            set_last_error("Handle is null");
+            return VatsimErrorCode::VatsimNullPointer;
+        };
+        // SAFETY: the caller guarantees that `out` is null or valid
+        unsafe { write_json(handle.inner.get_transceivers_data(), out) }
+    })
+}
+
+/// Get a user's ratings as JSON.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vatsim_user_ratings(cid: u64, out: *mut *mut c_char) -> VatsimErrorCode {
+    guard(VatsimErrorCode::VatsimPanic, || {
+        // SAFETY: the caller guarantees that `out` is null or valid
+        unsafe { write_json(blocking::user_ratings(cid), out) }
+    })
+}
+
+/// Get a user's time at each rating as JSON.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vatsim_get_ratings_times(
+    cid: u64,
+    out: *mut *mut c_char,
+) -> VatsimErrorCode {
+    guard(VatsimErrorCode::VatsimPanic, || {
+        // SAFETY: the caller guarantees that `out` is null or valid
+        unsafe { write_json(blocking::get_ratings_times(cid), out) }
+    })
+}
+
+/// Get a page of a user's connections as JSON; a page of 0 gets the first.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vatsim_get_connections(
+    cid: u64,
+    page_number: u64,
+    out: *mut *mut c_char,
+) -> VatsimErrorCode {
+    guard(VatsimErrorCode::VatsimPanic, || {
+        // SAFETY: the caller guarantees that `out` is null or valid
+        unsafe { write_json(blocking::get_connections(cid, page(page_number)), out) }
+    })
+}
+
+/// Get a page of a user's flight plans as JSON; a page of 0 gets the first.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vatsim_get_flight_plans(
+    cid: u64,
+    page_number: u64,
+    out: *mut *mut c_char,
+) -> VatsimErrorCode {
+    guard(VatsimErrorCode::VatsimPanic, || {
+        // SAFETY: the caller guarantees that `out` is null or valid
+        unsafe { write_json(blocking::get_flight_plans(cid, page(page_number)), out) }
+    })
+}
+
+/// Get the facilities currently staffed by ATC as JSON.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vatsim_get_online_facilities(out: *mut *mut c_char) -> VatsimErrorCode {
+    guard(VatsimErrorCode::VatsimPanic, || {
+        // SAFETY: the caller guarantees that `out` is null or valid
+        unsafe { write_json(blocking::get_online_facilities(), out) }
+    })
+}
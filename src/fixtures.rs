@@ -0,0 +1,187 @@
+//! Sample API responses for examples, benchmarks, and tests.
+//!
+//! The data is realistic - a handful of pilots and controllers around
+//! Los Angeles and London - but made up, with anonymized names and CIDs.
+//! Each response is available as its raw JSON, for testing parsing, and
+//! through a function that returns it parsed into the crate's models.
+//!
+//! Requires the `fixtures` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use vatsim_utils::{analysis::busiest_airports, fixtures};
+//!
+//! let data = fixtures::v3_data();
+//! let airports = busiest_airports(&data, &["K"]);
+//!
+//! assert_eq!(airports[0].identifier, "KLAX");
+//! ```
+//!
+//! Every fixture round-trips through its model to the same JSON values,
+//! which checks that serializing the models doesn't change the APIs'
+//! schema:
+//!
+//! ```rust
+//! use serde::{de::DeserializeOwned, Serialize};
+//! use serde_json::Value;
+//! use vatsim_utils::{fixtures::*, models::*};
+//!
+//! fn round_trip<T: DeserializeOwned + Serialize>(json: &str) {
+//!     let parsed: T = serde_json::from_str(json).unwrap();
+//!     let original: Value = serde_json::from_str(json).unwrap();
+//!     assert_eq!(serde_json::to_value(&parsed).unwrap(), original);
+//! }
+//!
+//! round_trip::<V3ResponseData>(V3_DATA);
+//! round_trip::<Vec<TransceiverResponseEntry>>(TRANSCEIVERS_DATA);
+//! round_trip::<UserRatingsSimple>(RATINGS);
+//! round_trip::<RatingsTimeData>(RATING_TIMES);
+//! round_trip::<PaginatedResponse<ConnectionEntry>>(CONNECTIONS);
+//! round_trip::<PaginatedResponse<AtcSessionEntry>>(ATC_SESSIONS);
+//! round_trip::<PaginatedResponse<RestFlightPlans>>(FLIGHT_PLANS);
+//! round_trip::<Vec<Region>>(REGIONS);
+//! round_trip::<Vec<Division>>(DIVISIONS);
+//! round_trip::<Vec<Facility>>(FACILITIES);
+//! ```
+
+use crate::models::{
+    AtcSessionEntry, ConnectionEntry, Division, Facility, PaginatedResponse, RatingsTimeData,
+    Region, RestFlightPlans, TransceiverResponseEntry, UserRatingsSimple, V3ResponseData,
+};
+
+/// JSON from the V3 live data feed.
+pub const V3_DATA: &str = include_str!("fixtures/v3.json");
+/// JSON from the transceivers live data feed.
+pub const TRANSCEIVERS_DATA: &str = include_str!("fixtures/transceivers.json");
+/// JSON from the REST API's user ratings endpoint.
+pub const RATINGS: &str = include_str!("fixtures/ratings.json");
+/// JSON from the REST API's rating times endpoint.
+pub const RATING_TIMES: &str = include_str!("fixtures/rating_times.json");
+/// JSON from the REST API's connections endpoint.
+pub const CONNECTIONS: &str = include_str!("fixtures/connections.json");
+/// JSON from the REST API's ATC sessions endpoint.
+pub const ATC_SESSIONS: &str = include_str!("fixtures/atc_sessions.json");
+/// JSON from the REST API's flight plans endpoint.
+pub const FLIGHT_PLANS: &str = include_str!("fixtures/flight_plans.json");
+/// JSON from the REST API's regions endpoint.
+pub const REGIONS: &str = include_str!("fixtures/regions.json");
+/// JSON from the REST API's divisions endpoint.
+pub const DIVISIONS: &str = include_str!("fixtures/divisions.json");
+/// JSON from the REST API's online facilities endpoint.
+pub const FACILITIES: &str = include_str!("fixtures/facilities.json");
+
+/// Parse one of the fixtures, which are known to be valid.
+fn parse<T: serde::de::DeserializeOwned>(json: &str) -> T {
+    serde_json::from_str(json).expect("Invalid fixture")
+}
+
+/// The V3 live data.
+///
+/// # Panics
+///
+/// Will panic if the fixture doesn't match the model, which
+/// should never happen.
+#[must_use]
+pub fn v3_data() -> V3ResponseData {
+    parse(V3_DATA)
+}
+
+/// The transceivers live data.
+///
+/// # Panics
+///
+/// Will panic if the fixture doesn't match the model, which
+/// should never happen.
+#[must_use]
+pub fn transceivers_data() -> Vec<TransceiverResponseEntry> {
+    parse(TRANSCEIVERS_DATA)
+}
+
+/// A user's ratings.
+///
+/// # Panics
+///
+/// Will panic if the fixture doesn't match the model, which
+/// should never happen.
+#[must_use]
+pub fn ratings() -> UserRatingsSimple {
+    parse(RATINGS)
+}
+
+/// A user's time at each rating.
+///
+/// # Panics
+///
+/// Will panic if the fixture doesn't match the model, which
+/// should never happen.
+#[must_use]
+pub fn rating_times() -> RatingsTimeData {
+    parse(RATING_TIMES)
+}
+
+/// A page of a user's connections.
+///
+/// # Panics
+///
+/// Will panic if the fixture doesn't match the model, which
+/// should never happen.
+#[must_use]
+pub fn connections() -> PaginatedResponse<ConnectionEntry> {
+    parse(CONNECTIONS)
+}
+
+/// A page of a user's ATC sessions.
+///
+/// # Panics
+///
+/// Will panic if the fixture doesn't match the model, which
+/// should never happen.
+#[must_use]
+pub fn atc_sessions() -> PaginatedResponse<AtcSessionEntry> {
+    parse(ATC_SESSIONS)
+}
+
+/// A page of a user's flight plans.
+///
+/// # Panics
+///
+/// Will panic if the fixture doesn't match the model, which
+/// should never happen.
+#[must_use]
+pub fn flight_plans() -> PaginatedResponse<RestFlightPlans> {
+    parse(FLIGHT_PLANS)
+}
+
+/// The VATSIM regions.
+///
+/// # Panics
+///
+/// Will panic if the fixture doesn't match the model, which
+/// should never happen.
+#[must_use]
+pub fn regions() -> Vec<Region> {
+    parse(REGIONS)
+}
+
+/// The VATSIM divisions.
+///
+/// # Panics
+///
+/// Will panic if the fixture doesn't match the model, which
+/// should never happen.
+#[must_use]
+pub fn divisions() -> Vec<Division> {
+    parse(DIVISIONS)
+}
+
+/// The online facilities.
+///
+/// # Panics
+///
+/// Will panic if the fixture doesn't match the model, which
+/// should never happen.
+#[must_use]
+pub fn facilities() -> Vec<Facility> {
+    parse(FACILITIES)
+}
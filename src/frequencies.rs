@@ -0,0 +1,190 @@
+//! Checking controller and ATIS frequencies against the VHF airband.
+//!
+//! Controllers choose their own frequency when they connect, so the feed
+//! has the occasional typo, like "1335.00" or "133.330". Observers and
+//! positions that aren't primary are given the placeholder 199.998.
+//! [`check_frequency`] classifies one frequency, and [`frequency_issues`]
+//! lists every station in a snapshot without a valid one.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::{frequencies::frequency_issues, live_api::Vatsim, models::Observers};
+//!
+//! # async fn _do() {
+//! let api = Vatsim::new().await.unwrap();
+//! let data = api.get_v3_data().await.unwrap();
+//! for issue in frequency_issues(&data, Observers::Exclude) {
+//!     println!("{} on {}: {:?}", issue.connection.callsign(), issue.frequency, issue.status);
+//! }
+//! # }
+//! ```
+
+use crate::{
+    analysis::Connection,
+    models::{Observers, V3ResponseData},
+};
+use serde::{Deserialize, Serialize};
+
+/// The frequency given to observers and positions that aren't primary.
+pub const PLACEHOLDER_FREQUENCY: &str = "199.998";
+
+/// Lowest frequency in the VHF airband used for communication, in kHz.
+const BAND_START_KHZ: u32 = 118_000;
+
+/// Highest frequency in the VHF airband used for communication, in kHz.
+const BAND_END_KHZ: u32 = 136_975;
+
+/// What's wrong with a frequency, if anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrequencyStatus {
+    /// A channel in the airband.
+    Valid,
+    /// The 199.998 placeholder.
+    Placeholder,
+    /// Outside 118.000–136.975 MHz. ATIS broadcast on a VOR's frequency
+    /// is out of the band too.
+    OutOfBand,
+    /// In the band, but not the name of a 25 or 8.33 kHz channel.
+    InvalidSpacing,
+    /// Not a frequency in MHz.
+    Unparseable,
+}
+
+impl FrequencyStatus {
+    /// Whether the frequency is a valid channel.
+    #[must_use]
+    pub fn is_valid(self) -> bool {
+        self == Self::Valid
+    }
+}
+
+/// Check a frequency in MHz, as given in the feed.
+///
+/// 8.33 kHz channels are named rather than numbered by their exact
+/// frequency: each 25 kHz block has its own name and the next three in
+/// steps of 5 kHz, like 118.000, 118.005, 118.010, and 118.015, then
+/// 118.025 to 118.040. A frequency given with two decimal places, like
+/// "118.02" for 118.025, is read as its 25 kHz channel.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::frequencies::{check_frequency, FrequencyStatus};
+///
+/// assert_eq!(check_frequency("133.900"), FrequencyStatus::Valid);
+/// assert_eq!(check_frequency("132.655"), FrequencyStatus::Valid);
+/// assert_eq!(check_frequency("118.02"), FrequencyStatus::Valid);
+/// assert_eq!(check_frequency("199.998"), FrequencyStatus::Placeholder);
+/// assert_eq!(check_frequency("113.750"), FrequencyStatus::OutOfBand);
+/// assert_eq!(check_frequency("133.345"), FrequencyStatus::InvalidSpacing);
+/// assert_eq!(check_frequency("none"), FrequencyStatus::Unparseable);
+/// ```
+#[must_use]
+pub fn check_frequency(frequency: &str) -> FrequencyStatus {
+    let frequency = frequency.trim();
+    if frequency == PLACEHOLDER_FREQUENCY {
+        return FrequencyStatus::Placeholder;
+    }
+    let Some(khz) = parse_khz(frequency) else {
+        return FrequencyStatus::Unparseable;
+    };
+    if !(BAND_START_KHZ..=BAND_END_KHZ).contains(&khz) {
+        FrequencyStatus::OutOfBand
+    } else if khz % 25 < 20 && khz % 5 == 0 {
+        FrequencyStatus::Valid
+    } else {
+        FrequencyStatus::InvalidSpacing
+    }
+}
+
+/// A controller or ATIS station without a valid frequency.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FrequencyIssue<'a> {
+    /// The controller or ATIS station.
+    pub connection: Connection<'a>,
+    /// The frequency, as given in the feed.
+    pub frequency: &'a str,
+    /// What's wrong with it.
+    pub status: FrequencyStatus,
+}
+
+/// Find controllers and ATIS stations whose frequencies aren't valid
+/// channels, including those on the placeholder frequency.
+///
+/// Observers are always on the placeholder, so they're usually worth
+/// excluding. Results are sorted by callsign.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "fixtures")] {
+/// use vatsim_utils::{
+///     fixtures,
+///     frequencies::{frequency_issues, FrequencyStatus},
+///     models::Observers,
+/// };
+///
+/// let data = fixtures::v3_data();
+/// let issues = frequency_issues(&data, Observers::Include);
+/// let found: Vec<_> = issues
+///     .iter()
+///     .map(|issue| (issue.connection.callsign(), issue.status))
+///     .collect();
+///
+/// assert_eq!(
+///     found,
+///     [
+///         ("EGLL_ATIS", FrequencyStatus::OutOfBand),
+///         ("KLAX_OBS", FrequencyStatus::Placeholder),
+///     ],
+/// );
+/// assert_eq!(frequency_issues(&data, Observers::Exclude).len(), 1);
+/// # }
+/// ```
+#[must_use]
+pub fn frequency_issues(data: &V3ResponseData, observers: Observers) -> Vec<FrequencyIssue<'_>> {
+    let controllers = data
+        .controllers_filtered(observers)
+        .map(|controller| (Connection::Controller(controller), &controller.frequency));
+    let atis = data
+        .atis
+        .iter()
+        .map(|atis| (Connection::Atis(atis), &atis.frequency));
+    let mut issues: Vec<_> = controllers
+        .chain(atis)
+        .filter_map(|(connection, frequency)| {
+            let status = check_frequency(frequency);
+            (!status.is_valid()).then_some(FrequencyIssue {
+                connection,
+                frequency,
+                status,
+            })
+        })
+        .collect();
+    issues.sort_by(|a, b| a.connection.callsign().cmp(b.connection.callsign()));
+    issues
+}
+
+/// Parse a frequency in MHz to kHz, rounding two decimal places up to
+/// their 25 kHz channel. Returns `None` for anything more precise than
+/// a kHz.
+fn parse_khz(frequency: &str) -> Option<u32> {
+    let (mhz, fraction) = frequency.split_once('.').unwrap_or((frequency, ""));
+    if mhz.is_empty()
+        || fraction.len() > 3
+        || !mhz
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let mhz: u32 = mhz.parse().ok()?;
+    let mut khz: u32 = format!("{fraction:0<3}").parse().ok()?;
+    if fraction.len() == 2 && matches!(khz % 100, 20 | 70) {
+        khz += 5;
+    }
+    mhz.checked_mul(1000)?.checked_add(khz)
+}
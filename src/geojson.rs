@@ -0,0 +1,145 @@
+//! Minimal [GeoJSON] types for exporting map data.
+//!
+//! Only the geometry types this crate produces are included. Everything
+//! here serializes to standard `GeoJSON`, ready to hand to mapping libraries
+//! like Leaflet or Mapbox.
+//!
+//! Note that `GeoJSON` positions are longitude first, then latitude.
+//!
+//! [GeoJSON]: https://datatracker.ietf.org/doc/html/rfc7946
+
+use crate::distance::destination_point;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A `[longitude, latitude]` pair.
+pub type Position = [f64; 2];
+
+/// A `GeoJSON` geometry.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", content = "coordinates")]
+pub enum Geometry {
+    /// A single position.
+    Point(Position),
+    /// A line through the positions.
+    LineString(Vec<Position>),
+    /// An area; the first ring is the exterior, any others are holes.
+    /// Each ring's first and last positions are the same.
+    Polygon(Vec<Vec<Position>>),
+    /// Several areas, each like a [`Geometry::Polygon`].
+    MultiPolygon(Vec<Vec<Vec<Position>>>),
+}
+
+/// A geometry with properties.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename = "Feature")]
+pub struct Feature {
+    /// The feature's shape.
+    pub geometry: Geometry,
+    /// Arbitrary properties attached to the feature.
+    pub properties: Map<String, Value>,
+}
+
+impl Feature {
+    /// Create a new feature with no properties.
+    #[must_use]
+    pub fn new(geometry: Geometry) -> Self {
+        Self {
+            geometry,
+            properties: Map::new(),
+        }
+    }
+
+    /// Add a property to the feature.
+    #[must_use]
+    pub fn property<V: Into<Value>>(mut self, key: &str, value: V) -> Self {
+        let _ = self.properties.insert(key.to_owned(), value.into());
+        self
+    }
+}
+
+/// A collection of features; typically the top-level `GeoJSON` object.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::geojson::{Feature, FeatureCollection, Geometry};
+///
+/// let collection = FeatureCollection {
+///     features: vec![Feature::new(Geometry::Point([-117.19, 32.73])).property("name", "KSAN")],
+/// };
+/// let json = serde_json::to_string(&collection).unwrap();
+///
+/// assert_eq!(
+///     json,
+///     r#"{"type":"FeatureCollection","features":[{"type":"Feature","geometry":{"type":"Point","coordinates":[-117.19,32.73]},"properties":{"name":"KSAN"}}]}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename = "FeatureCollection")]
+pub struct FeatureCollection {
+    /// The features.
+    pub features: Vec<Feature>,
+}
+
+/// Build a polygon approximating a circle of the radius (in nautical
+/// miles) around a point, using the number of points along its edge.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::geojson::{circle, Geometry};
+///
+/// let Geometry::Polygon(rings) = circle(32.7338, -117.1933, 10.0, 36) else {
+///     unreachable!();
+/// };
+///
+/// assert_eq!(rings[0].len(), 37);
+/// assert_eq!(rings[0].first(), rings[0].last());
+/// ```
+#[must_use]
+pub fn circle(lat: f64, lon: f64, radius_nm: f64, points: usize) -> Geometry {
+    Geometry::Polygon(vec![ring(lat, lon, radius_nm, points)])
+}
+
+/// Build concentric range rings around a point, one line string feature
+/// per radius (in nautical miles), each with a `radius_nm` property.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::geojson::{range_rings, Geometry};
+///
+/// let rings = range_rings(32.7338, -117.1933, &[10.0, 20.0, 30.0], 72);
+///
+/// assert_eq!(rings.features.len(), 3);
+/// assert_eq!(rings.features[1].properties["radius_nm"], 20.0);
+/// assert!(matches!(&rings.features[0].geometry, Geometry::LineString(line) if line.len() == 73));
+/// ```
+#[must_use]
+pub fn range_rings(lat: f64, lon: f64, radii_nm: &[f64], points: usize) -> FeatureCollection {
+    FeatureCollection {
+        features: radii_nm
+            .iter()
+            .map(|&radius| {
+                Feature::new(Geometry::LineString(ring(lat, lon, radius, points)))
+                    .property("radius_nm", radius)
+            })
+            .collect(),
+    }
+}
+
+/// Closed ring of positions around a point.
+fn ring(lat: f64, lon: f64, radius_nm: f64, points: usize) -> Vec<Position> {
+    let points = points.max(3);
+    let mut positions: Vec<Position> = (0..points)
+        .map(|i| {
+            #[allow(clippy::cast_precision_loss)]
+            let bearing = 360.0 * i as f64 / points as f64;
+            let (lat, lon) = destination_point(lat, lon, bearing, radius_nm);
+            [lon, lat]
+        })
+        .collect();
+    positions.push(positions[0]);
+    positions
+}
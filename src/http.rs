@@ -0,0 +1,286 @@
+//! Sending HTTP requests and checking their responses.
+//!
+//! Everything in the crate that makes requests goes through here, so
+//! errors carry the same context no matter which API was called, every
+//! request is logged with its [`RequestId`], and requests to VATSIM count
+//! towards the [request budget](crate::budget).
+
+use crate::errors::{Endpoint, Method, RequestId, ResponseContext, VatsimUtilError};
+#[cfg(feature = "async")]
+use crate::{
+    budget, config,
+    errors::{self, ErrorContext},
+};
+#[cfg(feature = "async")]
+use log::debug;
+#[cfg(feature = "async")]
+use reqwest::{
+    header::{HeaderValue, RETRY_AFTER},
+    Client, Request, RequestBuilder, Response,
+};
+use serde::de::DeserializeOwned;
+#[cfg(feature = "async")]
+use std::time::Instant;
+use std::time::{Duration, SystemTime};
+
+/// Status code of responses that ask the client to slow down.
+const TOO_MANY_REQUESTS: u16 = 429;
+
+/// Maximum number of characters of a response body kept in errors.
+const MAX_ERROR_BODY_CHARS: usize = 1024;
+
+/// Send the request, returning an error if the response's status
+/// code isn't a success.
+///
+/// The error depends on the status code's category, with 429 responses
+/// giving a [`VatsimUtilError::RateLimited`]. Errors are passed to the
+/// error hook before being returned.
+#[cfg(feature = "async")]
+pub(crate) async fn send(
+    endpoint: Endpoint,
+    request: RequestBuilder,
+) -> Result<Response, VatsimUtilError> {
+    let (client, request, context, id) = build(endpoint, request)?;
+    execute(endpoint, id, &client, request)
+        .await
+        .inspect_err(|e| errors::report(e, &context))
+}
+
+/// Send the request and parse the response's body as JSON.
+///
+/// Failures to parse report the path to the field that didn't match the
+/// model. Errors are passed to the error hook before being returned.
+#[cfg(feature = "async")]
+pub(crate) async fn fetch_json<T: DeserializeOwned>(
+    endpoint: Endpoint,
+    request: RequestBuilder,
+) -> Result<T, VatsimUtilError> {
+    let (client, request, context, id) = build(endpoint, request)?;
+    let result = match execute(endpoint, id, &client, request).await {
+        Ok(response) => json(endpoint, id, response).await,
+        Err(e) => Err(e),
+    };
+    result.inspect_err(|e| errors::report(e, &context))
+}
+
+/// Build the request, adding the API key if it's for the core API, and
+/// getting its ID and the details for the error hook.
+#[cfg(feature = "async")]
+fn build(
+    endpoint: Endpoint,
+    request: RequestBuilder,
+) -> Result<(Client, Request, ErrorContext, RequestId), VatsimUtilError> {
+    let (client, request) = request.build_split();
+    match request {
+        Ok(mut request) => {
+            if let Some(key) = config::api_key_for(request.url().as_str()) {
+                if let Ok(mut value) = HeaderValue::from_str(&key) {
+                    value.set_sensitive(true);
+                    let _ = request.headers_mut().insert("X-API-Key", value);
+                }
+            }
+            let id = RequestId::next();
+            let context = ErrorContext {
+                request_id: Some(id),
+                endpoint,
+                method: Some(method(request.method())),
+                url: Some(request.url().to_string()),
+            };
+            Ok((client, request, context, id))
+        }
+        Err(e) => {
+            let error = VatsimUtilError::ReqwestError(e);
+            errors::report(
+                &error,
+                &ErrorContext {
+                    request_id: None,
+                    endpoint,
+                    method: None,
+                    url: None,
+                },
+            );
+            Err(error)
+        }
+    }
+}
+
+/// Send the request and check the response's status code.
+#[cfg(feature = "async")]
+async fn execute(
+    endpoint: Endpoint,
+    id: RequestId,
+    client: &Client,
+    request: Request,
+) -> Result<Response, VatsimUtilError> {
+    if endpoint != Endpoint::Webhook {
+        budget::acquire().await;
+    }
+    let method = method(request.method());
+    let url = request.url().to_string();
+    debug!("{id} {method} {url}");
+    let started = Instant::now();
+    let response = client
+        .execute(request)
+        .await
+        .inspect_err(|e| debug!("{id} failed: {e}"))
+        .map_err(|source| VatsimUtilError::Network { endpoint, source })?;
+    let status = response.status();
+    debug!("{id} {status} after {}ms", started.elapsed().as_millis());
+    if status.is_success() {
+        return Ok(response);
+    }
+    if status.as_u16() == TOO_MANY_REQUESTS {
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned);
+        return Err(status_error(
+            endpoint,
+            id,
+            method,
+            url,
+            status.as_u16(),
+            retry_after.as_deref(),
+            String::new(),
+        ));
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(status_error(
+        endpoint,
+        id,
+        method,
+        url,
+        status.as_u16(),
+        None,
+        body,
+    ))
+}
+
+/// Build the error for a response with an unsuccessful status code.
+///
+/// The `Retry-After` header's value and the body are only used for rate
+/// limiting and other errors, respectively.
+pub(crate) fn status_error(
+    endpoint: Endpoint,
+    request_id: RequestId,
+    method: Method,
+    url: String,
+    status: u16,
+    retry_after: Option<&str>,
+    body: String,
+) -> VatsimUtilError {
+    if status == TOO_MANY_REQUESTS {
+        return VatsimUtilError::RateLimited {
+            request_id,
+            endpoint,
+            method,
+            url,
+            retry_after: retry_after.and_then(parse_retry_after),
+        };
+    }
+    let context = ResponseContext {
+        request_id,
+        endpoint,
+        method,
+        url,
+        body: truncate(body),
+    };
+    match status {
+        500.. => VatsimUtilError::ServerError { status, context },
+        400.. => VatsimUtilError::ClientError { status, context },
+        _ => VatsimUtilError::Redirect { status, context },
+    }
+}
+
+/// The crate's name for the request's method.
+///
+/// The crate only sends the methods in [`Method`], so anything else is
+/// reported as a `GET`.
+#[cfg(feature = "async")]
+fn method(method: &reqwest::Method) -> Method {
+    match *method {
+        reqwest::Method::POST => Method::Post,
+        reqwest::Method::PUT => Method::Put,
+        reqwest::Method::PATCH => Method::Patch,
+        reqwest::Method::DELETE => Method::Delete,
+        _ => Method::Get,
+    }
+}
+
+/// Parse the response's body as JSON.
+#[cfg(feature = "async")]
+async fn json<T: DeserializeOwned>(
+    endpoint: Endpoint,
+    id: RequestId,
+    response: Response,
+) -> Result<T, VatsimUtilError> {
+    let url = response.url().to_string();
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|source| VatsimUtilError::Network { endpoint, source })?;
+    parse_json(endpoint, id, url, &bytes)
+}
+
+/// Read the response's body as text.
+#[cfg(feature = "async")]
+pub(crate) async fn text(
+    endpoint: Endpoint,
+    response: Response,
+) -> Result<String, VatsimUtilError> {
+    response
+        .text()
+        .await
+        .map_err(|source| VatsimUtilError::Network { endpoint, source })
+}
+
+/// Parse a response body as JSON, reporting the path to the field that
+/// didn't match the model on failure.
+///
+/// With the `strict-models` feature, fields that the model doesn't have
+/// are logged as warnings.
+pub(crate) fn parse_json<T: DeserializeOwned>(
+    endpoint: Endpoint,
+    request_id: RequestId,
+    url: String,
+    bytes: &[u8],
+) -> Result<T, VatsimUtilError> {
+    let deserializer = &mut serde_json::Deserializer::from_slice(bytes);
+    #[cfg(feature = "strict-models")]
+    let mut warn = |path: serde_ignored::Path| {
+        log::warn!("{request_id} unknown field from {endpoint}: {path}");
+    };
+    #[cfg(feature = "strict-models")]
+    let deserializer = serde_ignored::Deserializer::new(deserializer, &mut warn);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        VatsimUtilError::JsonSchemaMismatch {
+            request_id,
+            endpoint,
+            url,
+            path: e.path().to_string(),
+            source: e.into_inner(),
+        }
+    })
+}
+
+/// Shorten a response body to fit in an error.
+fn truncate(body: String) -> String {
+    match body.char_indices().nth(MAX_ERROR_BODY_CHARS) {
+        Some((index, _)) => format!("{}…", &body[..index]),
+        None => body,
+    }
+}
+
+/// Parse a `Retry-After` header's value, which is either a number of
+/// seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value.trim()).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
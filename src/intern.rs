@@ -0,0 +1,189 @@
+//! Sharing repeated strings between snapshots of live data.
+//!
+//! Services that keep many snapshots in memory - for replays, charts, or
+//! history - store the same server names, aircraft types, frequencies,
+//! and airports thousands of times over. A [`StringInterner`] hands out
+//! one [`Arc<str>`] per distinct string, and [`StringInterner::snapshot`]
+//! converts a snapshot into a compact [`InternedSnapshot`] of the fields
+//! that are usually kept, with those strings shared.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::{intern::StringInterner, live_api::Vatsim};
+//!
+//! # async fn _do() {
+//! let api = Vatsim::new().await.unwrap();
+//! let mut interner = StringInterner::new();
+//! let mut history = Vec::new();
+//! loop {
+//!     let data = api.get_v3_data().await.unwrap();
+//!     history.push(interner.snapshot(&data));
+//!     if history.len() > 1000 {
+//!         let _ = history.remove(0);
+//!         interner.purge();
+//!     }
+//!     // wait for the feed to update ...
+//! }
+//! # }
+//! ```
+
+use crate::models::{Controller, Pilot, V3ResponseData};
+use std::{collections::HashSet, sync::Arc};
+
+/// Set of shared strings.
+#[derive(Debug, Default)]
+pub struct StringInterner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl StringInterner {
+    /// Create an empty interner.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The shared copy of the string, adding it if it's new.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use vatsim_utils::intern::StringInterner;
+    ///
+    /// let mut interner = StringInterner::new();
+    /// let first = interner.intern("USA-WEST");
+    /// let second = interner.intern("USA-WEST");
+    /// assert!(Arc::ptr_eq(&first, &second));
+    /// ```
+    pub fn intern(&mut self, s: &str) -> Arc<str> {
+        if let Some(existing) = self.strings.get(s) {
+            return Arc::clone(existing);
+        }
+        let shared: Arc<str> = Arc::from(s);
+        let _ = self.strings.insert(Arc::clone(&shared));
+        shared
+    }
+
+    /// Number of distinct strings held.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Whether no strings are held.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Drop strings that aren't used anywhere else, so that the interner
+    /// doesn't keep growing as snapshots are discarded.
+    pub fn purge(&mut self) {
+        self.strings.retain(|s| Arc::strong_count(s) > 1);
+    }
+
+    /// Convert a pilot into its compact form.
+    pub fn pilot(&mut self, pilot: &Pilot) -> InternedPilot {
+        let flight_plan = pilot.flight_plan.as_ref();
+        InternedPilot {
+            cid: pilot.cid,
+            callsign: self.intern(&pilot.callsign),
+            server: self.intern(&pilot.server),
+            latitude: pilot.latitude,
+            longitude: pilot.longitude,
+            altitude: pilot.altitude,
+            groundspeed: pilot.groundspeed,
+            heading: pilot.heading,
+            transponder: self.intern(&pilot.transponder),
+            aircraft: flight_plan.map(|fp| self.intern(&fp.aircraft_short)),
+            departure: flight_plan.map(|fp| self.intern(&fp.departure)),
+            arrival: flight_plan.map(|fp| self.intern(&fp.arrival)),
+        }
+    }
+
+    /// Convert a controller into its compact form.
+    pub fn controller(&mut self, controller: &Controller) -> InternedController {
+        InternedController {
+            cid: controller.cid,
+            callsign: self.intern(&controller.callsign),
+            frequency: self.intern(&controller.frequency),
+            facility: controller.facility,
+            rating: controller.rating,
+            server: self.intern(&controller.server),
+        }
+    }
+
+    /// Convert a snapshot into its compact form.
+    pub fn snapshot(&mut self, data: &V3ResponseData) -> InternedSnapshot {
+        InternedSnapshot {
+            update_timestamp: data.general.update_timestamp.clone(),
+            pilots: data.pilots.iter().map(|p| self.pilot(p)).collect(),
+            controllers: data
+                .controllers
+                .iter()
+                .map(|c| self.controller(c))
+                .collect(),
+        }
+    }
+}
+
+/// The commonly-kept fields of a [`Pilot`], with repeated strings shared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InternedPilot {
+    /// Pilot's CID.
+    pub cid: u64,
+    /// Callsign the pilot is connected as.
+    pub callsign: Arc<str>,
+    /// Server the pilot is connected to.
+    pub server: Arc<str>,
+    /// Latitude, in degrees.
+    pub latitude: f64,
+    /// Longitude, in degrees.
+    pub longitude: f64,
+    /// Altitude, in feet.
+    pub altitude: i64,
+    /// Groundspeed, in knots.
+    pub groundspeed: i64,
+    /// Heading, in degrees.
+    pub heading: i64,
+    /// Squawk code.
+    pub transponder: Arc<str>,
+    /// Short aircraft type from the flight plan, if one was filed.
+    pub aircraft: Option<Arc<str>>,
+    /// Departure airport from the flight plan, if one was filed.
+    pub departure: Option<Arc<str>>,
+    /// Arrival airport from the flight plan, if one was filed.
+    pub arrival: Option<Arc<str>>,
+}
+
+/// The commonly-kept fields of a [`Controller`], with repeated strings
+/// shared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InternedController {
+    /// Controller's CID.
+    pub cid: u64,
+    /// Callsign the controller is connected as.
+    pub callsign: Arc<str>,
+    /// Primary frequency.
+    pub frequency: Arc<str>,
+    /// Facility type.
+    pub facility: i64,
+    /// Controller's rating.
+    pub rating: i8,
+    /// Server the controller is connected to.
+    pub server: Arc<str>,
+}
+
+/// The commonly-kept parts of a [`V3ResponseData`], with repeated strings
+/// shared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InternedSnapshot {
+    /// The snapshot's `general.update_timestamp`.
+    pub update_timestamp: String,
+    /// The pilots.
+    pub pilots: Vec<InternedPilot>,
+    /// The controllers, including observers.
+    pub controllers: Vec<InternedController>,
+}
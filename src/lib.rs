@@ -1,6 +1,39 @@
 //! `vatsim_utils` - a collection of utilities for accessing [VATSIM] data.
 //!
 //! [VATSIM]: https://vatsim.net/
+//!
+//! # Identifying your application
+//!
+//! VATSIM asks clients of its data to identify themselves. Before making
+//! any requests, set your application's name and contact details, which
+//! are sent in the user agent:
+//!
+//! ```rust
+//! use vatsim_utils::config::{set_client_config, ClientConfig};
+//!
+//! set_client_config(ClientConfig::default().contact("my-app/1.0", "me@example.com"));
+//! ```
+//!
+//! # Async runtimes
+//!
+//! The async clients are in the `async` feature, which is on by default;
+//! turn it off to build without them, like with only the
+//! `blocking` functions in the `sync-ureq` feature.
+//!
+//! The core of the crate doesn't spawn tasks or use any runtime's timers,
+//! so its async functions can be awaited from any executor. HTTP requests
+//! are made with [reqwest], however, which needs a [tokio] reactor to be
+//! running: under [async-std], enable its `tokio1` feature, and under
+//! [smol], wrap futures in [async-compat]'s `Compat`. The pieces that spawn
+//! tasks, like [`Vatsim::shared`](live_api::Vatsim::shared) in the `tokio`
+//! feature, and the mock server in the `testing` feature need a tokio
+//! runtime.
+//!
+//! [reqwest]: https://docs.rs/reqwest/latest/reqwest/
+//! [tokio]: https://docs.rs/tokio/latest/tokio/
+//! [async-std]: https://docs.rs/async-std/latest/async_std/
+//! [smol]: https://docs.rs/smol/latest/smol/
+//! [async-compat]: https://docs.rs/async-compat/latest/async_compat/
 
 #![deny(
     clippy::all,
@@ -15,10 +48,68 @@
     unused_qualifications,
     unused_results
 )]
+// Without an HTTP client, the request plumbing has nothing to serve.
+#![cfg_attr(
+    not(any(feature = "async", feature = "sync-ureq")),
+    allow(dead_code, unused_imports)
+)]
 
-#[cfg(feature = "airports")]
+pub mod analysis;
+#[cfg(feature = "sync-ureq")]
+pub mod blocking;
+pub mod bookings;
+pub mod boundaries;
+pub mod budget;
+pub mod config;
+pub mod descent;
+pub mod diff;
+#[cfg(feature = "discord")]
+pub mod discord;
 pub mod distance;
+pub mod dns;
+#[cfg(feature = "async")]
+pub mod drift;
+pub mod emergencies;
 pub mod errors;
+pub mod events;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+pub mod frequencies;
+pub mod geojson;
+#[cfg(any(feature = "async", feature = "sync-ureq"))]
+mod http;
+pub mod intern;
+#[cfg(feature = "async")]
 pub mod live_api;
+#[cfg(feature = "magnetic")]
+pub mod magnetic;
+pub mod memo;
 pub mod models;
+pub mod occupancy;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "async")]
 pub mod rest_api;
+pub mod routes;
+#[cfg(feature = "chrono")]
+pub mod sessions;
+pub mod spatial;
+#[cfg(feature = "chrono")]
+pub mod stats;
+pub mod sun;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub mod timestamps;
+pub mod tracking;
+#[cfg(feature = "uom")]
+pub mod units;
+pub mod urls;
+pub mod validation;
+#[cfg(feature = "webhooks")]
+pub mod webhooks;
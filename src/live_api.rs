@@ -1,7 +1,8 @@
 //! Wrapper for the VATSIM APIs to get live data from the servers.
 //!
 //! These functions are [async], as they deal with HTTP requests. You'll need
-//! to use an async runtime like [tokio] to run them.
+//! to use an async runtime like [tokio] to run them; see the
+//! [crate docs](crate#async-runtimes) for others.
 //!
 //! See the [struct] docs for usage information.
 //!
@@ -21,15 +22,334 @@
 //! ```
 
 use crate::{
-    errors::VatsimUtilError,
-    models::{Status, StatusData, TransceiverResponseEntry, V3ResponseData},
+    config::{self, ClientConfig},
+    errors::{self, BatchResults, Endpoint, ErrorContext, VatsimUtilError},
+    http,
+    models::{
+        Controller, GeneralData, Pilot, Server, Status, TransceiverResponseEntry, V3ResponseData,
+    },
+    urls,
+    validation::{sanitize_pilots, SanitizePolicy},
 };
-use log::debug;
-use rand::seq::SliceRandom;
-use reqwest::{Client, ClientBuilder};
+use futures_util::{stream, Stream};
+use log::{debug, warn};
+use rand::{seq::SliceRandom, Rng};
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize};
+#[cfg(feature = "tokio")]
+use std::sync::{Arc, Weak};
+use std::{
+    sync::{PoisonError, RwLock},
+    time::Duration,
+};
+
+/// Only the general information and pilots from the V3 feed; the rest
+/// of the document is skipped.
+#[derive(Deserialize)]
+struct PilotsSection {
+    general: GeneralData,
+    pilots: Vec<Pilot>,
+}
+
+/// Only the general information and controllers from the V3 feed.
+#[derive(Deserialize)]
+struct ControllersSection {
+    general: GeneralData,
+    controllers: Vec<Controller>,
+}
+
+/// Only the general information from the V3 feed.
+#[derive(Deserialize)]
+struct GeneralSection {
+    general: GeneralData,
+}
+
+/// How long to wait between polls of the V3 feed, following the reload
+/// interval that the feed advertises in `general.reload`.
+///
+/// The advertised interval is clamped between a minimum and maximum, and
+/// randomly adjusted by up to the jitter fraction so that many clients
+/// don't poll in lockstep.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use vatsim_utils::live_api::{PollInterval, Vatsim};
+///
+/// # async fn sleep(_: Duration) {}
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let interval = PollInterval::default().min(Duration::from_secs(30));
+/// loop {
+///     let data = api.get_v3_data().await.unwrap();
+///     // use data ...
+///     sleep(interval.next_delay(&data.general)).await;
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PollInterval {
+    min: Duration,
+    max: Duration,
+    jitter: f64,
+}
+
+impl Default for PollInterval {
+    /// Between 15 seconds and 5 minutes, with 10% jitter.
+    fn default() -> Self {
+        Self {
+            min: Duration::from_secs(15),
+            max: Duration::from_secs(300),
+            jitter: 0.1,
+        }
+    }
+}
+
+impl PollInterval {
+    /// Never wait less than this.
+    #[must_use]
+    pub fn min(mut self, min: Duration) -> Self {
+        self.min = min;
+        self
+    }
+
+    /// Never wait more than this.
+    #[must_use]
+    pub fn max(mut self, max: Duration) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// Fraction of the interval, from 0 to 1, to randomly add or subtract.
+    ///
+    /// A value that isn't finite turns jitter off.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use vatsim_utils::{live_api::PollInterval, models::GeneralData};
+    /// # let general: GeneralData = serde_json::from_str(r#"{"version":3,"reload":1,"update":"","update_timestamp":"","connected_clients":0,"unique_users":0}"#).unwrap();
+    /// let interval = PollInterval::default().jitter(f64::NAN);
+    ///
+    /// assert_eq!(interval.next_delay(&general), interval.base_delay(&general));
+    /// ```
+    #[must_use]
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = finite_or_zero(jitter).clamp(0.0, 1.0);
+        self
+    }
+
+    /// The interval advertised by the data, clamped to the minimum and
+    /// maximum, without jitter.
+    ///
+    /// The feed's `reload` is in minutes; values of zero or less use
+    /// the minimum.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// # use vatsim_utils::{live_api::PollInterval, models::GeneralData};
+    /// # let mut general: GeneralData = serde_json::from_str(r#"{"version":3,"reload":1,"update":"","update_timestamp":"","connected_clients":0,"unique_users":0}"#).unwrap();
+    ///
+    /// general.reload = 1;
+    /// assert_eq!(PollInterval::default().base_delay(&general), Duration::from_secs(60));
+    /// general.reload = 60;
+    /// assert_eq!(PollInterval::default().base_delay(&general), Duration::from_secs(300));
+    /// ```
+    #[must_use]
+    pub fn base_delay(&self, general: &GeneralData) -> Duration {
+        let advertised = u64::try_from(general.reload).map_or(Duration::ZERO, |minutes| {
+            Duration::from_secs(minutes.saturating_mul(60))
+        });
+        advertised.clamp(self.min, self.max.max(self.min))
+    }
+
+    /// How long to wait before polling again, with jitter.
+    #[must_use]
+    pub fn next_delay(&self, general: &GeneralData) -> Duration {
+        let base = self.base_delay(general);
+        let jitter = finite_or_zero(self.jitter).clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return base;
+        }
+        let factor = rand::thread_rng().gen_range(-jitter..=jitter);
+        Duration::try_from_secs_f64(base.as_secs_f64() * (1.0 + factor))
+            .unwrap_or(base)
+            .max(self.min)
+    }
+}
+
+/// The value, or zero if it's NaN or infinite.
+fn finite_or_zero(value: f64) -> f64 {
+    if value.is_finite() {
+        value
+    } else {
+        0.0
+    }
+}
+
+/// All of the mirrors for a feed, and which is being used.
+#[derive(Debug)]
+struct Mirrors {
+    urls: Vec<String>,
+    current: usize,
+}
+
+impl Mirrors {
+    /// Use the mirrors, starting at a random one to spread the load.
+    fn shuffled(urls: Vec<String>) -> Option<Self> {
+        if urls.is_empty() {
+            return None;
+        }
+        let current = rand::thread_rng().gen_range(0..urls.len());
+        Some(Self { urls, current })
+    }
+
+    /// Use just the one URL.
+    fn single(url: String) -> Self {
+        Self {
+            urls: vec![url],
+            current: 0,
+        }
+    }
+
+    /// The mirror being used.
+    fn current(&self) -> &str {
+        &self.urls[self.current]
+    }
+
+    /// Move on to the next mirror, if `failed` is still the one being
+    /// used; another request may have moved on already.
+    fn fail_over(&mut self, failed: &str) {
+        if self.current() == failed {
+            self.current = (self.current + 1) % self.urls.len();
+            debug!("Mirror {failed} failed, switching to {}", self.current());
+        }
+    }
+}
+
+/// Live data URLs from the status endpoint.
+#[derive(Debug)]
+struct LiveUrls {
+    v3: Mirrors,
+    transceivers: Mirrors,
+    sweatbox_servers: Option<String>,
+    metar: Option<String>,
+}
+
+/// The live data feeds that have mirrors.
+#[derive(Debug, Clone, Copy)]
+enum Feed {
+    V3,
+    Transceivers,
+}
+
+/// Builder for a [`Vatsim`] instance, from [`Vatsim::builder`].
+#[derive(Debug)]
+pub struct VatsimBuilder {
+    client: Option<Client>,
+    config: ClientConfig,
+    v3_url: Option<String>,
+    transceivers_url: Option<String>,
+    sweatbox_servers_url: Option<String>,
+}
 
-/// Initial VATSIM API requests are made to this endpoint.
-const STATUS_URL: &str = "https://status.vatsim.net/status.json";
+impl VatsimBuilder {
+    /// Use the HTTP client for all requests, rather than creating one.
+    ///
+    /// The [user agent](Self::user_agent) and [timeout](Self::timeout)
+    /// are ignored, as they're part of the client.
+    #[must_use]
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Replace the whole user agent sent with requests; see
+    /// [`ClientConfig::user_agent`].
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.config = self.config.user_agent(user_agent);
+        self
+    }
+
+    /// Set the time limit for a whole request; see
+    /// [`ClientConfig::timeout`].
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config = self.config.timeout(timeout);
+        self
+    }
+
+    /// Use the URL for the V3 feed.
+    ///
+    /// When both this and the [transceivers URL](Self::transceivers_url)
+    /// are set, the status endpoint isn't called at all.
+    #[must_use]
+    pub fn v3_url(mut self, url: &str) -> Self {
+        self.v3_url = Some(url.to_owned());
+        self
+    }
+
+    /// Use the URL for the transceivers feed.
+    #[must_use]
+    pub fn transceivers_url(mut self, url: &str) -> Self {
+        self.transceivers_url = Some(url.to_owned());
+        self
+    }
+
+    /// Use the URL for the sweatbox servers.
+    #[must_use]
+    pub fn sweatbox_servers_url(mut self, url: &str) -> Self {
+        self.sweatbox_servers_url = Some(url.to_owned());
+        self
+    }
+
+    /// Create the API struct instance, getting any URLs that weren't
+    /// supplied from the status endpoint.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the HTTP request to the VATSIM API status
+    /// endpoint fails, when it's needed.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the HTTP user agent cannot be constructed, which
+    /// should never happen.
+    pub async fn build(self) -> Result<Vatsim, VatsimUtilError> {
+        debug!("Creating VATSIM struct instance");
+        let client = self
+            .client
+            .unwrap_or_else(|| config::build_client_with(&self.config));
+        let urls = match (self.v3_url, self.transceivers_url) {
+            (Some(v3), Some(transceivers)) => LiveUrls {
+                v3: Mirrors::single(v3),
+                transceivers: Mirrors::single(transceivers),
+                sweatbox_servers: self.sweatbox_servers_url,
+                metar: None,
+            },
+            (v3, transceivers) => {
+                let fetched = Vatsim::get_endpoint_urls(&client).await?;
+                LiveUrls {
+                    v3: v3.map_or(fetched.v3, Mirrors::single),
+                    transceivers: transceivers.map_or(fetched.transceivers, Mirrors::single),
+                    sweatbox_servers: self.sweatbox_servers_url.or(fetched.sweatbox_servers),
+                    metar: fetched.metar,
+                }
+            }
+        };
+        Ok(Vatsim {
+            client,
+            urls: RwLock::new(urls),
+            max_age: None,
+            sanitize: None,
+            auto_refresh: false,
+        })
+    }
+}
 
 /// Struct containing access to the VATSIM live APIs - those
 /// listed on the [VATSIM Developer Info wiki page].
@@ -38,8 +358,10 @@ const STATUS_URL: &str = "https://status.vatsim.net/status.json";
 #[derive(Debug)]
 pub struct Vatsim {
     client: Client,
-    v3_url: String,
-    transceivers_url: String,
+    urls: RwLock<LiveUrls>,
+    max_age: Option<Duration>,
+    sanitize: Option<SanitizePolicy>,
+    auto_refresh: bool,
 }
 
 impl Vatsim {
@@ -47,7 +369,9 @@ impl Vatsim {
     ///
     /// Internally, this function also makes the API call to the status
     /// endpoint to get the endpoint to make later API calls, which
-    /// is why this function is also `async`.
+    /// is why this function is also `async`. The status endpoint can
+    /// be changed with [`urls::set_endpoints`]. Use [`builder`](Self::builder)
+    /// to supply the URLs or the HTTP client instead.
     ///
     /// # Example
     ///
@@ -70,41 +394,365 @@ impl Vatsim {
     /// Will panic if the HTTP user agent cannot be constructed, which
     /// should never happen.
     pub async fn new() -> Result<Self, VatsimUtilError> {
-        debug!("Creating VATSIM struct instance");
-        let client = ClientBuilder::new()
-            .user_agent("github.com/celeo/vatsim_utils")
-            .build()
-            .expect("Invalid HTTP Agent");
-        let (v3_url, transceivers_url) = Vatsim::get_endpoint_urls(&client).await?;
-        Ok(Self {
-            client,
-            v3_url,
-            transceivers_url,
-        })
+        Self::builder().build().await
     }
 
-    /// Get the V3 and transceivers URLs by querying the status endpoint.
-    async fn get_endpoint_urls(client: &Client) -> Result<(String, String), VatsimUtilError> {
-        debug!("Getting V3 url from status page");
-        let response = client.get(STATUS_URL).send().await?;
-        if !response.status().is_success() {
-            return Err(VatsimUtilError::InvalidStatusCode(
-                response.status().as_u16(),
-            ));
+    /// Start configuring a new API struct instance, with the HTTP client
+    /// settings from [`config::client_config`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use vatsim_utils::live_api::Vatsim;
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::builder()
+    ///     .user_agent("my-app/1.0 (ops@example.com)")
+    ///     .timeout(Duration::from_secs(10))
+    ///     .v3_url("https://data.vatsim.net/v3/vatsim-data.json")
+    ///     .transceivers_url("https://data.vatsim.net/v3/transceivers-data.json")
+    ///     .build()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn builder() -> VatsimBuilder {
+        VatsimBuilder {
+            client: None,
+            config: config::client_config(),
+            v3_url: None,
+            transceivers_url: None,
+            sweatbox_servers_url: None,
         }
-        let data: StatusData = (response.json::<Status>().await?).data;
-        let v3_url = data
-            .v3
-            .choose(&mut rand::thread_rng())
-            .expect("No VATSIM V3 API URLs returned")
-            .clone();
-        let transceivers_url = data
-            .transceivers
+    }
+
+    /// Return a [`VatsimUtilError::StaleData`] error from
+    /// [`get_v3_data`](Self::get_v3_data), [`get_pilots`](Self::get_pilots),
+    /// and [`get_controllers`](Self::get_controllers) for data that was
+    /// last updated longer ago than the maximum age.
+    ///
+    /// VATSIM's mirrors occasionally stop updating while still serving
+    /// data, which otherwise goes unnoticed. The feed normally updates
+    /// every 15 seconds, so a maximum of a minute or two is reasonable.
+    /// Data with an update time that can't be parsed isn't checked.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use vatsim_utils::{errors::VatsimUtilError, live_api::Vatsim};
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new()
+    ///     .await
+    ///     .unwrap()
+    ///     .max_age(Duration::from_secs(120));
+    /// match api.get_v3_data().await {
+    ///     Ok(data) => println!("{} pilots", data.pilots.len()),
+    ///     Err(VatsimUtilError::StaleData { age, .. }) => {
+    ///         println!("Data is {} seconds old", age.as_secs());
+    ///     }
+    ///     Err(e) => println!("{e}"),
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Deal with pilots with impossible positions, groundspeeds, or
+    /// altitudes in [`get_v3_data`](Self::get_v3_data) and
+    /// [`get_pilots`](Self::get_pilots) according to the policy.
+    ///
+    /// Pilots that are found are logged at the warn level. See
+    /// [`sanitize_pilots`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::{live_api::Vatsim, validation::SanitizePolicy};
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new()
+    ///     .await
+    ///     .unwrap()
+    ///     .sanitize(SanitizePolicy::Drop);
+    /// let pilots = api.get_pilots().await.unwrap();
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn sanitize(mut self, policy: SanitizePolicy) -> Self {
+        self.sanitize = Some(policy);
+        self
+    }
+
+    /// [Refresh](Self::refresh_endpoints) the URLs from the status
+    /// endpoint when requests to every mirror of a feed fail, then try
+    /// the request again.
+    ///
+    /// Mirrors are occasionally rotated out, which long-running processes
+    /// otherwise only notice by failing until they're restarted. The
+    /// status endpoint is only asked once per request, and if it can't be
+    /// reached, the current URLs are kept and the request's error is
+    /// returned. URLs supplied to the [builder](Self::builder) are
+    /// replaced by those from the status endpoint.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::live_api::Vatsim;
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap().auto_refresh(true);
+    /// let data = api.get_v3_data().await.unwrap();
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn auto_refresh(mut self, enabled: bool) -> Self {
+        self.auto_refresh = enabled;
+        self
+    }
+
+    /// Sanitize the pilots with the policy, if one is set.
+    fn sanitize_pilots(&self, pilots: &mut Vec<Pilot>) {
+        let Some(policy) = self.sanitize else {
+            return;
+        };
+        for issue in sanitize_pilots(pilots, policy) {
+            warn!("Bogus pilot data ({policy:?}): {issue:?}");
+        }
+    }
+
+    /// Check the data's age against the maximum, if one is set.
+    fn check_age(&self, general: &GeneralData, url: &str) -> Result<(), VatsimUtilError> {
+        let (Some(max_age), Some(age)) = (self.max_age, general.age()) else {
+            return Ok(());
+        };
+        if age <= max_age {
+            return Ok(());
+        }
+        let error = VatsimUtilError::StaleData {
+            updated: general.update_timestamp.clone(),
+            age,
+        };
+        errors::report(
+            &error,
+            &ErrorContext {
+                request_id: None,
+                endpoint: Endpoint::V3Feed,
+                method: Some(errors::Method::Get),
+                url: Some(url.to_owned()),
+            },
+        );
+        Err(error)
+    }
+
+    /// Create a new API struct instance behind an [`Arc`], with a
+    /// background task that [refreshes](Self::refresh_endpoints) its URLs
+    /// from the status endpoint at the interval.
+    ///
+    /// This keeps long-running processes from using a mirror after it's
+    /// been removed from the status endpoint. The task stops once all of
+    /// the `Arc`s have been dropped. Failures to refresh are logged, and
+    /// the previous URLs kept.
+    ///
+    /// Requires the `tokio` feature, and must be called within a [tokio]
+    /// runtime.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use vatsim_utils::live_api::Vatsim;
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::shared(Duration::from_secs(60 * 60)).await.unwrap();
+    /// let data = api.get_v3_data().await.unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can fail for the same reasons as [`new`](Self::new).
+    ///
+    /// # Panics
+    ///
+    /// Will panic if called outside of a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub async fn shared(refresh_every: Duration) -> Result<Arc<Self>, VatsimUtilError> {
+        let api = Arc::new(Self::new().await?);
+        let weak = Arc::downgrade(&api);
+        drop(tokio::spawn(refresh_periodically(weak, refresh_every)));
+        Ok(api)
+    }
+
+    /// Get new V3 and transceivers URLs from the status endpoint,
+    /// replacing the current ones.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::live_api::Vatsim;
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap();
+    /// // ... some time later
+    /// api.refresh_endpoints().await.unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the HTTP request to the VATSIM API status
+    /// endpoint fails, in which case the current URLs are kept.
+    pub async fn refresh_endpoints(&self) -> Result<(), VatsimUtilError> {
+        let urls = Vatsim::get_endpoint_urls(&self.client).await?;
+        *self.urls.write().unwrap_or_else(PoisonError::into_inner) = urls;
+        Ok(())
+    }
+
+    /// The V3 feed mirror currently being used, for logging.
+    ///
+    /// The status endpoint lists several mirrors of each feed. One is
+    /// picked at random, and when a request to it fails, or returns data
+    /// older than the [maximum age](Self::max_age), the request is tried
+    /// again with the next mirror, which is then used from then on.
+    #[must_use]
+    pub fn v3_mirror(&self) -> String {
+        self.current_url(Feed::V3)
+    }
+
+    /// The transceivers feed mirror currently being used, for logging.
+    ///
+    /// See [`v3_mirror`](Self::v3_mirror) for how mirrors are chosen.
+    #[must_use]
+    pub fn transceivers_mirror(&self) -> String {
+        self.current_url(Feed::Transceivers)
+    }
+
+    /// The mirror currently being used for the feed.
+    fn current_url(&self, feed: Feed) -> String {
+        let urls = self.urls.read().unwrap_or_else(PoisonError::into_inner);
+        match feed {
+            Feed::V3 => urls.v3.current(),
+            Feed::Transceivers => urls.transceivers.current(),
+        }
+        .to_owned()
+    }
+
+    /// Move the feed on from a mirror that failed.
+    fn fail_over(&self, feed: Feed, failed: &str) {
+        let mut urls = self.urls.write().unwrap_or_else(PoisonError::into_inner);
+        match feed {
+            Feed::V3 => urls.v3.fail_over(failed),
+            Feed::Transceivers => urls.transceivers.fail_over(failed),
+        }
+    }
+
+    /// Fetch JSON from the feed, checking it with `check`, and trying each
+    /// mirror in turn while requests fail, then refreshing the mirrors if
+    /// [auto-refresh](Self::auto_refresh) is on.
+    ///
+    /// Errors from a response that couldn't be parsed aren't retried, as
+    /// every mirror serves the same data.
+    async fn fetch_feed<T, F>(
+        &self,
+        feed: Feed,
+        endpoint: Endpoint,
+        check: F,
+    ) -> Result<T, VatsimUtilError>
+    where
+        T: DeserializeOwned,
+        F: Fn(&T, &str) -> Result<(), VatsimUtilError>,
+    {
+        let mut attempts = self.mirror_count(feed);
+        let mut refreshed = !self.auto_refresh;
+        let mut attempt = 1;
+        loop {
+            let url = self.current_url(feed);
+            let result = match http::fetch_json(endpoint, self.client.get(&url)).await {
+                Ok(data) => check(&data, &url).map(|()| data),
+                Err(e) => Err(e),
+            };
+            match result {
+                Err(e) if attempt < attempts && should_fail_over(&e) => {
+                    warn!("Request to mirror {url} failed, trying the next: {e}");
+                    self.fail_over(feed, &url);
+                    attempt += 1;
+                }
+                Err(e) if !refreshed && should_fail_over(&e) => {
+                    warn!("Requests to every mirror failed, refreshing URLs: {e}");
+                    refreshed = true;
+                    if let Err(refresh_error) = self.refresh_endpoints().await {
+                        warn!("Could not refresh live data URLs: {refresh_error}");
+                        return Err(e);
+                    }
+                    attempts = self.mirror_count(feed);
+                    attempt = 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Number of mirrors of the feed.
+    fn mirror_count(&self, feed: Feed) -> usize {
+        let urls = self.urls.read().unwrap_or_else(PoisonError::into_inner);
+        match feed {
+            Feed::V3 => urls.v3.urls.len(),
+            Feed::Transceivers => urls.transceivers.urls.len(),
+        }
+    }
+
+    /// The current sweatbox servers URL, if the status endpoint listed one.
+    fn sweatbox_servers_url(&self) -> Option<String> {
+        self.urls
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .sweatbox_servers
+            .clone()
+    }
+
+    /// The METAR URL from the status endpoint, or the METAR service from
+    /// [`urls::endpoints`] if it didn't list one.
+    fn metar_url(&self) -> String {
+        self.urls
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .metar
+            .clone()
+            .unwrap_or_else(|| format!("{}/metar.php", urls::endpoints().metar))
+    }
+
+    /// Get the live data URLs by querying the status endpoint.
+    async fn get_endpoint_urls(client: &Client) -> Result<LiveUrls, VatsimUtilError> {
+        debug!("Getting V3 url from status page");
+        let status: Status =
+            http::fetch_json(Endpoint::Status, client.get(urls::endpoints().status)).await?;
+        let data = status.data;
+        let v3 = Mirrors::shuffled(data.v3).ok_or(VatsimUtilError::NoV3Url())?;
+        let transceivers =
+            Mirrors::shuffled(data.transceivers).ok_or(VatsimUtilError::NoTransceiversUrl())?;
+        let sweatbox_url = data
+            .servers_sweatbox
             .choose(&mut rand::thread_rng())
-            .expect("No VATSIM transceivers API URLs returned")
-            .clone();
-        debug!("V3 URL: {v3_url}, transceiver URL: {transceivers_url}");
-        Ok((v3_url, transceivers_url))
+            .cloned();
+        let metar_url = status.metar.choose(&mut rand::thread_rng()).cloned();
+        debug!(
+            "V3 URL: {}, transceiver URL: {}",
+            v3.current(),
+            transceivers.current()
+        );
+        Ok(LiveUrls {
+            v3,
+            transceivers,
+            sweatbox_servers: sweatbox_url,
+            metar: metar_url,
+        })
     }
 
     /// Query the stored V3 endpoint.
@@ -126,22 +774,22 @@ impl Vatsim {
     ///
     /// # Errors
     ///
-    /// This function can fail if the HTTP request fails or if the returned
+    /// This function can fail if the HTTP request fails, if the returned
     /// data does not match the schemas of the models passed to the
-    /// deserializer.
+    /// deserializer, or if the data is older than the
+    /// [maximum age](Self::max_age).
     ///
     /// # Panics
     ///
     /// Could panic if the callsign `String`s fail `partial_cmp`.
     pub async fn get_v3_data(&self) -> Result<V3ResponseData, VatsimUtilError> {
         debug!("Getting current V3 data");
-        let response = self.client.get(&self.v3_url).send().await?;
-        if !response.status().is_success() {
-            return Err(VatsimUtilError::InvalidStatusCode(
-                response.status().as_u16(),
-            ));
-        }
-        let mut data: V3ResponseData = response.json().await?;
+        let mut data: V3ResponseData = self
+            .fetch_feed(Feed::V3, Endpoint::V3Feed, |data: &V3ResponseData, url| {
+                self.check_age(&data.general, url)
+            })
+            .await?;
+        self.sanitize_pilots(&mut data.pilots);
         data.pilots
             .sort_by(|a, b| a.callsign.partial_cmp(&b.callsign).unwrap());
         data.controllers
@@ -149,6 +797,50 @@ impl Vatsim {
         Ok(data)
     }
 
+    /// Poll the V3 endpoint, yielding each snapshot as a stream.
+    ///
+    /// The first poll is made straight away, and each after it once the
+    /// [interval](PollInterval) has passed, following the reload cadence
+    /// that the feed advertises. Failed polls are yielded as errors and
+    /// retried after the interval's minimum, so the stream never ends on
+    /// its own; stop polling it, or drop it, to stop. Timers don't depend
+    /// on any particular async runtime.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use futures_util::{pin_mut, StreamExt};
+    /// use vatsim_utils::live_api::{PollInterval, Vatsim};
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap();
+    /// let updates = api.subscribe(PollInterval::default());
+    /// pin_mut!(updates);
+    /// while let Some(result) = updates.next().await {
+    ///     match result {
+    ///         Ok(data) => println!("{} pilots", data.pilots.len()),
+    ///         Err(e) => eprintln!("{e}"),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn subscribe(
+        &self,
+        interval: PollInterval,
+    ) -> impl Stream<Item = Result<V3ResponseData, VatsimUtilError>> + '_ {
+        stream::unfold(Duration::ZERO, move |delay| async move {
+            if !delay.is_zero() {
+                futures_timer::Delay::new(delay).await;
+            }
+            let result = self.get_v3_data().await;
+            let next = match &result {
+                Ok(data) => interval.next_delay(&data.general),
+                Err(_) => interval.min,
+            };
+            Some((result, next))
+        })
+    }
+
     /// Get pilot transceiver frequency data.
     ///
     /// # Example
@@ -172,13 +864,257 @@ impl Vatsim {
         &self,
     ) -> Result<Vec<TransceiverResponseEntry>, VatsimUtilError> {
         debug!("Getting current transceivers data");
-        let response = self.client.get(&self.transceivers_url).send().await?;
-        if !response.status().is_success() {
-            return Err(VatsimUtilError::InvalidStatusCode(
-                response.status().as_u16(),
-            ));
-        }
-        let data = response.json().await?;
+        let data = self
+            .fetch_feed(Feed::Transceivers, Endpoint::Transceivers, |_, _| Ok(()))
+            .await?;
         Ok(data)
     }
+
+    /// Get the sweatbox servers, which are used for training rather than
+    /// live traffic.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::live_api::Vatsim;
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap();
+    /// let servers = api.get_sweatbox_servers().await.unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the status endpoint didn't list a URL
+    /// for the sweatbox servers, if the HTTP request fails, or if the
+    /// returned data does not match the schemas of the models passed to
+    /// the deserializer.
+    pub async fn get_sweatbox_servers(&self) -> Result<Vec<Server>, VatsimUtilError> {
+        debug!("Getting sweatbox servers");
+        let url = self
+            .sweatbox_servers_url()
+            .ok_or(VatsimUtilError::NoSweatboxServersUrl())?;
+        http::fetch_json(Endpoint::SweatboxServers, self.client.get(url)).await
+    }
+
+    /// Query the stored V3 endpoint for only the pilots, sorted by
+    /// their callsigns.
+    ///
+    /// The rest of the feed is skipped while parsing rather than being
+    /// deserialized into models, which is faster and uses less memory
+    /// than [`get_v3_data`](Self::get_v3_data) when only the pilots
+    /// are needed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::live_api::Vatsim;
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap();
+    /// let pilots = api.get_pilots().await.unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the HTTP request fails, if the returned
+    /// data does not match the schemas of the models passed to the
+    /// deserializer, or if the data is older than the
+    /// [maximum age](Self::max_age).
+    pub async fn get_pilots(&self) -> Result<Vec<Pilot>, VatsimUtilError> {
+        debug!("Getting current V3 pilots");
+        let PilotsSection { mut pilots, .. } = self
+            .fetch_feed(Feed::V3, Endpoint::V3Feed, |data: &PilotsSection, url| {
+                self.check_age(&data.general, url)
+            })
+            .await?;
+        self.sanitize_pilots(&mut pilots);
+        pilots.sort_by(|a, b| a.callsign.cmp(&b.callsign));
+        Ok(pilots)
+    }
+
+    /// Query the stored V3 endpoint for only the controllers, sorted by
+    /// their callsigns.
+    ///
+    /// See [`get_pilots`](Self::get_pilots) for why.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the HTTP request fails, if the returned
+    /// data does not match the schemas of the models passed to the
+    /// deserializer, or if the data is older than the
+    /// [maximum age](Self::max_age).
+    pub async fn get_controllers(&self) -> Result<Vec<Controller>, VatsimUtilError> {
+        debug!("Getting current V3 controllers");
+        let ControllersSection {
+            mut controllers, ..
+        } = self
+            .fetch_feed(
+                Feed::V3,
+                Endpoint::V3Feed,
+                |data: &ControllersSection, url| self.check_age(&data.general, url),
+            )
+            .await?;
+        controllers.sort_by(|a, b| a.callsign.cmp(&b.callsign));
+        Ok(controllers)
+    }
+
+    /// Query the stored V3 endpoint for only the general information,
+    /// like when the data was last updated.
+    ///
+    /// See [`get_pilots`](Self::get_pilots) for why. The data's age isn't
+    /// checked against the [maximum](Self::max_age), so this can be used
+    /// to see how stale the feed is.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the HTTP request fails or if the returned
+    /// data does not match the schemas of the models passed to the
+    /// deserializer.
+    pub async fn get_general(&self) -> Result<GeneralData, VatsimUtilError> {
+        debug!("Getting current V3 general data");
+        let data: GeneralSection = self
+            .fetch_feed(Feed::V3, Endpoint::V3Feed, |_, _| Ok(()))
+            .await?;
+        Ok(data.general)
+    }
+
+    /// Check whether a user is connected to the network, as a pilot or
+    /// controller.
+    ///
+    /// This asks VATSIM's slurper, which responds with a line or two of
+    /// text, rather than downloading the whole V3 feed. If the slurper
+    /// can't be reached, it falls back to looking for the user in the feed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::live_api::Vatsim;
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap();
+    /// if api.is_cid_online(1234567890).await.unwrap() {
+    ///     println!("Online");
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if both the slurper and V3 feed requests
+    /// fail, or if the V3 feed does not match the schemas of the models
+    /// passed to the deserializer.
+    pub async fn is_cid_online(&self, cid: u64) -> Result<bool, VatsimUtilError> {
+        debug!("Checking if {cid} is online");
+        match self.slurper_online(cid).await {
+            Ok(online) => Ok(online),
+            Err(e) => {
+                debug!("Slurper check failed, falling back to the V3 feed: {e}");
+                let data = self.get_v3_data().await?;
+                Ok(data.pilots.iter().any(|p| p.cid == cid)
+                    || data.controllers.iter().any(|c| c.cid == cid))
+            }
+        }
+    }
+
+    /// Get the current METAR for a station, as raw text.
+    ///
+    /// Returns `None` if the METAR service has nothing for the station.
+    /// The service also matches on the start of the identifier, so asking
+    /// for a partial one like `"KS"` returns a line for each match.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::live_api::Vatsim;
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap();
+    /// if let Some(metar) = api.get_metar("KSFO").await.unwrap() {
+    ///     println!("{metar}");
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the HTTP request fails.
+    pub async fn get_metar(&self, icao: &str) -> Result<Option<String>, VatsimUtilError> {
+        debug!("Getting METAR for {icao}");
+        let request = self
+            .client
+            .get(self.metar_url())
+            .query(&[("id", icao.trim().to_uppercase())]);
+        let response = http::send(Endpoint::Metar, request).await?;
+        let text = http::text(Endpoint::Metar, response).await?;
+        let text = text.trim();
+        Ok((!text.is_empty()).then(|| text.to_owned()))
+    }
+
+    /// Get the current METARs for several stations, as raw text.
+    ///
+    /// Stations are looked up one after another; a failed lookup doesn't
+    /// stop the rest, and its error is returned alongside the METARs that
+    /// were found. See [`get_metar`](Self::get_metar) for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::live_api::Vatsim;
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap();
+    /// let results = api.get_metars(&["KSFO", "KLAX"]).await;
+    /// for (station, metar) in &results.successes {
+    ///     println!("{station}: {}", metar.as_deref().unwrap_or("none"));
+    /// }
+    /// # }
+    /// ```
+    pub async fn get_metars(&self, stations: &[&str]) -> BatchResults<String, Option<String>> {
+        let mut results = BatchResults::default();
+        for &station in stations {
+            let key = station.trim().to_uppercase();
+            match self.get_metar(station).await {
+                Ok(metar) => results.successes.push((key, metar)),
+                Err(error) => results.errors.failures.push((key, error)),
+            }
+        }
+        results
+    }
+
+    /// Ask the slurper whether the user is online.
+    async fn slurper_online(&self, cid: u64) -> Result<bool, VatsimUtilError> {
+        let url = format!("{}/users/info/", urls::endpoints().slurper);
+        let request = self.client.get(url).query(&[("cid", cid)]);
+        let response = http::send(Endpoint::Slurper, request).await?;
+        let text = http::text(Endpoint::Slurper, response).await?;
+        let cid = cid.to_string();
+        Ok(text
+            .lines()
+            .any(|line| line.split(',').next().map(str::trim) == Some(cid.as_str())))
+    }
+}
+
+/// Whether a request that failed with the error might succeed on another
+/// mirror.
+fn should_fail_over(error: &VatsimUtilError) -> bool {
+    error.is_network()
+        || error.status_code().is_some()
+        || matches!(error, VatsimUtilError::StaleData { .. })
+}
+
+/// Refresh the client's URLs at the interval until it's dropped.
+#[cfg(feature = "tokio")]
+async fn refresh_periodically(api: Weak<Vatsim>, every: Duration) {
+    loop {
+        tokio::time::sleep(every).await;
+        let Some(api) = api.upgrade() else {
+            break;
+        };
+        if let Err(e) = api.refresh_endpoints().await {
+            log::warn!("Could not refresh live data URLs: {e}");
+        }
+    }
 }
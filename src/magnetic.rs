@@ -0,0 +1,293 @@
+//! Converting true bearings to magnetic with the [World Magnetic Model].
+//!
+//! The bearings from [`distance`](crate::distance) are true, but pilots
+//! and controllers work in magnetic headings. [`MagneticModel`] evaluates
+//! the model's spherical harmonic expansion to get the magnetic
+//! variation (declination) at a position and date.
+//!
+//! The crate doesn't bundle the model's coefficients, which NOAA replaces
+//! every five years. Download the `WMM.COF` file for the current model
+//! from NOAA and load it with [`MagneticModel::from_cof`].
+//!
+//! [World Magnetic Model]: https://www.ncei.noaa.gov/products/world-magnetic-model
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::time::SystemTime;
+//! use vatsim_utils::{distance::initial_bearing, magnetic::MagneticModel};
+//!
+//! let model = MagneticModel::from_cof(&std::fs::read_to_string("WMM.COF").unwrap()).unwrap();
+//! let bearing = initial_bearing(32.7338, -117.1933, 33.9416, -118.4085);
+//! let magnetic = model.true_to_magnetic(bearing, 32.7338, -117.1933, SystemTime::now());
+//! println!("KSAN to KLAX: {magnetic:.0}° magnetic");
+//! ```
+
+use crate::errors::VatsimUtilError;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Semi-major axis of the WGS 84 ellipsoid, in kilometers.
+const WGS84_A_KM: f64 = 6378.137;
+
+/// Flattening of the WGS 84 ellipsoid.
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// The model's reference radius, in kilometers.
+const REFERENCE_RADIUS_KM: f64 = 6371.2;
+
+/// Kilometers in a foot.
+const KM_PER_FOOT: f64 = 0.000_304_8;
+
+/// Seconds in an average year.
+const SECONDS_PER_YEAR: f64 = 365.25 * 86_400.0;
+
+/// The World Magnetic Model, or another model in the same format, loaded
+/// from its coefficients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MagneticModel {
+    name: String,
+    epoch: f64,
+    degree: usize,
+    g: Vec<Vec<f64>>,
+    h: Vec<Vec<f64>>,
+    g_dot: Vec<Vec<f64>>,
+    h_dot: Vec<Vec<f64>>,
+}
+
+impl MagneticModel {
+    /// Parse a model from NOAA's `.COF` coefficient file format.
+    ///
+    /// The first line holds the epoch as a decimal year and the model's
+    /// name, and each following line holds a degree, order, the `g` and
+    /// `h` coefficients in nanotesla, and their yearly rates of change.
+    /// Parsing stops at a line of 9s or the end of the data.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use vatsim_utils::magnetic::MagneticModel;
+    ///
+    /// // a made-up model: a dipole tilted to the west
+    /// let model = MagneticModel::from_cof("
+    ///     2025.0            TEST-2025     01/01/2025
+    ///   1  0  -30000.0       0.0        0.0        0.0
+    ///   1  1       0.0    3000.0        0.0        0.0
+    /// 999999999999999999999999999999999999999999999999
+    /// ").unwrap();
+    /// let date = UNIX_EPOCH + Duration::from_secs(1_735_689_600);
+    ///
+    /// assert_eq!(model.name(), "TEST-2025");
+    /// let declination = model.declination(0.0, 0.0, 0.0, date);
+    /// assert!((declination - -5.71).abs() < 0.01);
+    /// assert!((model.true_to_magnetic(90.0, 0.0, 0.0, date) - 95.71).abs() < 0.01);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the header or a coefficient line can't
+    /// be parsed, with the line number.
+    pub fn from_cof(data: &str) -> Result<Self, VatsimUtilError> {
+        let mut lines = data
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+        let Some((header_number, header)) = lines.next() else {
+            return Err(VatsimUtilError::InvalidMagneticModel(1));
+        };
+        let mut header_parts = header.split_whitespace();
+        let (Some(Ok(epoch)), Some(name)) = (
+            header_parts.next().map(str::parse::<f64>),
+            header_parts.next(),
+        ) else {
+            return Err(VatsimUtilError::InvalidMagneticModel(header_number));
+        };
+        let mut coefficients = Vec::new();
+        for (number, line) in lines {
+            if line.starts_with("9999") {
+                break;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let parsed = match parts[..] {
+                [n, m, g, h, g_dot, h_dot] => (|| {
+                    Some((
+                        n.parse::<usize>().ok()?,
+                        m.parse::<usize>().ok()?,
+                        [
+                            g.parse::<f64>().ok()?,
+                            h.parse().ok()?,
+                            g_dot.parse().ok()?,
+                            h_dot.parse().ok()?,
+                        ],
+                    ))
+                })(),
+                _ => None,
+            };
+            match parsed {
+                Some((n, m, values)) if n >= 1 && m <= n => coefficients.push((n, m, values)),
+                _ => return Err(VatsimUtilError::InvalidMagneticModel(number)),
+            }
+        }
+        let degree = coefficients.iter().map(|(n, ..)| *n).max().unwrap_or(0);
+        let empty = vec![vec![0.0; degree + 1]; degree + 1];
+        let mut model = Self {
+            name: name.to_owned(),
+            epoch,
+            degree,
+            g: empty.clone(),
+            h: empty.clone(),
+            g_dot: empty.clone(),
+            h_dot: empty,
+        };
+        for (n, m, [g, h, g_dot, h_dot]) in coefficients {
+            model.g[n][m] = g;
+            model.h[n][m] = h;
+            model.g_dot[n][m] = g_dot;
+            model.h_dot[n][m] = h_dot;
+        }
+        Ok(model)
+    }
+
+    /// The model's name, like "WMM-2025".
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The model's epoch, as a decimal year. The model is valid for five
+    /// years from it.
+    #[must_use]
+    pub fn epoch(&self) -> f64 {
+        self.epoch
+    }
+
+    /// The magnetic variation at a position, altitude in feet, and date,
+    /// in degrees east of true north; west variation is negative.
+    ///
+    /// The variation is undefined at the magnetic poles, where this
+    /// returns `NaN`.
+    #[must_use]
+    pub fn declination(&self, lat: f64, lon: f64, altitude_ft: f64, date: SystemTime) -> f64 {
+        let (north, east) = self.horizontal_field(lat, lon, altitude_ft * KM_PER_FOOT, date);
+        if north == 0.0 && east == 0.0 {
+            return f64::NAN;
+        }
+        f64::atan2(east, north).to_degrees()
+    }
+
+    /// Convert a true bearing to magnetic at a position, at sea level,
+    /// and date.
+    #[must_use]
+    pub fn true_to_magnetic(&self, bearing: f64, lat: f64, lon: f64, date: SystemTime) -> f64 {
+        (bearing - self.declination(lat, lon, 0.0, date)).rem_euclid(360.0)
+    }
+
+    /// Convert a magnetic bearing to true at a position, at sea level,
+    /// and date.
+    #[must_use]
+    pub fn magnetic_to_true(&self, bearing: f64, lat: f64, lon: f64, date: SystemTime) -> f64 {
+        (bearing + self.declination(lat, lon, 0.0, date)).rem_euclid(360.0)
+    }
+
+    /// The north and east components of the field, in nanotesla, at a
+    /// geodetic position and height above the ellipsoid in kilometers.
+    #[allow(clippy::many_single_char_names)]
+    fn horizontal_field(&self, lat: f64, lon: f64, height_km: f64, date: SystemTime) -> (f64, f64) {
+        let years = decimal_year(date) - self.epoch;
+        let geodetic_lat = lat.to_radians();
+        let lon = lon.to_radians();
+
+        // geodetic to geocentric spherical coordinates
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let prime_vertical = WGS84_A_KM / (1.0 - e2 * geodetic_lat.sin().powi(2)).sqrt();
+        let p = (prime_vertical + height_km) * geodetic_lat.cos();
+        let z = (prime_vertical * (1.0 - e2) + height_km) * geodetic_lat.sin();
+        let r = p.hypot(z);
+        let geocentric_lat = (z / r).asin();
+
+        // Schmidt semi-normalized associated Legendre functions of the
+        // colatitude, and their derivatives with respect to it
+        let (cos_theta, sin_theta) = (geocentric_lat.sin(), geocentric_lat.cos());
+        let size = self.degree + 1;
+        let mut legendre = vec![vec![0.0; size]; size];
+        let mut derivative = vec![vec![0.0; size]; size];
+        legendre[0][0] = 1.0;
+        for n in 1..size {
+            for m in 0..=n {
+                if n == m {
+                    legendre[n][m] = sin_theta * legendre[n - 1][m - 1];
+                    derivative[n][m] =
+                        sin_theta * derivative[n - 1][m - 1] + cos_theta * legendre[n - 1][m - 1];
+                } else {
+                    #[allow(clippy::cast_precision_loss)]
+                    let k = if n == 1 {
+                        0.0
+                    } else {
+                        (((n - 1) * (n - 1) - m * m) as f64) / (((2 * n - 1) * (2 * n - 3)) as f64)
+                    };
+                    let two_back =
+                        |table: &Vec<Vec<f64>>| if n >= 2 { table[n - 2][m] } else { 0.0 };
+                    legendre[n][m] = cos_theta * legendre[n - 1][m] - k * two_back(&legendre);
+                    derivative[n][m] = cos_theta * derivative[n - 1][m]
+                        - sin_theta * legendre[n - 1][m]
+                        - k * two_back(&derivative);
+                }
+            }
+        }
+        let mut schmidt = vec![vec![0.0; size]; size];
+        schmidt[0][0] = 1.0;
+        for n in 1..size {
+            #[allow(clippy::cast_precision_loss)]
+            {
+                schmidt[n][0] = schmidt[n - 1][0] * (2 * n - 1) as f64 / n as f64;
+                for m in 1..=n {
+                    let factor = if m == 1 { 2.0 } else { 1.0 };
+                    schmidt[n][m] =
+                        schmidt[n][m - 1] * ((n - m + 1) as f64 * factor / (n + m) as f64).sqrt();
+                }
+            }
+        }
+
+        let (mut b_theta, mut b_phi, mut b_r) = (0.0, 0.0, 0.0);
+        for n in 1..size {
+            #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+            let ratio = (REFERENCE_RADIUS_KM / r).powi(n as i32 + 2);
+            for m in 0..=n {
+                let g = self.g[n][m] + years * self.g_dot[n][m];
+                let h = self.h[n][m] + years * self.h_dot[n][m];
+                #[allow(clippy::cast_precision_loss)]
+                let (sin_m, cos_m) = (m as f64 * lon).sin_cos();
+                let p = schmidt[n][m] * legendre[n][m];
+                let dp = schmidt[n][m] * derivative[n][m];
+                let cos_term = g * cos_m + h * sin_m;
+                b_theta -= ratio * cos_term * dp;
+                #[allow(clippy::cast_precision_loss)]
+                {
+                    b_phi += ratio * m as f64 * (g * sin_m - h * cos_m) * p;
+                    b_r += ratio * (n + 1) as f64 * cos_term * p;
+                }
+            }
+        }
+        if sin_theta.abs() < 1e-10 {
+            return (0.0, 0.0);
+        }
+        b_phi /= sin_theta;
+
+        // rotate from geocentric to geodetic
+        let north_geocentric = -b_theta;
+        let down_geocentric = -b_r;
+        let psi = geocentric_lat - geodetic_lat;
+        let north = north_geocentric * psi.cos() - down_geocentric * psi.sin();
+        (north, b_phi)
+    }
+}
+
+/// A time as a decimal year, like 2025.5 for the middle of 2025.
+fn decimal_year(date: SystemTime) -> f64 {
+    let seconds = match date.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs_f64(),
+        Err(before) => -before.duration().as_secs_f64(),
+    };
+    1970.0 + seconds / SECONDS_PER_YEAR
+}
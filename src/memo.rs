@@ -0,0 +1,184 @@
+//! In-process memoization of REST API data that rarely changes.
+//!
+//! The `_cached` functions in [`rest_api`](crate::rest_api), like
+//! [`get_regions_cached`](crate::rest_api::get_regions_cached), and their
+//! blocking versions keep the last response in memory and return it
+//! until it's older than its TTL, without making any request at all. Use them on hot paths that resolve
+//! the same lists over and over; use the uncached functions to always get
+//! fresh data.
+//!
+//! The TTLs can be changed with [`set_memo_ttls`], and everything
+//! memoized can be thrown away with [`clear_memo`]. A TTL of zero turns
+//! memoization off for that data.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use vatsim_utils::memo::{memo_ttls, reset_memo_ttls, set_memo_ttls, MemoTtls};
+//!
+//! set_memo_ttls(MemoTtls::default().facilities(Duration::from_secs(30)));
+//! assert_eq!(memo_ttls().facilities, Duration::from_secs(30));
+//! reset_memo_ttls();
+//! ```
+
+use crate::models::{Division, Facility, Region};
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::{
+    sync::{LazyLock, Mutex, PoisonError, RwLock},
+    time::{Duration, Instant},
+};
+
+/// Currently-configured TTLs.
+static TTLS: LazyLock<RwLock<MemoTtls>> = LazyLock::new(|| RwLock::new(MemoTtls::default()));
+
+/// Memoized list of regions.
+pub(crate) static REGIONS: Memo<Vec<Region>> = Memo::new();
+
+/// Memoized list of divisions.
+pub(crate) static DIVISIONS: Memo<Vec<Division>> = Memo::new();
+
+/// Memoized list of online facilities.
+pub(crate) static FACILITIES: Memo<Vec<Facility>> = Memo::new();
+
+/// How long memoized data is kept for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoTtls {
+    /// The list of regions. Defaults to a day.
+    pub regions: Duration,
+    /// The list of divisions. Defaults to a day.
+    pub divisions: Duration,
+    /// The list of online facilities. Defaults to five minutes, as
+    /// controllers come and go.
+    pub facilities: Duration,
+}
+
+impl Default for MemoTtls {
+    fn default() -> Self {
+        Self {
+            regions: Duration::from_secs(86_400),
+            divisions: Duration::from_secs(86_400),
+            facilities: Duration::from_secs(300),
+        }
+    }
+}
+
+impl MemoTtls {
+    /// Set how long the list of regions is kept.
+    #[must_use]
+    pub fn regions(mut self, ttl: Duration) -> Self {
+        self.regions = ttl;
+        self
+    }
+
+    /// Set how long the list of divisions is kept.
+    #[must_use]
+    pub fn divisions(mut self, ttl: Duration) -> Self {
+        self.divisions = ttl;
+        self
+    }
+
+    /// Set how long the list of online facilities is kept.
+    #[must_use]
+    pub fn facilities(mut self, ttl: Duration) -> Self {
+        self.facilities = ttl;
+        self
+    }
+}
+
+/// Get the current TTLs.
+#[must_use]
+pub fn memo_ttls() -> MemoTtls {
+    *TTLS.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Replace the TTLs.
+///
+/// Data that's already memoized is checked against the new TTLs the next
+/// time it's used.
+pub fn set_memo_ttls(ttls: MemoTtls) {
+    *TTLS.write().unwrap_or_else(PoisonError::into_inner) = ttls;
+}
+
+/// Go back to the default TTLs.
+pub fn reset_memo_ttls() {
+    set_memo_ttls(MemoTtls::default());
+}
+
+/// Throw away all memoized data, so the next calls fetch it again.
+pub fn clear_memo() {
+    REGIONS.clear();
+    DIVISIONS.clear();
+    FACILITIES.clear();
+}
+
+/// A single memoized value, and when it was fetched.
+pub(crate) struct Memo<T> {
+    value: Mutex<Option<(Instant, T)>>,
+}
+
+impl<T: Clone> Memo<T> {
+    /// Create an empty memo.
+    const fn new() -> Self {
+        Self {
+            value: Mutex::new(None),
+        }
+    }
+
+    /// Get the value if it's younger than `ttl`, otherwise fetch and
+    /// store it.
+    ///
+    /// Concurrent callers that find the value missing each fetch it.
+    #[cfg(feature = "async")]
+    pub(crate) async fn get_or_fetch<E>(
+        &self,
+        ttl: Duration,
+        fetch: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        if let Some(value) = self.get(ttl) {
+            return Ok(value);
+        }
+        let value = fetch.await?;
+        self.store(ttl, &value);
+        Ok(value)
+    }
+
+    /// Blocking version of [`get_or_fetch`](Memo::get_or_fetch).
+    #[cfg(feature = "sync-ureq")]
+    pub(crate) fn get_or_fetch_blocking<E>(
+        &self,
+        ttl: Duration,
+        fetch: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        if let Some(value) = self.get(ttl) {
+            return Ok(value);
+        }
+        let value = fetch()?;
+        self.store(ttl, &value);
+        Ok(value)
+    }
+
+    /// The value, if it's younger than `ttl`.
+    fn get(&self, ttl: Duration) -> Option<T> {
+        self.value
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .filter(|(fetched, _)| fetched.elapsed() < ttl)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Store a freshly-fetched value, unless memoization is off.
+    fn store(&self, ttl: Duration, value: &T) {
+        if !ttl.is_zero() {
+            *self.value.lock().unwrap_or_else(PoisonError::into_inner) =
+                Some((Instant::now(), value.clone()));
+        }
+    }
+
+    /// Throw away the value.
+    pub(crate) fn clear(&self) {
+        *self.value.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
@@ -0,0 +1,148 @@
+//! JSON API models for deserializing data from the various HTTP APIs.
+//!
+//! The model structs and their fields, here and in the versioned modules
+//! below, aren't individually documented: they're returned by this
+//! crate's public functions, and their fields match those that come from
+//! the APIs, except when underlines are included to improve field
+//! readability and adhere to Rust's styling guidelines. Helper methods
+//! on the models are documented.
+//!
+//! With the `strict-models` feature enabled, fields in a response that
+//! the models don't know about are logged as warnings with their path,
+//! like `pilots.231.new_field`, rather than silently ignored. Parsing
+//! still succeeds, so this shows exactly what VATSIM added to the feeds
+//! before data is dropped. Fields that are missing or the wrong type are
+//! reported by the path in [`JsonSchemaMismatch`] errors either way.
+//!
+//! # Versions
+//!
+//! The models for each version of an API live in their own module:
+//! [`v3`] for the V3 live data feed and [`rest_v1`] for the REST API on
+//! api.vatsim.net. The current versions are also exported from this
+//! module, which is where most code should import them from. When VATSIM
+//! rolls out a new version of a feed, it gets a new module next to the
+//! old one, with `From` conversions between them, so that both can be
+//! used while consumers move over.
+//!
+//! [`JsonSchemaMismatch`]: crate::errors::VatsimUtilError::JsonSchemaMismatch
+//!
+//! # Serialization
+//!
+//! Serializing a model gives back the API's own JSON: the original field
+//! names (like `lastratingchange` and `scratchpadmods`, whatever the Rust
+//! field is called), with timestamps and other values left as the strings
+//! and numbers they were. Proxies and caches can parse the data, use it,
+//! and pass it on without changing its schema. Fields come out in the
+//! models' order, which can differ from the API's, so compare parsed
+//! values rather than bytes; fields the models don't know about are
+//! dropped.
+//!
+//! ```rust
+//! # #[cfg(feature = "fixtures")] {
+//! use serde_json::Value;
+//! use vatsim_utils::{fixtures, models::{PaginatedResponse, AtcSessionEntry, V3ResponseData}};
+//!
+//! let data: V3ResponseData = serde_json::from_str(fixtures::V3_DATA).unwrap();
+//! let original: Value = serde_json::from_str(fixtures::V3_DATA).unwrap();
+//! assert_eq!(serde_json::to_value(&data).unwrap(), original);
+//!
+//! let sessions: PaginatedResponse<AtcSessionEntry> =
+//!     serde_json::from_str(fixtures::ATC_SESSIONS).unwrap();
+//! let json = serde_json::to_string(&sessions).unwrap();
+//! assert!(json.contains(r#""total_scratchpadmods":5"#));
+//! # }
+//! ```
+
+#![allow(missing_docs)]
+
+pub mod rest_v1;
+pub mod v3;
+
+pub use rest_v1::*;
+pub use v3::*;
+
+use crate::bookings::BookingType;
+use serde::{Deserialize, Serialize};
+
+/// Whether the server with the ident is one of the servers and is a
+/// sweatbox.
+fn is_sweatbox_server(ident: &str, servers: &[Server]) -> bool {
+    servers
+        .iter()
+        .any(|server| server.is_sweatbox && server.ident.eq_ignore_ascii_case(ident))
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Booking {
+    pub id: u64,
+    pub cid: u64,
+    #[serde(rename = "type")]
+    pub booking_type: BookingType,
+    pub callsign: String,
+    pub start: String,
+    pub end: String,
+    pub division: Option<String>,
+    pub subdivision: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EventOrganiser {
+    pub region: Option<String>,
+    pub division: Option<String>,
+    pub subdivision: Option<String>,
+    pub organised_by_vatsim: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EventAirport {
+    pub icao: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EventRoute {
+    pub departure: String,
+    pub arrival: String,
+    pub route: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Event {
+    pub id: u64,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub name: String,
+    pub link: String,
+    pub organisers: Vec<EventOrganiser>,
+    pub airports: Vec<EventAirport>,
+    pub routes: Vec<EventRoute>,
+    pub start_time: String,
+    pub end_time: String,
+    pub short_description: String,
+    pub description: String,
+    pub banner: Option<String>,
+}
+
+impl Event {
+    /// ICAO codes of the airports the event involves: those it lists,
+    /// then the ends of its routes, without duplicates.
+    #[must_use]
+    pub fn airport_icaos(&self) -> Vec<&str> {
+        let mut icaos: Vec<&str> = Vec::new();
+        let listed = self.airports.iter().map(|a| a.icao.as_str());
+        let routes = self
+            .routes
+            .iter()
+            .flat_map(|r| [r.departure.as_str(), r.arrival.as_str()]);
+        for icao in listed.chain(routes) {
+            if !icao.is_empty() && !icaos.iter().any(|i| i.eq_ignore_ascii_case(icao)) {
+                icaos.push(icao);
+            }
+        }
+        icaos
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EventsResponse {
+    pub data: Vec<Event>,
+}
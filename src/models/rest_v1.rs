@@ -0,0 +1,363 @@
+//! Models for version 1 of the REST API on [api.vatsim.net].
+//!
+//! [api.vatsim.net]: https://api.vatsim.net/
+
+use super::{
+    is_sweatbox_server,
+    v3::{FlightPlan, Server},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UserRatingsSimple {
+    id: String,
+    rating: i8,
+    pilot_rating: i8,
+    susp_date: Option<String>,
+    reg_date: String,
+    region: String,
+    division: String,
+    subdivision: String,
+    #[serde(rename = "lastratingchange")]
+    last_rating_change: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RatingsTimeData {
+    pub id: f64,
+    pub atc: f64,
+    pub pilot: f64,
+    pub s1: f64,
+    pub s2: f64,
+    pub s3: f64,
+    pub c1: f64,
+    pub c2: f64,
+    pub c3: f64,
+    pub i1: f64,
+    pub i2: f64,
+    pub i3: f64,
+    pub sup: f64,
+    pub adm: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConnectionEntry {
+    pub id: u64,
+    pub vatsim_id: String,
+    #[serde(rename = "type")]
+    pub connection_type: u16,
+    pub rating: i8,
+    pub callsign: String,
+    pub start: String,
+    pub end: Option<String>,
+    pub server: String,
+}
+
+impl ConnectionEntry {
+    /// Whether the connection was to a sweatbox server rather than the
+    /// live network.
+    ///
+    /// The connection's server is looked up by ident in `servers`, which
+    /// can be the servers from the [V3 feed](super::V3ResponseData::servers) or
+    /// from [`get_sweatbox_servers`]. Connections to servers that aren't
+    /// in the list, like ones that have since been retired, count as live.
+    ///
+    /// [`get_sweatbox_servers`]: crate::live_api::Vatsim::get_sweatbox_servers
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use vatsim_utils::models::{ConnectionEntry, Server};
+    ///
+    /// let sweatbox = Server {
+    ///     ident: String::from("SWEATBOX"),
+    ///     hostname_or_ip: String::from("sweatbox.vatsim.net"),
+    ///     location: String::new(),
+    ///     name: String::from("SWEATBOX"),
+    ///     clients_connection_allowed: 1,
+    ///     client_connections_allowed: false,
+    ///     is_sweatbox: true,
+    /// };
+    /// let mut connection = ConnectionEntry {
+    ///     id: 1,
+    ///     vatsim_id: String::from("1234567"),
+    ///     connection_type: 1,
+    ///     rating: 3,
+    ///     callsign: String::from("SAN_TWR"),
+    ///     start: String::new(),
+    ///     end: None,
+    ///     server: String::from("SWEATBOX"),
+    /// };
+    /// assert!(connection.on_sweatbox(&[sweatbox.clone()]));
+    ///
+    /// connection.server = String::from("USA-WEST");
+    /// assert!(!connection.on_sweatbox(&[sweatbox]));
+    /// ```
+    #[must_use]
+    pub fn on_sweatbox(&self, servers: &[Server]) -> bool {
+        is_sweatbox_server(&self.server, servers)
+    }
+}
+
+/// A paginated response wrapper. Includes a count of items,
+/// potential links to next/previous pages, and a list of results.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PaginatedResponse<T> {
+    pub count: u64,
+    pub next: Option<String>,
+    pub previous: Option<String>,
+    pub results: Vec<T>,
+}
+
+impl<T> PaginatedResponse<T> {
+    /// Whether this is the first page.
+    #[must_use]
+    pub fn is_first(&self) -> bool {
+        self.previous.is_none()
+    }
+
+    /// Whether this is the last page.
+    #[must_use]
+    pub fn is_last(&self) -> bool {
+        self.next.is_none()
+    }
+
+    /// This page's number, starting from 1.
+    ///
+    /// The number is worked out from the `page` parameter in the links to
+    /// the next and previous pages. The API leaves the parameter off the
+    /// link to the first page, so that's page 1.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use vatsim_utils::models::PaginatedResponse;
+    ///
+    /// let page: PaginatedResponse<u64> = PaginatedResponse {
+    ///     count: 45,
+    ///     next: Some(String::from("https://api.vatsim.net/api/ratings/1/connections/?page=3")),
+    ///     previous: Some(String::from("https://api.vatsim.net/api/ratings/1/connections/")),
+    ///     results: vec![0; 20],
+    /// };
+    ///
+    /// assert_eq!(page.page(), 2);
+    /// assert_eq!(page.total_pages(), 3);
+    /// assert!(!page.is_last());
+    /// ```
+    #[must_use]
+    pub fn page(&self) -> u64 {
+        if let Some(next) = self.next.as_deref().and_then(page_parameter) {
+            return next.saturating_sub(1).max(1);
+        }
+        match &self.previous {
+            Some(previous) => page_parameter(previous).unwrap_or(1) + 1,
+            None => 1,
+        }
+    }
+
+    /// The number of pages of results.
+    ///
+    /// Every page but the last is full, so the page size is taken from
+    /// this page's results if there are more pages after it. On the last
+    /// page, the total is this page's number.
+    #[must_use]
+    pub fn total_pages(&self) -> u64 {
+        if self.is_last() || self.results.is_empty() {
+            return self.page();
+        }
+        let size = self.results.len() as u64;
+        self.count.div_ceil(size).max(self.page())
+    }
+}
+
+/// The value of the `page` parameter in a URL's query string.
+fn page_parameter(url: &str) -> Option<u64> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("page="))
+        .and_then(|page| page.parse().ok())
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AtcSessionEntry {
+    pub connection_id: u64,
+    pub start: String,
+    pub end: String,
+    pub server: String,
+    pub vatsim_id: String,
+    #[serde(rename = "type")]
+    pub session_type: u16,
+    pub rating: i8,
+    pub callsign: String,
+    pub minutes_on_callsign: String,
+    pub total_minutes_on_callsign: f64,
+    pub total_aircraft_tracked: u64,
+    pub total_aircraft_seen: u64,
+    pub total_flights_amended: u64,
+    pub total_handoffs_initiated: u64,
+    pub total_handoffs_received: u64,
+    pub total_handoffs_refused: u64,
+    pub total_squawks_assigned: u64,
+    #[serde(rename = "total_cruisealts_modified")]
+    pub total_cruise_alts_modified: u64,
+    #[serde(rename = "total_tempalts_modified")]
+    pub total_temp_alts_modified: u64,
+    #[serde(rename = "total_scratchpadmods")]
+    pub total_scratchpad_mods: u64,
+    #[serde(rename = "aircrafttracked")]
+    pub aircraft_tracked: u64,
+    #[serde(rename = "aircraftseen")]
+    pub aircraft_seen: u64,
+    #[serde(rename = "flightsamended")]
+    pub flights_amended: u64,
+    #[serde(rename = "handoffsinitiated")]
+    pub handoffs_initiated: u64,
+    #[serde(rename = "handoffsreceived")]
+    pub handoffs_received: u64,
+    #[serde(rename = "handoffsrefused")]
+    pub handoffs_refused: u64,
+    #[serde(rename = "squawksassigned")]
+    pub squawks_assigned: u64,
+    #[serde(rename = "cruisealtsmodified")]
+    pub cruise_alts_modified: u64,
+    #[serde(rename = "tempaltsmodified")]
+    pub temp_alts_modified: u64,
+    #[serde(rename = "scratchpadmods")]
+    pub scratchpad_mods: u64,
+}
+
+impl AtcSessionEntry {
+    /// Whether the session was on a sweatbox server rather than the live
+    /// network.
+    ///
+    /// See [`ConnectionEntry::on_sweatbox`] for how servers are matched.
+    #[must_use]
+    pub fn on_sweatbox(&self, servers: &[Server]) -> bool {
+        is_sweatbox_server(&self.server, servers)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RestFlightPlans {
+    pub id: u64,
+    pub connection_id: u64,
+    pub vatsim_id: String,
+    pub flight_type: String,
+    pub callsign: String,
+    pub aircraft: String,
+    #[serde(rename = "cruisespeed")]
+    pub cruise_speed: String,
+    pub dep: String,
+    pub arr: String,
+    pub alt: String,
+    pub altitude: String,
+    #[serde(rename = "rmks")]
+    pub remarks: String,
+    pub route: String,
+    #[serde(rename = "deptime")]
+    pub departure_time: String,
+    #[serde(rename = "hrsenroute")]
+    pub hrs_enroute: u64,
+    #[serde(rename = "minenroute")]
+    pub min_enroute: u64,
+    #[serde(rename = "hrsfuel")]
+    pub hrs_fuel: u64,
+    #[serde(rename = "minsfuel")]
+    pub mins_fuel: u8,
+    pub filed: String,
+    #[serde(rename = "assignedsquawk")]
+    pub assigned_squawk: String,
+    #[serde(rename = "modifiedbycid")]
+    pub modified_by_cid: String,
+    #[serde(rename = "modifiedbycallsign")]
+    pub modified_by_callsign: String,
+}
+
+impl From<&RestFlightPlans> for FlightPlan {
+    /// Convert a flight plan from the REST API into the shape the V3 feed
+    /// uses, so history and live data can go through the same code.
+    ///
+    /// The REST API only has the aircraft in its short FAA format, like
+    /// "H/B744/L", which is used for all three aircraft fields, with the
+    /// type designator picked out for `aircraft_short`. It has no revision
+    /// number, so that's 0.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "fixtures")] {
+    /// use vatsim_utils::{fixtures, models::FlightPlan};
+    ///
+    /// let history = fixtures::flight_plans();
+    /// let plan = FlightPlan::from(&history.results[0]);
+    /// assert_eq!(plan.departure, "KJFK");
+    /// assert_eq!(plan.aircraft_short, "A321");
+    /// assert_eq!(plan.enroute_time, "0105");
+    /// # }
+    /// ```
+    fn from(plan: &RestFlightPlans) -> Self {
+        let aircraft_short = plan
+            .aircraft
+            .split('/')
+            .find(|part| part.len() > 1)
+            .unwrap_or_default();
+        Self {
+            flight_rules: plan.flight_type.clone(),
+            aircraft: plan.aircraft.clone(),
+            aircraft_faa: plan.aircraft.clone(),
+            aircraft_short: aircraft_short.to_owned(),
+            departure: plan.dep.clone(),
+            arrival: plan.arr.clone(),
+            alternate: plan.alt.clone(),
+            cruise_tas: plan.cruise_speed.clone(),
+            altitude: plan.altitude.clone(),
+            deptime: plan.departure_time.clone(),
+            enroute_time: format!("{:02}{:02}", plan.hrs_enroute, plan.min_enroute),
+            fuel_time: format!("{:02}{:02}", plan.hrs_fuel, plan.mins_fuel),
+            remarks: plan.remarks.clone(),
+            route: plan.route.clone(),
+            revision_id: 0,
+            assigned_transponder: plan.assigned_squawk.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Region {
+    pub id: String,
+    pub name: String,
+    pub director: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Division {
+    pub id: String,
+    pub name: String,
+    pub parentregion: String,
+    pub subdivisionallowed: u8,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Facility {
+    pub id: String,
+    pub start: String,
+    pub callsign: String,
+    pub rating: i8,
+}
+
+impl Facility {
+    /// The part of the callsign before the first underscore, generally
+    /// the airport or FIR/ARTCC, like "SAN" for "`SAN_TWR`".
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        self.callsign.split('_').next().unwrap_or(&self.callsign)
+    }
+
+    /// The facility type, which is the callsign's suffix, like "TWR"
+    /// or "CTR".
+    #[must_use]
+    pub fn facility_type(&self) -> &str {
+        self.callsign.rsplit('_').next().unwrap_or(&self.callsign)
+    }
+}
@@ -0,0 +1,560 @@
+//! Models for version 3 of the live data feed, along with the status
+//! file that lists its URLs and the transceivers feed.
+
+use super::is_sweatbox_server;
+use crate::routes::{route_path, NavdataProvider, RoutePath};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StatusData {
+    pub v3: Vec<String>,
+    pub transceivers: Vec<String>,
+    pub servers: Vec<String>,
+    pub servers_sweatbox: Vec<String>,
+    pub servers_all: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Status {
+    pub data: StatusData,
+    pub user: Vec<String>,
+    pub metar: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FlightPlan {
+    pub flight_rules: String,
+    pub aircraft: String,
+    pub aircraft_faa: String,
+    pub aircraft_short: String,
+    pub departure: String,
+    pub arrival: String,
+    pub alternate: String,
+    pub cruise_tas: String,
+    pub altitude: String,
+    pub deptime: String,
+    pub enroute_time: String,
+    pub fuel_time: String,
+    pub remarks: String,
+    pub route: String,
+    pub revision_id: i64,
+    pub assigned_transponder: String,
+}
+
+impl FlightPlan {
+    /// The filed route resolved to a path from the departure airport to
+    /// the arrival airport. See [`route_path`] for how it's resolved.
+    #[must_use]
+    pub fn path(&self, navdata: &impl NavdataProvider) -> RoutePath {
+        route_path(
+            Some(self.departure.as_str()).filter(|icao| !icao.is_empty()),
+            &self.route,
+            Some(self.arrival.as_str()).filter(|icao| !icao.is_empty()),
+            navdata,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Pilot {
+    pub cid: u64,
+    pub name: String,
+    pub callsign: String,
+    pub server: String,
+    pub pilot_rating: i8,
+    pub military_rating: i8,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: i64,
+    pub groundspeed: i64,
+    pub transponder: String,
+    pub heading: i64,
+    pub qnh_i_hg: f64,
+    pub qnh_mb: i64,
+    pub flight_plan: Option<FlightPlan>,
+    pub logon_time: String,
+    pub last_updated: String,
+}
+
+impl Pilot {
+    /// Whether the pilot is connected to a sweatbox server rather than
+    /// the live network.
+    ///
+    /// See [`ConnectionEntry::on_sweatbox`] for how servers are matched.
+    #[must_use]
+    pub fn on_sweatbox(&self, servers: &[Server]) -> bool {
+        is_sweatbox_server(&self.server, servers)
+    }
+
+    /// Whether the pilot has filed a flight plan.
+    #[must_use]
+    pub fn has_flight_plan(&self) -> bool {
+        self.flight_plan.is_some()
+    }
+
+    /// The departure airport from the flight plan.
+    ///
+    /// This and the other flight plan accessors return `None` both when
+    /// there's no flight plan and when the field was left empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "fixtures")] {
+    /// use vatsim_utils::fixtures;
+    ///
+    /// let data = fixtures::v3_data();
+    /// let pilot = data.pilots.iter().find(|p| p.callsign == "AAL1234").unwrap();
+    /// assert_eq!(pilot.departure(), Some("KLAX"));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn departure(&self) -> Option<&str> {
+        self.flight_plan_field(|plan| &plan.departure)
+    }
+
+    /// The arrival airport from the flight plan.
+    #[must_use]
+    pub fn arrival(&self) -> Option<&str> {
+        self.flight_plan_field(|plan| &plan.arrival)
+    }
+
+    /// The route from the flight plan.
+    #[must_use]
+    pub fn route(&self) -> Option<&str> {
+        self.flight_plan_field(|plan| &plan.route)
+    }
+
+    /// The cruise altitude from the flight plan, as filed, like "35000"
+    /// or "FL350".
+    #[must_use]
+    pub fn filed_altitude(&self) -> Option<&str> {
+        self.flight_plan_field(|plan| &plan.altitude)
+    }
+
+    /// The short aircraft type from the flight plan, like "B738".
+    #[must_use]
+    pub fn aircraft_type(&self) -> Option<&str> {
+        self.flight_plan_field(|plan| &plan.aircraft_short)
+    }
+
+    /// A non-empty field of the flight plan.
+    fn flight_plan_field(&self, field: impl FnOnce(&FlightPlan) -> &String) -> Option<&str> {
+        self.flight_plan
+            .as_ref()
+            .map(|plan| field(plan).as_str())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Controller {
+    pub cid: u64,
+    pub name: String,
+    pub callsign: String,
+    pub frequency: String,
+    pub facility: i64,
+    pub rating: i8,
+    pub server: String,
+    pub visual_range: i64,
+    pub text_atis: Option<Vec<String>>,
+    pub last_updated: String,
+    pub logon_time: String,
+}
+
+impl Controller {
+    /// Whether this connection is an observer rather than an
+    /// active controller.
+    ///
+    /// Observers connect with facility type 0 and/or a callsign
+    /// ending in "`_OBS`".
+    #[must_use]
+    pub fn is_observer(&self) -> bool {
+        self.facility == 0 || self.callsign.ends_with("_OBS")
+    }
+
+    /// The controller's info text as a single paragraph.
+    ///
+    /// See [`Atis::text`] for how the lines are joined.
+    #[must_use]
+    pub fn text(&self) -> Option<String> {
+        self.text_atis.as_deref().and_then(join_text)
+    }
+
+    /// Whether the controller is connected to a sweatbox server rather
+    /// than the live network.
+    ///
+    /// See [`ConnectionEntry::on_sweatbox`] for how servers are matched.
+    #[must_use]
+    pub fn on_sweatbox(&self, servers: &[Server]) -> bool {
+        is_sweatbox_server(&self.server, servers)
+    }
+}
+
+/// Whether to include observers when working with controllers.
+///
+/// See [`Controller::is_observer`] for how observers are identified.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Observers {
+    /// Include observers alongside other controllers.
+    #[default]
+    Include,
+    /// Exclude observers, leaving only actively-controlling positions.
+    Exclude,
+}
+
+impl Observers {
+    /// Whether the controller should be included.
+    #[must_use]
+    pub fn allows(self, controller: &Controller) -> bool {
+        self == Self::Include || !controller.is_observer()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GeneralData {
+    pub version: i64,
+    pub reload: i64,
+    pub update: String,
+    pub update_timestamp: String,
+    pub connected_clients: i64,
+    pub unique_users: i64,
+}
+
+impl GeneralData {
+    /// How long ago the data was updated, from the `update` field.
+    ///
+    /// Returns `None` if the field can't be parsed. Update times in the
+    /// future, from clock differences, give an age of zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use vatsim_utils::models::GeneralData;
+    /// # let mut general: GeneralData = serde_json::from_str(r#"{"version":3,"reload":1,"update":"","update_timestamp":"","connected_clients":0,"unique_users":0}"#).unwrap();
+    /// general.update = String::from("20220805001710");
+    ///
+    /// assert!(general.age().unwrap().as_secs() > 60 * 60 * 24 * 365);
+    /// ```
+    #[must_use]
+    pub fn age(&self) -> Option<Duration> {
+        let updated = self.updated()?;
+        Some(
+            SystemTime::now()
+                .duration_since(updated)
+                .unwrap_or(Duration::ZERO),
+        )
+    }
+
+    /// When the data was updated, from the `update` field.
+    ///
+    /// Returns `None` if the field can't be parsed, including when it's
+    /// not a real date and time, like February 30th or 24:00.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::time::{Duration, UNIX_EPOCH};
+    /// # use vatsim_utils::models::GeneralData;
+    /// # let mut general: GeneralData = serde_json::from_str(r#"{"version":3,"reload":1,"update":"","update_timestamp":"","connected_clients":0,"unique_users":0}"#).unwrap();
+    /// general.update = String::from("20240229235959");
+    /// assert_eq!(
+    ///     general.updated(),
+    ///     Some(UNIX_EPOCH + Duration::from_secs(1_709_251_199))
+    /// );
+    ///
+    /// for invalid in ["20230229000000", "20220431000000", "20220805240000", "20220805006000"] {
+    ///     general.update = String::from(invalid);
+    ///     assert_eq!(general.updated(), None);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn updated(&self) -> Option<SystemTime> {
+        Some(UNIX_EPOCH + Duration::from_secs(unix_seconds(&self.update)?))
+    }
+}
+
+/// Seconds since the Unix epoch of a `YYYYMMDDhhmmss` UTC timestamp.
+fn unix_seconds(timestamp: &str) -> Option<u64> {
+    if timestamp.len() != 14 || !timestamp.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let field = |range: std::ops::Range<usize>| timestamp[range].parse::<u64>().ok();
+    let (year, month, day) = (field(0..4)?, field(4..6)?, field(6..8)?);
+    let (hour, minute, second) = (field(8..10)?, field(10..12)?, field(12..14)?);
+    if year < 1970 || !(1..=12).contains(&month) || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if !(1..=days_in_month).contains(&day) {
+        return None;
+    }
+    // Days from the epoch to the date, from Howard Hinnant's `days_from_civil`.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Atis {
+    pub cid: u64,
+    pub name: String,
+    pub callsign: String,
+    pub frequency: String,
+    pub facility: u8,
+    pub rating: u8,
+    pub server: String,
+    pub visual_range: u16,
+    pub atis_code: Option<String>,
+    pub text_atis: Option<Vec<String>>,
+    pub last_updated: String,
+    pub logon_time: String,
+}
+
+impl Atis {
+    /// The airport the ATIS is for, taken from the callsign.
+    ///
+    /// This is the part of the callsign before the first underscore,
+    /// so both "`KSAN_ATIS`" and "`KSAN_D_ATIS`" give "KSAN".
+    #[must_use]
+    pub fn airport(&self) -> &str {
+        self.callsign.split('_').next().unwrap_or(&self.callsign)
+    }
+
+    /// The ATIS text as a single paragraph.
+    ///
+    /// The feed splits the text into lines wherever the controller's client
+    /// wrapped it; this joins them back together with single spaces, with
+    /// blank lines and repeated whitespace removed. Returns `None` if
+    /// there's no text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use vatsim_utils::models::Atis;
+    /// # let mut atis: Atis = serde_json::from_str(r#"{"cid":1,"name":"","callsign":"KSAN_ATIS","frequency":"134.800","facility":4,"rating":3,"server":"USA-W","visual_range":0,"atis_code":"A","text_atis":null,"last_updated":"","logon_time":""}"#).unwrap();
+    /// atis.text_atis = Some(vec![
+    ///     "SAN DIEGO INFO A  1651Z.".to_owned(),
+    ///     "".to_owned(),
+    ///     " WIND 280 AT 8.".to_owned(),
+    /// ]);
+    ///
+    /// assert_eq!(atis.text().unwrap(), "SAN DIEGO INFO A 1651Z. WIND 280 AT 8.");
+    /// ```
+    #[must_use]
+    pub fn text(&self) -> Option<String> {
+        self.text_atis.as_deref().and_then(join_text)
+    }
+}
+
+/// Join lines of text into a paragraph, or `None` if there's no text.
+fn join_text(lines: &[String]) -> Option<String> {
+    let text = lines
+        .iter()
+        .flat_map(|line| line.split_whitespace())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Server {
+    pub ident: String,
+    pub hostname_or_ip: String,
+    pub location: String,
+    pub name: String,
+    pub clients_connection_allowed: u16,
+    pub client_connections_allowed: bool,
+    pub is_sweatbox: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReferenceItem {
+    pub id: i8,
+    pub short: String,
+    pub long: String,
+}
+
+impl fmt::Display for ReferenceItem {
+    /// Formats as "short – long", like "C1 – Enroute Controller".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} – {}", self.short, self.long)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReferenceNameItem {
+    pub id: i8,
+    pub short_name: String,
+    pub long_name: String,
+}
+
+impl fmt::Display for ReferenceNameItem {
+    /// Formats as "short – long", like "PPL – Private Pilot License".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} – {}", self.short_name, self.long_name)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Prefile {
+    pub cid: u64,
+    pub name: String,
+    pub callsign: String,
+    pub flight_plan: Option<FlightPlan>,
+    pub last_updated: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct V3ResponseData {
+    pub general: GeneralData,
+    pub pilots: Vec<Pilot>,
+    pub controllers: Vec<Controller>,
+    pub atis: Vec<Atis>,
+    pub servers: Vec<Server>,
+    #[serde(default)]
+    pub prefiles: Vec<Prefile>,
+    pub facilities: Vec<ReferenceItem>,
+    pub ratings: Vec<ReferenceItem>,
+    pub pilot_ratings: Vec<ReferenceNameItem>,
+    pub military_ratings: Vec<ReferenceNameItem>,
+}
+
+impl V3ResponseData {
+    /// Iterate over the controllers, including or excluding observers.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::{live_api::Vatsim, models::Observers};
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap();
+    /// let data = api.get_v3_data().await.unwrap();
+    /// let active = data.controllers_filtered(Observers::Exclude).count();
+    /// # }
+    /// ```
+    pub fn controllers_filtered(&self, observers: Observers) -> impl Iterator<Item = &Controller> {
+        self.controllers
+            .iter()
+            .filter(move |controller| observers.allows(controller))
+    }
+
+    /// Iterate over the ATIS stations for the airport.
+    ///
+    /// See [`Atis::airport`] for how the airport is determined; airports
+    /// with separate arrival and departure ATIS have more than one.
+    pub fn atis_for_airport<'a>(&'a self, airport: &'a str) -> impl Iterator<Item = &'a Atis> {
+        self.atis
+            .iter()
+            .filter(move |atis| atis.airport().eq_ignore_ascii_case(airport))
+    }
+
+    /// Get the controller that's running the ATIS, if they're also
+    /// connected on a controller position.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::live_api::Vatsim;
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap();
+    /// let data = api.get_v3_data().await.unwrap();
+    /// for atis in data.atis_for_airport("KSAN") {
+    ///     if let Some(controller) = data.atis_controller(atis) {
+    ///         println!("{} is run by {}", atis.callsign, controller.callsign);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn atis_controller(&self, atis: &Atis) -> Option<&Controller> {
+        self.controllers
+            .iter()
+            .find(|controller| controller.cid == atis.cid && !controller.is_observer())
+    }
+
+    /// Look up the controller's rating in the snapshot's `ratings` list.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use vatsim_utils::live_api::Vatsim;
+    ///
+    /// # async fn _do() {
+    /// let api = Vatsim::new().await.unwrap();
+    /// let data = api.get_v3_data().await.unwrap();
+    /// for controller in &data.controllers {
+    ///     if let Some(rating) = data.controller_rating(controller) {
+    ///         // like "SAN_TWR: S2 – Tower Controller"
+    ///         println!("{}: {rating}", controller.callsign);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn controller_rating(&self, controller: &Controller) -> Option<&ReferenceItem> {
+        self.ratings.iter().find(|r| r.id == controller.rating)
+    }
+
+    /// Look up the controller's facility type in the snapshot's
+    /// `facilities` list.
+    #[must_use]
+    pub fn controller_facility(&self, controller: &Controller) -> Option<&ReferenceItem> {
+        self.facilities
+            .iter()
+            .find(|f| i64::from(f.id) == controller.facility)
+    }
+
+    /// Look up the pilot's rating in the snapshot's `pilot_ratings` list.
+    #[must_use]
+    pub fn pilot_rating(&self, pilot: &Pilot) -> Option<&ReferenceNameItem> {
+        self.pilot_ratings
+            .iter()
+            .find(|r| r.id == pilot.pilot_rating)
+    }
+
+    /// Look up the pilot's military rating in the snapshot's
+    /// `military_ratings` list.
+    #[must_use]
+    pub fn pilot_military_rating(&self, pilot: &Pilot) -> Option<&ReferenceNameItem> {
+        self.military_ratings
+            .iter()
+            .find(|r| r.id == pilot.military_rating)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TransceiverEntry {
+    pub id: u16,
+    pub frequency: u64,
+    #[serde(rename = "latDeg")]
+    pub lat_deg: f64,
+    #[serde(rename = "lonDeg")]
+    pub lon_deg: f64,
+    #[serde(rename = "heightMslM")]
+    pub height_msl_m: f64,
+    #[serde(rename = "heightAglM")]
+    pub height_agl_m: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TransceiverResponseEntry {
+    pub callsign: String,
+    pub transceivers: Vec<TransceiverEntry>,
+}
@@ -0,0 +1,161 @@
+//! When the network is busiest, from recorded snapshots of live data.
+//!
+//! Feed snapshots - as they're fetched, or read back from a recording
+//! like the [JSON Lines export](crate::export) - to [`Occupancy::add`],
+//! and it keeps the average and peak numbers of pilots and controllers
+//! for each hour of the day, each day of the week, and each hour of
+//! each day of the week, ready to chart. All times are UTC.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::{export::JsonLinesRecord, models::{Observers, V3ResponseData}, occupancy::Occupancy};
+//! use std::{fs::File, io::{BufRead, BufReader}};
+//!
+//! let mut occupancy = Occupancy::new(Observers::Exclude);
+//! for line in BufReader::new(File::open("snapshots.jsonl").unwrap()).lines() {
+//!     let record: JsonLinesRecord<V3ResponseData> = serde_json::from_str(&line.unwrap()).unwrap();
+//!     occupancy.add(&record.data);
+//! }
+//! for (hour, bucket) in occupancy.by_hour.iter().enumerate() {
+//!     println!("{hour:02}:00 - {:.0} pilots on average", bucket.average_pilots());
+//! }
+//! ```
+
+use crate::models::{Observers, V3ResponseData};
+use serde::{Deserialize, Serialize};
+use std::time::UNIX_EPOCH;
+
+/// Numbers of connections across the snapshots in one time bucket.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OccupancyBucket {
+    /// Number of snapshots in the bucket.
+    pub samples: usize,
+    /// Pilots, added up across the snapshots.
+    pub total_pilots: usize,
+    /// Most pilots in any one snapshot.
+    pub peak_pilots: usize,
+    /// Controllers, added up across the snapshots.
+    pub total_controllers: usize,
+    /// Most controllers in any one snapshot.
+    pub peak_controllers: usize,
+}
+
+impl OccupancyBucket {
+    /// Average number of pilots per snapshot; 0 with no snapshots.
+    #[must_use]
+    pub fn average_pilots(&self) -> f64 {
+        average(self.total_pilots, self.samples)
+    }
+
+    /// Average number of controllers per snapshot; 0 with no snapshots.
+    #[must_use]
+    pub fn average_controllers(&self) -> f64 {
+        average(self.total_controllers, self.samples)
+    }
+
+    /// Count a snapshot's connections.
+    fn add(&mut self, pilots: usize, controllers: usize) {
+        self.samples += 1;
+        self.total_pilots += pilots;
+        self.peak_pilots = self.peak_pilots.max(pilots);
+        self.total_controllers += controllers;
+        self.peak_controllers = self.peak_controllers.max(controllers);
+    }
+}
+
+/// Pilots and controllers per hour of the day and day of the week, in UTC.
+///
+/// Days of the week are indexed from Monday, as 0, to Sunday, as 6.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Occupancy {
+    /// Whether observers count as controllers.
+    #[serde(skip)]
+    observers: Observers,
+    /// Per hour of the day; index 0 is 00:00-00:59.
+    pub by_hour: [OccupancyBucket; 24],
+    /// Per day of the week.
+    pub by_weekday: [OccupancyBucket; 7],
+    /// Per hour of each day of the week, indexed by day and then hour.
+    pub by_weekday_hour: [[OccupancyBucket; 24]; 7],
+}
+
+impl Occupancy {
+    /// Create empty statistics, including or excluding observers from
+    /// the controllers.
+    #[must_use]
+    pub fn new(observers: Observers) -> Self {
+        Self {
+            observers,
+            ..Self::default()
+        }
+    }
+
+    /// Count a snapshot, at the time it was updated.
+    ///
+    /// Snapshots whose time can't be parsed are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "fixtures")] {
+    /// use vatsim_utils::{fixtures, models::Observers, occupancy::Occupancy};
+    ///
+    /// // updated on a Saturday at 18:30
+    /// let data = fixtures::v3_data();
+    /// let mut occupancy = Occupancy::new(Observers::Exclude);
+    /// occupancy.add(&data);
+    ///
+    /// assert_eq!(occupancy.by_hour[18].peak_pilots, 5);
+    /// assert_eq!(occupancy.by_weekday[5].peak_controllers, 4);
+    /// assert_eq!(occupancy.by_weekday_hour[5][18].samples, 1);
+    /// # }
+    /// ```
+    pub fn add(&mut self, data: &V3ResponseData) {
+        let Some(seconds) = data
+            .general
+            .updated()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+        else {
+            return;
+        };
+        let days = seconds / 86_400;
+        #[allow(clippy::cast_possible_truncation)]
+        let hour = (seconds % 86_400 / 3600) as usize;
+        // 1970-01-01 was a Thursday
+        #[allow(clippy::cast_possible_truncation)]
+        let weekday = ((days + 3) % 7) as usize;
+
+        let pilots = data.pilots.len();
+        let controllers = data.controllers_filtered(self.observers).count();
+        self.by_hour[hour].add(pilots, controllers);
+        self.by_weekday[weekday].add(pilots, controllers);
+        self.by_weekday_hour[weekday][hour].add(pilots, controllers);
+    }
+}
+
+/// Build occupancy statistics from the snapshots.
+///
+/// See [`Occupancy::add`] for details.
+#[must_use]
+pub fn occupancy<'a>(
+    snapshots: impl IntoIterator<Item = &'a V3ResponseData>,
+    observers: Observers,
+) -> Occupancy {
+    let mut occupancy = Occupancy::new(observers);
+    for data in snapshots {
+        occupancy.add(data);
+    }
+    occupancy
+}
+
+/// Total divided by the count, or 0 for no count.
+#[allow(clippy::cast_precision_loss)]
+fn average(total: usize, count: usize) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total as f64 / count as f64
+    }
+}
@@ -0,0 +1,159 @@
+//! Rendering network metrics in the [Prometheus text exposition format].
+//!
+//! Metrics are derived from a single V3 snapshot, so the report should be
+//! rebuilt each time new data is fetched. Request latencies aren't part of
+//! the snapshot; time your own calls and add them to the report with
+//! [`PrometheusReport::request_latency`].
+//!
+//! [Prometheus text exposition format]: https://prometheus.io/docs/instrumenting/exposition_formats/
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::{live_api::Vatsim, prometheus::PrometheusReport};
+//! use std::time::Instant;
+//!
+//! # async fn _do() {
+//! let api = Vatsim::new().await.unwrap();
+//! let start = Instant::now();
+//! let data = api.get_v3_data().await.unwrap();
+//! let body = PrometheusReport::new(&data)
+//!     .request_latency("v3", start.elapsed())
+//!     .render();
+//! // serve `body` from your metrics endpoint ...
+//! # }
+//! ```
+
+use crate::models::{Observers, V3ResponseData};
+use std::{collections::HashSet, fmt::Write, time::Duration};
+
+/// Builder for a metrics page from a V3 snapshot.
+#[derive(Debug)]
+pub struct PrometheusReport<'a> {
+    data: &'a V3ResponseData,
+    observers: Observers,
+    latencies: Vec<(String, Duration)>,
+}
+
+impl<'a> PrometheusReport<'a> {
+    /// Create a new report from the snapshot.
+    #[must_use]
+    pub fn new(data: &'a V3ResponseData) -> Self {
+        Self {
+            data,
+            observers: Observers::Include,
+            latencies: Vec::new(),
+        }
+    }
+
+    /// Set whether observers are counted as controllers.
+    ///
+    /// Observers are included by default.
+    #[must_use]
+    pub fn observers(mut self, observers: Observers) -> Self {
+        self.observers = observers;
+        self
+    }
+
+    /// Include how long a request to an endpoint took.
+    ///
+    /// The endpoint name is used as the `endpoint` label's value.
+    #[must_use]
+    pub fn request_latency(mut self, endpoint: &str, duration: Duration) -> Self {
+        self.latencies.push((endpoint.to_owned(), duration));
+        self
+    }
+
+    /// Render the metrics to a string.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let general = &self.data.general;
+        write_gauge(
+            &mut out,
+            "vatsim_connected_clients",
+            "Number of clients connected to the network.",
+            &[(String::new(), general.connected_clients.to_string())],
+        );
+        write_gauge(
+            &mut out,
+            "vatsim_unique_users",
+            "Number of unique users connected to the network.",
+            &[(String::new(), general.unique_users.to_string())],
+        );
+        write_gauge(
+            &mut out,
+            "vatsim_pilots_online",
+            "Number of pilots connected to the network.",
+            &[(String::new(), self.data.pilots.len().to_string())],
+        );
+        write_gauge(
+            &mut out,
+            "vatsim_atis_online",
+            "Number of ATIS stations connected to the network.",
+            &[(String::new(), self.data.atis.len().to_string())],
+        );
+
+        let mut seen = HashSet::new();
+        let controllers: Vec<_> = self
+            .data
+            .facilities
+            .iter()
+            .filter(|facility| seen.insert(facility.id))
+            .map(|facility| {
+                let count = self
+                    .data
+                    .controllers_filtered(self.observers)
+                    .filter(|controller| controller.facility == i64::from(facility.id))
+                    .count();
+                (
+                    format!("{{facility=\"{}\"}}", escape(&facility.short)),
+                    count.to_string(),
+                )
+            })
+            .collect();
+        write_gauge(
+            &mut out,
+            "vatsim_controllers_online",
+            "Number of controllers connected to the network, by facility type.",
+            &controllers,
+        );
+
+        if !self.latencies.is_empty() {
+            let latencies: Vec<_> = self
+                .latencies
+                .iter()
+                .map(|(endpoint, duration)| {
+                    (
+                        format!("{{endpoint=\"{}\"}}", escape(endpoint)),
+                        duration.as_secs_f64().to_string(),
+                    )
+                })
+                .collect();
+            write_gauge(
+                &mut out,
+                "vatsim_request_duration_seconds",
+                "Duration of the most recent request to a VATSIM endpoint.",
+                &latencies,
+            );
+        }
+        out
+    }
+}
+
+/// Write a gauge's help & type lines, followed by a line per sample.
+fn write_gauge(out: &mut String, name: &str, help: &str, samples: &[(String, String)]) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{name}{labels} {value}");
+    }
+}
+
+/// Escape a label value per the exposition format.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,166 @@
+//! Python bindings for the live data client and REST API functions.
+//!
+//! The bindings wrap the [`blocking`] functions, releasing
+//! the GIL while requests are made, and return the models as Python `dict`s
+//! and `list`s with the same fields as the Rust structs. Errors are raised
+//! as `vatsim_utils.VatsimError`.
+//!
+//! Requires the `python` feature. To build an importable module, compile a
+//! `cdylib` with [maturin] and enable [pyo3]'s `extension-module` feature;
+//! the module's name is `vatsim_utils`.
+//!
+//! ```python
+//! import vatsim_utils
+//!
+//! api = vatsim_utils.Vatsim()
+//! data = api.get_v3_data()
+//! print(len(data["pilots"]))
+//! print(vatsim_utils.user_ratings(1234567890)["rating"])
+//! ```
+//!
+//! [maturin]: https://www.maturin.rs/
+//! [pyo3]: https://pyo3.rs/
+
+use crate::{blocking, errors::VatsimUtilError};
+use pyo3::{create_exception, exceptions::PyException, prelude::*};
+use serde::Serialize;
+
+create_exception!(
+    vatsim_utils,
+    VatsimError,
+    PyException,
+    "Error from making a request to or parsing data from VATSIM."
+);
+
+/// Convert a model to Python objects.
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    Ok(pythonize::pythonize(py, value)?.unbind())
+}
+
+/// Make the request without holding the GIL, converting the result
+/// to Python objects.
+fn call<T, F>(py: Python<'_>, f: F) -> PyResult<PyObject>
+where
+    T: Serialize + Send,
+    F: Send + FnOnce() -> Result<T, VatsimUtilError>,
+{
+    let value = py
+        .allow_threads(f)
+        .map_err(|e| VatsimError::new_err(e.to_string()))?;
+    to_py(py, &value)
+}
+
+/// Client for the live data feeds.
+#[pyclass(name = "Vatsim", module = "vatsim_utils", frozen)]
+struct PyVatsim {
+    inner: blocking::Vatsim,
+}
+
+#[pymethods]
+impl PyVatsim {
+    #[new]
+    fn new(py: Python<'_>) -> PyResult<Self> {
+        py.allow_threads(blocking::Vatsim::new)
+            .map(|inner| Self { inner })
+            .map_err(|e| VatsimError::new_err(e.to_string()))
+    }
+
+    fn get_v3_data(&self, py: Python<'_>) -> PyResult<PyObject> {
+        call(py, || self.inner.get_v3_data())
+    }
+
+    fn get_transceivers_data(&self, py: Python<'_>) -> PyResult<PyObject> {
+        call(py, || self.inner.get_transceivers_data())
+    }
+}
+
+#[pyfunction]
+fn user_ratings(py: Python<'_>, cid: u64) -> PyResult<PyObject> {
+    call(py, || blocking::user_ratings(cid))
+}
+
+#[pyfunction]
+fn get_ratings_times(py: Python<'_>, cid: u64) -> PyResult<PyObject> {
+    call(py, || blocking::get_ratings_times(cid))
+}
+
+#[pyfunction]
+#[pyo3(signature = (cid, page = None))]
+fn get_connections(py: Python<'_>, cid: u64, page: Option<u64>) -> PyResult<PyObject> {
+    call(py, || blocking::get_connections(cid, page))
+}
+
+#[pyfunction]
+fn get_connections_all(py: Python<'_>, cid: u64) -> PyResult<PyObject> {
+    call(py, || {
+        blocking::get_connections_all(cid)
+            .into_values()
+            .map(|pages| pages.into_iter().flatten().collect::<Vec<_>>())
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (cid, page = None, specifier = None, start = None, date = None))]
+fn get_atc_sessions(
+    py: Python<'_>,
+    cid: u64,
+    page: Option<u64>,
+    specifier: Option<&str>,
+    start: Option<&str>,
+    date: Option<&str>,
+) -> PyResult<PyObject> {
+    call(py, || {
+        blocking::get_atc_sessions(cid, page, specifier, start, date)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (cid, page = None))]
+fn get_flight_plans(py: Python<'_>, cid: u64, page: Option<u64>) -> PyResult<PyObject> {
+    call(py, || blocking::get_flight_plans(cid, page))
+}
+
+#[pyfunction]
+fn get_regions(py: Python<'_>) -> PyResult<PyObject> {
+    call(py, blocking::get_regions)
+}
+
+#[pyfunction]
+fn get_online_facilities(py: Python<'_>) -> PyResult<PyObject> {
+    call(py, blocking::get_online_facilities)
+}
+
+#[pyfunction]
+#[pyo3(signature = (specifier, page = None, start = None, date = None))]
+fn get_facility_history(
+    py: Python<'_>,
+    specifier: &str,
+    page: Option<u64>,
+    start: Option<&str>,
+    date: Option<&str>,
+) -> PyResult<PyObject> {
+    call(py, || {
+        blocking::get_facility_history(specifier, page, start, date)
+    })
+}
+
+/// The `vatsim_utils` Python module.
+///
+/// # Errors
+///
+/// This function can fail if the module's contents can't be added.
+#[pymodule]
+pub fn vatsim_utils(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("VatsimError", m.py().get_type::<VatsimError>())?;
+    m.add_class::<PyVatsim>()?;
+    m.add_function(wrap_pyfunction!(user_ratings, m)?)?;
+    m.add_function(wrap_pyfunction!(get_ratings_times, m)?)?;
+    m.add_function(wrap_pyfunction!(get_connections, m)?)?;
+    m.add_function(wrap_pyfunction!(get_connections_all, m)?)?;
+    m.add_function(wrap_pyfunction!(get_atc_sessions, m)?)?;
+    m.add_function(wrap_pyfunction!(get_flight_plans, m)?)?;
+    m.add_function(wrap_pyfunction!(get_regions, m)?)?;
+    m.add_function(wrap_pyfunction!(get_online_facilities, m)?)?;
+    m.add_function(wrap_pyfunction!(get_facility_history, m)?)?;
+    Ok(())
+}
@@ -3,28 +3,30 @@
 //!
 //! These functions are not grouped into a struct, as the URLs that
 //! they call are static - not dependent on a preceding call - unlike
-//! those used to get live data from the network.
+//! those used to get live data from the network. The base URL can be
+//! changed with [`urls::set_endpoints`].
 //!
 //! [api.vatsim.net]: https://api.vatsim.net/
 
 use crate::{
-    errors::VatsimUtilError,
+    config,
+    errors::{BatchResults, Endpoint, VatsimUtilError},
+    http, memo,
     models::{
-        AtcSessionEntry, ConnectionEntry, Facility, PaginatedResponse, RatingsTimeData, Region,
-        RestFlightPlans, UserRatingsSimple,
+        AtcSessionEntry, ConnectionEntry, Division, Facility, PaginatedResponse, RatingsTimeData,
+        Region, RestFlightPlans, UserRatingsSimple,
     },
+    urls,
 };
-use once_cell::sync::Lazy;
-use reqwest::{Client, ClientBuilder, Method};
-use std::fmt::Write;
+use futures_util::{stream, StreamExt};
+use reqwest::{Client, Method, Url};
+use std::{fmt::Write, future::Future, sync::LazyLock};
 
 /// HTTP client.
-static CLIENT: Lazy<Client> = Lazy::new(|| {
-    ClientBuilder::new()
-        .user_agent("github.com/celeo/vatsim_utils")
-        .build()
-        .expect("Invalid HTTP Agent")
-});
+static CLIENT: LazyLock<Client> = LazyLock::new(config::build_client);
+
+/// Base URL of stats.vatsim.net.
+const STATS_SITE: &str = "https://stats.vatsim.net/";
 
 /// Get the URL for viewing a user's stats on stats.vatsim.net.
 ///
@@ -45,6 +47,118 @@ pub fn stats_url(cid: u64) -> String {
     format!("https://stats.vatsim.net/stats/{cid}")
 }
 
+/// Get the URL for viewing a user's controlling stats on stats.vatsim.net.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::rest_api::stats_atc_url;
+///
+/// let url = stats_atc_url(1234567890).unwrap();
+///
+/// assert_eq!(url.as_str(), "https://stats.vatsim.net/stats/1234567890/atc");
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the URL can't be built.
+pub fn stats_atc_url(cid: u64) -> Result<Url, VatsimUtilError> {
+    build_url(STATS_SITE, &["stats", &cid.to_string(), "atc"], &[])
+}
+
+/// Get the URL for viewing a user's piloting stats on stats.vatsim.net.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::rest_api::stats_pilot_url;
+///
+/// let url = stats_pilot_url(1234567890).unwrap();
+///
+/// assert_eq!(url.as_str(), "https://stats.vatsim.net/stats/1234567890/pilot");
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the URL can't be built.
+pub fn stats_pilot_url(cid: u64) -> Result<Url, VatsimUtilError> {
+    build_url(STATS_SITE, &["stats", &cid.to_string(), "pilot"], &[])
+}
+
+/// Get the URL for viewing a user's profile on my.vatsim.net.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::rest_api::profile_url;
+///
+/// let url = profile_url(1234567890).unwrap();
+///
+/// assert_eq!(url.as_str(), "https://my.vatsim.net/members/view/1234567890");
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the URL can't be built.
+pub fn profile_url(cid: u64) -> Result<Url, VatsimUtilError> {
+    build_url(
+        "https://my.vatsim.net/",
+        &["members", "view", &cid.to_string()],
+        &[],
+    )
+}
+
+/// Third-party sites that show the network on a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapSite {
+    /// [VATSIM Radar](https://vatsim-radar.com/)
+    VatsimRadar,
+    /// [SimAware](https://simaware.ca/)
+    SimAware,
+}
+
+/// Get the URL for viewing a connected user on a map site.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::rest_api::{map_member_url, MapSite};
+///
+/// let url = map_member_url(MapSite::VatsimRadar, 1234567890).unwrap();
+///
+/// assert_eq!(url.as_str(), "https://vatsim-radar.com/?pilot=1234567890");
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the URL can't be built.
+pub fn map_member_url(site: MapSite, cid: u64) -> Result<Url, VatsimUtilError> {
+    let cid = cid.to_string();
+    match site {
+        MapSite::VatsimRadar => build_url("https://vatsim-radar.com/", &[], &[("pilot", &cid)]),
+        MapSite::SimAware => build_url("https://simaware.ca/", &[], &[("cid", &cid)]),
+    }
+}
+
+/// Build a URL from a base, percent-encoding each path segment and query
+/// pair.
+fn build_url(
+    base: &str,
+    segments: &[&str],
+    query: &[(&str, &str)],
+) -> Result<Url, VatsimUtilError> {
+    let mut url = Url::parse(base).map_err(|e| VatsimUtilError::InvalidUrl(e.to_string()))?;
+    let _ = url
+        .path_segments_mut()
+        .map_err(|()| VatsimUtilError::InvalidUrl(format!("{base} can't have a path")))?
+        .pop_if_empty()
+        .extend(segments);
+    if !query.is_empty() {
+        let _ = url.query_pairs_mut().extend_pairs(query);
+    }
+    Ok(url)
+}
+
 /// Get a simple view of a user's ratings on the network.
 ///
 /// # Example
@@ -63,19 +177,39 @@ pub fn stats_url(cid: u64) -> String {
 /// data does not match the schemas of the models passed to the
 /// deserializer.
 pub async fn user_ratings(cid: u64) -> Result<UserRatingsSimple, VatsimUtilError> {
-    let response = CLIENT
-        .get(format!("https://api.vatsim.net/api/ratings/{cid}/"))
-        .send()
-        .await?;
-    if !response.status().is_success() {
-        return Err(VatsimUtilError::InvalidStatusCode(
-            response.status().as_u16(),
-        ));
-    }
-    let data = response.json().await?;
+    let url = format!("{}/api/ratings/{cid}/", urls::endpoints().api);
+    let data = http::fetch_json(Endpoint::Ratings, CLIENT.get(url)).await?;
     Ok(data)
 }
 
+/// Get a simple view of several users' ratings on the network.
+///
+/// Users are looked up one after another; a failed lookup doesn't stop
+/// the rest, and its error is returned alongside the ratings that were
+/// found.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::rest_api::user_ratings_batch;
+///
+/// # async fn _do() {
+/// let results = user_ratings_batch(&[1234567890, 1234567891]).await;
+/// for (cid, ratings) in &results.successes {
+///     println!("{cid}: {ratings:?}");
+/// }
+/// for (cid, error) in &results.errors.failures {
+///     eprintln!("{cid}: {error}");
+/// }
+/// # }
+/// ```
+pub async fn user_ratings_batch(cids: &[u64]) -> BatchResults<u64, UserRatingsSimple> {
+    stream::iter(cids.iter().copied())
+        .then(|cid| async move { (cid, user_ratings(cid).await) })
+        .collect()
+        .await
+}
+
 /// Get the amount of time the user has spent as various positions on the network.
 ///
 /// # Example
@@ -94,18 +228,8 @@ pub async fn user_ratings(cid: u64) -> Result<UserRatingsSimple, VatsimUtilError
 /// data does not match the schemas of the models passed to the
 /// deserializer.
 pub async fn get_ratings_times(cid: u64) -> Result<RatingsTimeData, VatsimUtilError> {
-    let response = CLIENT
-        .get(format!(
-            "https://api.vatsim.net/api/ratings/{cid}/rating_times"
-        ))
-        .send()
-        .await?;
-    if !response.status().is_success() {
-        return Err(VatsimUtilError::InvalidStatusCode(
-            response.status().as_u16(),
-        ));
-    }
-    let data = response.json().await?;
+    let url = format!("{}/api/ratings/{cid}/rating_times", urls::endpoints().api);
+    let data = http::fetch_json(Endpoint::RatingTimes, CLIENT.get(url)).await?;
     Ok(data)
 }
 
@@ -134,20 +258,40 @@ pub async fn get_connections(
     cid: u64,
     page: Option<u64>,
 ) -> Result<PaginatedResponse<ConnectionEntry>, VatsimUtilError> {
-    let mut url = format!("https://api.vatsim.net/api/ratings/{cid}/connections");
+    let mut url = format!("{}/api/ratings/{cid}/connections", urls::endpoints().api);
     if let Some(p) = page {
         let _ = write!(url, "?page={p}");
     }
-    let response = CLIENT.get(url).send().await?;
-    if !response.status().is_success() {
-        return Err(VatsimUtilError::InvalidStatusCode(
-            response.status().as_u16(),
-        ));
-    }
-    let data = response.json().await?;
+    let data = http::fetch_json(Endpoint::Connections, CLIENT.get(url)).await?;
     Ok(data)
 }
 
+/// Get several pages of a user's previous connections.
+///
+/// Pages are fetched one after another; a failed page doesn't stop the
+/// rest, and its error is returned alongside the pages that were found,
+/// so a caller can try just the failed pages again.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::rest_api::get_connections_pages;
+///
+/// # async fn _do() {
+/// let results = get_connections_pages(1234567890, &[1, 2, 3]).await;
+/// let retry: Vec<u64> = results.errors.failures.iter().map(|(page, _)| *page).collect();
+/// # }
+/// ```
+pub async fn get_connections_pages(
+    cid: u64,
+    pages: &[u64],
+) -> BatchResults<u64, PaginatedResponse<ConnectionEntry>> {
+    stream::iter(pages.iter().copied())
+        .then(|page| async move { (page, get_connections(cid, Some(page)).await) })
+        .collect()
+        .await
+}
+
 /// Get a user's ATC sessions.
 ///
 /// A page number can optionally be specified.
@@ -189,7 +333,7 @@ pub async fn get_atc_sessions(
     start: Option<&str>,
     date: Option<&str>,
 ) -> Result<PaginatedResponse<AtcSessionEntry>, VatsimUtilError> {
-    let mut url = format!("https://api.vatsim.net/api/ratings/{cid}/atcsessions/");
+    let mut url = format!("{}/api/ratings/{cid}/atcsessions/", urls::endpoints().api);
     if let Some(spec) = specifier {
         url += spec;
     }
@@ -203,13 +347,7 @@ pub async fn get_atc_sessions(
     if let Some(d) = date {
         req = req.query(&[("date", d)]);
     }
-    let response = req.send().await?;
-    if !response.status().is_success() {
-        return Err(VatsimUtilError::InvalidStatusCode(
-            response.status().as_u16(),
-        ));
-    }
-    let response_data = response.json().await?;
+    let response_data = http::fetch_json(Endpoint::AtcSessions, req).await?;
     Ok(response_data)
 }
 
@@ -241,17 +379,11 @@ pub async fn get_flight_plans(
     cid: u64,
     page: Option<u64>,
 ) -> Result<PaginatedResponse<RestFlightPlans>, VatsimUtilError> {
-    let mut url = format!("https://api.vatsim.net/api/ratings/{cid}/flight_plans");
+    let mut url = format!("{}/api/ratings/{cid}/flight_plans", urls::endpoints().api);
     if let Some(p) = page {
         let _ = write!(url, "?page={p}");
     }
-    let response = CLIENT.get(url).send().await?;
-    if !response.status().is_success() {
-        return Err(VatsimUtilError::InvalidStatusCode(
-            response.status().as_u16(),
-        ));
-    }
-    let data = response.json().await?;
+    let data = http::fetch_json(Endpoint::FlightPlans, CLIENT.get(url)).await?;
     Ok(data)
 }
 
@@ -273,19 +405,147 @@ pub async fn get_flight_plans(
 /// data does not match the schemas of the models passed to the
 /// deserializer.
 pub async fn get_regions() -> Result<Vec<Region>, VatsimUtilError> {
-    let response = CLIENT
-        .get("https://api.vatsim.net/api/regions/")
-        .send()
-        .await?;
-    if !response.status().is_success() {
-        return Err(VatsimUtilError::InvalidStatusCode(
-            response.status().as_u16(),
-        ));
-    }
-    let data = response.json().await?;
+    let url = format!("{}/api/regions/", urls::endpoints().api);
+    let data = http::fetch_json(Endpoint::Regions, CLIENT.get(url)).await?;
     Ok(data)
 }
 
+/// Get the list of VATSIM regions, [memoized](crate::memo) for a day
+/// by default.
+///
+/// The list almost never changes, so this avoids fetching it for every
+/// lookup; use [`get_regions`] to always fetch it.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::rest_api::get_regions_cached;
+///
+/// # async fn _do() {
+/// let regions = get_regions_cached().await.unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the list isn't memoized and the HTTP request
+/// fails or if the returned data does not match the schemas of the models
+/// passed to the deserializer.
+pub async fn get_regions_cached() -> Result<Vec<Region>, VatsimUtilError> {
+    memo::REGIONS
+        .get_or_fetch(memo::memo_ttls().regions, get_regions())
+        .await
+}
+
+/// Empty the cache used by [`get_regions_cached`], so the next call
+/// fetches the list again.
+pub fn clear_regions_cache() {
+    memo::REGIONS.clear();
+}
+
+/// Get the list of VATSIM divisions.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::rest_api::get_divisions;
+///
+/// # async fn _do() {
+/// let divisions = get_divisions().await.unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the HTTP request fails or if the returned
+/// data does not match the schemas of the models passed to the
+/// deserializer.
+pub async fn get_divisions() -> Result<Vec<Division>, VatsimUtilError> {
+    let url = format!("{}/api/divisions/", urls::endpoints().api);
+    http::fetch_json(Endpoint::Divisions, CLIENT.get(url)).await
+}
+
+/// Get the list of VATSIM divisions, [memoized](crate::memo) for a day
+/// by default.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::rest_api::get_divisions_cached;
+///
+/// # async fn _do() {
+/// let divisions = get_divisions_cached().await.unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the list isn't memoized and the HTTP request
+/// fails or if the returned data does not match the schemas of the models
+/// passed to the deserializer.
+pub async fn get_divisions_cached() -> Result<Vec<Division>, VatsimUtilError> {
+    memo::DIVISIONS
+        .get_or_fetch(memo::memo_ttls().divisions, get_divisions())
+        .await
+}
+
+/// Get a region by its ID, like `AMAS`, ignoring case.
+///
+/// Uses the [cached](get_regions_cached) list of regions.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::rest_api::get_region;
+///
+/// # async fn _do() {
+/// let region = get_region("AMAS").await.unwrap();
+/// if let Some(region) = region {
+///     println!("{}", region.name);
+/// }
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the list isn't memoized and the HTTP request
+/// fails or if the returned data does not match the schemas of the models
+/// passed to the deserializer.
+pub async fn get_region(id: &str) -> Result<Option<Region>, VatsimUtilError> {
+    let regions = get_regions_cached().await?;
+    Ok(regions
+        .into_iter()
+        .find(|region| region.id.eq_ignore_ascii_case(id)))
+}
+
+/// Get the regions whose names contain `query`, ignoring case.
+///
+/// Uses the [cached](get_regions_cached) list of regions.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::rest_api::search_regions;
+///
+/// # async fn _do() {
+/// let regions = search_regions("america").await.unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the list isn't memoized and the HTTP request
+/// fails or if the returned data does not match the schemas of the models
+/// passed to the deserializer.
+pub async fn search_regions(query: &str) -> Result<Vec<Region>, VatsimUtilError> {
+    let query = query.to_lowercase();
+    let regions = get_regions_cached().await?;
+    Ok(regions
+        .into_iter()
+        .filter(|region| region.name.to_lowercase().contains(&query))
+        .collect())
+}
+
 /// Get facilities currently staffed by ATC.
 ///
 /// # Example
@@ -304,19 +564,134 @@ pub async fn get_regions() -> Result<Vec<Region>, VatsimUtilError> {
 /// data does not match the schemas of the models passed to the
 /// deserializer.
 pub async fn get_online_facilities() -> Result<Vec<Facility>, VatsimUtilError> {
-    let response = CLIENT
-        .get("https://api.vatsim.net/api/facilities/")
-        .send()
-        .await?;
-    if !response.status().is_success() {
-        return Err(VatsimUtilError::InvalidStatusCode(
-            response.status().as_u16(),
-        ));
-    }
-    let data = response.json().await?;
+    let url = format!("{}/api/facilities/", urls::endpoints().api);
+    let data = http::fetch_json(Endpoint::Facilities, CLIENT.get(url)).await?;
     Ok(data)
 }
 
+/// Get facilities currently staffed by ATC, [memoized](crate::memo) for
+/// five minutes by default.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::rest_api::get_online_facilities_cached;
+///
+/// # async fn _do() {
+/// let facilities = get_online_facilities_cached().await.unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the list isn't memoized and the HTTP request
+/// fails or if the returned data does not match the schemas of the models
+/// passed to the deserializer.
+pub async fn get_online_facilities_cached() -> Result<Vec<Facility>, VatsimUtilError> {
+    memo::FACILITIES
+        .get_or_fetch(memo::memo_ttls().facilities, get_online_facilities())
+        .await
+}
+
+/// Rules for which online facilities to include.
+///
+/// Each rule that has any values set must match for a facility to be
+/// included; a default filter matches every facility.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::{models::Facility, rest_api::FacilityFilter};
+///
+/// let filter = FacilityFilter::default()
+///     .callsign_prefix("SAN_")
+///     .facility_type("TWR");
+/// let facility = Facility {
+///     id: String::from("1"),
+///     start: String::new(),
+///     callsign: String::from("SAN_TWR"),
+///     rating: 3,
+/// };
+/// assert!(filter.matches(&facility));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FacilityFilter {
+    /// Callsign prefixes to match, like "SAN_".
+    pub callsign_prefixes: Vec<String>,
+    /// Facility types to match, like "TWR", ignoring case.
+    pub facility_types: Vec<String>,
+}
+
+impl FacilityFilter {
+    /// Add a callsign prefix to match.
+    #[must_use]
+    pub fn callsign_prefix(mut self, prefix: &str) -> Self {
+        self.callsign_prefixes.push(prefix.to_owned());
+        self
+    }
+
+    /// Add a facility type to match.
+    #[must_use]
+    pub fn facility_type(mut self, facility_type: &str) -> Self {
+        self.facility_types.push(facility_type.to_owned());
+        self
+    }
+
+    /// Whether the facility passes this filter.
+    #[must_use]
+    pub fn matches(&self, facility: &Facility) -> bool {
+        (self.callsign_prefixes.is_empty()
+            || self
+                .callsign_prefixes
+                .iter()
+                .any(|prefix| facility.callsign.starts_with(prefix.as_str())))
+            && (self.facility_types.is_empty()
+                || self
+                    .facility_types
+                    .iter()
+                    .any(|t| t.eq_ignore_ascii_case(facility.facility_type())))
+    }
+
+    /// The facilities that pass this filter.
+    #[must_use]
+    pub fn apply<'a>(&self, facilities: &'a [Facility]) -> Vec<&'a Facility> {
+        facilities.iter().filter(|f| self.matches(f)).collect()
+    }
+}
+
+/// Get facilities currently staffed by ATC that pass the filter.
+///
+/// Uses the [cached](get_online_facilities_cached) list of facilities.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::rest_api::{get_online_facilities_filtered, FacilityFilter};
+///
+/// # async fn _do() {
+/// let filter = FacilityFilter::default()
+///     .callsign_prefix("LAX_")
+///     .callsign_prefix("SAN_")
+///     .facility_type("TWR");
+/// let towers = get_online_facilities_filtered(&filter).await.unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if the list isn't memoized and the HTTP request
+/// fails or if the returned data does not match the schemas of the models
+/// passed to the deserializer.
+pub async fn get_online_facilities_filtered(
+    filter: &FacilityFilter,
+) -> Result<Vec<Facility>, VatsimUtilError> {
+    let facilities = get_online_facilities_cached().await?;
+    Ok(facilities
+        .into_iter()
+        .filter(|f| filter.matches(f))
+        .collect())
+}
+
 /// Get a facility's historical staffing data.
 ///
 /// A page number and start and end dates are optional.
@@ -353,7 +728,7 @@ pub async fn get_facility_history(
 ) -> Result<PaginatedResponse<AtcSessionEntry>, VatsimUtilError> {
     let mut req = CLIENT.request(
         Method::GET,
-        format!("https://api.vatsim.net/api/facilities/{specifier}"),
+        format!("{}/api/facilities/{specifier}", urls::endpoints().api),
     );
     if let Some(p) = page {
         req = req.query(&[("page", p.to_string().as_str())]);
@@ -364,12 +739,83 @@ pub async fn get_facility_history(
     if let Some(d) = date {
         req = req.query(&[("date", d)]);
     }
-    let response = req.send().await?;
-    if !response.status().is_success() {
-        return Err(VatsimUtilError::InvalidStatusCode(
-            response.status().as_u16(),
-        ));
-    }
-    let response_data = response.json().await?;
+    let response_data = http::fetch_json(Endpoint::FacilityHistory, req).await?;
     Ok(response_data)
 }
+
+/// Get a facility's historical staffing data, from all pages.
+///
+/// This gets the first page with [`get_facility_history`], then every
+/// other page that it says there are, so can make many requests for busy
+/// facilities; using a start date is recommended. A failed page doesn't
+/// stop the rest, and its error is returned alongside the pages that
+/// were found, keyed by page number, so a caller can try just the failed
+/// pages again. If the first page fails, that's the only result.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::rest_api::get_facility_history_all;
+///
+/// # async fn _do() {
+/// let results = get_facility_history_all("SAN_TWR", Some("2022-02-01"), None).await;
+/// let sessions: Vec<_> = results.successes.into_iter().flat_map(|(_, page)| page).collect();
+/// for (page, error) in &results.errors.failures {
+///     eprintln!("page {page}: {error}");
+/// }
+/// # }
+/// ```
+pub async fn get_facility_history_all(
+    specifier: &str,
+    start: Option<&str>,
+    date: Option<&str>,
+) -> BatchResults<u64, Vec<AtcSessionEntry>> {
+    all_pages(|page| get_facility_history(specifier, Some(page), start, date)).await
+}
+
+/// Get a list of all the user's previous connections, from all pages.
+///
+/// This gets the first page with [`get_connections`], then every other
+/// page that it says there are, so can make many requests for long-time
+/// users. Failed pages are returned alongside the rest, as with
+/// [`get_facility_history_all`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::rest_api::get_connections_all;
+///
+/// # async fn _do() {
+/// let connections: Vec<_> = get_connections_all(1234567890)
+///     .await
+///     .into_values()
+///     .unwrap()
+///     .into_iter()
+///     .flatten()
+///     .collect();
+/// # }
+/// ```
+pub async fn get_connections_all(cid: u64) -> BatchResults<u64, Vec<ConnectionEntry>> {
+    all_pages(|page| get_connections(cid, Some(page))).await
+}
+
+/// Get the first page, then every other page that it says there are,
+/// keeping going past pages that fail.
+async fn all_pages<T, F, Fut>(fetch: F) -> BatchResults<u64, Vec<T>>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = Result<PaginatedResponse<T>, VatsimUtilError>>,
+{
+    let first = match fetch(1).await {
+        Ok(first) => first,
+        Err(error) => return [(1, Err(error))].into_iter().collect(),
+    };
+    let total_pages = first.total_pages();
+    stream::iter([(1, Ok(first.results))])
+        .chain(stream::iter(2..=total_pages).then(|page| {
+            let response = fetch(page);
+            async move { (page, response.await.map(|response| response.results)) }
+        }))
+        .collect()
+        .await
+}
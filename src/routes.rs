@@ -0,0 +1,331 @@
+//! Turning filed routes into geographic paths.
+//!
+//! VATSIM flight plans carry routes as free text, like
+//! `PADRZ6 PADRZ J80 BLH DCT HOGGZ`. [`route_path`] walks a route and
+//! resolves each fix and airway to coordinates with a [`NavdataProvider`].
+//! The crate doesn't ship navigation data, which changes every AIRAC
+//! cycle; implement the trait over your own source to plug it in.
+//! [`NoNavdata`] resolves nothing, and an [`AirportDatabase`] resolves
+//! only airports.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use vatsim_utils::routes::{route_path, NavdataProvider};
+//!
+//! struct Fixes(HashMap<&'static str, (f64, f64)>);
+//!
+//! impl NavdataProvider for Fixes {
+//!     fn resolve_fix(&self, ident: &str, _near: Option<(f64, f64)>) -> Option<(f64, f64)> {
+//!         self.0.get(ident).copied()
+//!     }
+//! }
+//!
+//! let navdata = Fixes(HashMap::from([
+//!     ("KSAN", (32.7338, -117.1933)),
+//!     ("MZB", (32.7822, -117.2253)),
+//!     ("KLAX", (33.9416, -118.4085)),
+//! ]));
+//! let path = route_path(Some("KSAN"), "MZB DCT KLAX", Some("KLAX"), &navdata);
+//!
+//! let idents: Vec<_> = path.waypoints.iter().map(|w| w.ident.as_str()).collect();
+//! assert_eq!(idents, ["KSAN", "MZB", "KLAX"]);
+//! assert!(path.unresolved.is_empty());
+//! ```
+
+use crate::distance::{
+    along_track_distance, destination_point, distance_nm, initial_bearing, AirportDatabase,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A point along a route.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Waypoint {
+    /// The fix, navaid, or airport identifier.
+    pub ident: String,
+    /// Latitude, in degrees.
+    pub latitude: f64,
+    /// Longitude, in degrees.
+    pub longitude: f64,
+}
+
+/// A source of navigation data for resolving routes.
+///
+/// Only [`resolve_fix`](Self::resolve_fix) is required. Without the
+/// other methods, airways are never expanded and airports are only found
+/// if they're also fixes.
+pub trait NavdataProvider {
+    /// The `(latitude, longitude)` of a waypoint, VOR, or NDB.
+    ///
+    /// Idents aren't unique worldwide, so `near` is the previous point on
+    /// the route, if there is one, for picking the closest match.
+    fn resolve_fix(&self, ident: &str, near: Option<(f64, f64)>) -> Option<(f64, f64)>;
+
+    /// The fixes along an airway from `from` to `to`, excluding `from`
+    /// and including `to`, or `None` if there's no such airway or it
+    /// doesn't join the two.
+    fn expand_airway(&self, airway: &str, from: &str, to: &str) -> Option<Vec<Waypoint>> {
+        let _ = (airway, from, to);
+        None
+    }
+
+    /// The `(latitude, longitude)` of an airport.
+    fn resolve_airport(&self, icao: &str) -> Option<(f64, f64)> {
+        self.resolve_fix(icao, None)
+    }
+}
+
+/// A [`NavdataProvider`] with no data, which resolves nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoNavdata;
+
+impl NavdataProvider for NoNavdata {
+    fn resolve_fix(&self, _ident: &str, _near: Option<(f64, f64)>) -> Option<(f64, f64)> {
+        None
+    }
+}
+
+impl NavdataProvider for AirportDatabase {
+    /// Resolves only airports, which are the only points the database
+    /// knows about.
+    fn resolve_fix(&self, ident: &str, _near: Option<(f64, f64)>) -> Option<(f64, f64)> {
+        self.location(ident)
+    }
+}
+
+/// A route resolved to coordinates.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct RoutePath {
+    /// The points along the route that could be resolved, in order.
+    pub waypoints: Vec<Waypoint>,
+    /// Parts of the route that couldn't be resolved, like procedures or
+    /// fixes the navdata doesn't have. They're left out of the path.
+    pub unresolved: Vec<String>,
+}
+
+impl RoutePath {
+    /// Length of the path, in nautical miles, following each leg.
+    #[must_use]
+    pub fn length_nm(&self) -> f64 {
+        self.waypoints
+            .windows(2)
+            .map(|leg| {
+                distance_nm(
+                    leg[0].latitude,
+                    leg[0].longitude,
+                    leg[1].latitude,
+                    leg[1].longitude,
+                )
+            })
+            .sum()
+    }
+
+    /// Where a position is along the path, projected onto the closest leg.
+    ///
+    /// Unlike the direct distance to the destination, this follows the
+    /// route's turns. Returns `None` if the path has fewer than two
+    /// waypoints. Routes that double back on themselves can be matched to
+    /// the wrong leg.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use vatsim_utils::routes::{RoutePath, Waypoint};
+    ///
+    /// let waypoint = |ident: &str, latitude, longitude| Waypoint {
+    ///     ident: ident.to_owned(),
+    ///     latitude,
+    ///     longitude,
+    /// };
+    /// // a dogleg: north 60 nm, then east
+    /// let path = RoutePath {
+    ///     waypoints: vec![
+    ///         waypoint("A", 30.0, -100.0),
+    ///         waypoint("B", 31.0, -100.0),
+    ///         waypoint("C", 31.0, -98.8),
+    ///     ],
+    ///     unresolved: Vec::new(),
+    /// };
+    ///
+    /// // halfway up the first leg, just off to the east
+    /// let progress = path.progress(30.5, -99.99).unwrap();
+    /// assert_eq!(progress.flown_nm.round(), 30.0);
+    /// assert_eq!(progress.remaining_nm.round(), (path.length_nm() - 30.0).round());
+    /// assert_eq!(progress.next_waypoint, 1);
+    /// assert!(progress.off_route_nm < 1.0);
+    /// ```
+    #[must_use]
+    pub fn progress(&self, latitude: f64, longitude: f64) -> Option<RouteProgress> {
+        let mut flown_before = 0.0;
+        let mut closest: Option<RouteProgress> = None;
+        for (index, leg) in self.waypoints.windows(2).enumerate() {
+            let start = (leg[0].latitude, leg[0].longitude);
+            let end = (leg[1].latitude, leg[1].longitude);
+            let length = distance_nm(start.0, start.1, end.0, end.1);
+            let along = along_track_distance((latitude, longitude), start, end).clamp(0.0, length);
+            let bearing = initial_bearing(start.0, start.1, end.0, end.1);
+            let (lat, lon) = destination_point(start.0, start.1, bearing, along);
+            let off_route_nm = distance_nm(latitude, longitude, lat, lon);
+            if closest.is_none_or(|c| off_route_nm < c.off_route_nm) {
+                closest = Some(RouteProgress {
+                    flown_nm: flown_before + along,
+                    remaining_nm: 0.0,
+                    next_waypoint: index + 1,
+                    off_route_nm,
+                });
+            }
+            flown_before += length;
+        }
+        closest.map(|progress| RouteProgress {
+            remaining_nm: (flown_before - progress.flown_nm).max(0.0),
+            ..progress
+        })
+    }
+}
+
+/// How far a flight has got along a route.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct RouteProgress {
+    /// Distance along the route from its start to the flight, in nautical
+    /// miles.
+    pub flown_nm: f64,
+    /// Distance along the route from the flight to its end, in nautical
+    /// miles.
+    pub remaining_nm: f64,
+    /// Index into [`RoutePath::waypoints`] of the next waypoint.
+    pub next_waypoint: usize,
+    /// How far the flight is from the route, in nautical miles.
+    pub off_route_nm: f64,
+}
+
+impl RouteProgress {
+    /// Time to fly the rest of the route at a groundspeed in knots, if
+    /// the flight is moving.
+    #[must_use]
+    pub fn time_remaining(&self, groundspeed: i64) -> Option<Duration> {
+        #[allow(clippy::cast_precision_loss)]
+        let groundspeed = groundspeed as f64;
+        (groundspeed > 0.0)
+            .then(|| Duration::from_secs_f64(self.remaining_nm / groundspeed * 3600.0))
+    }
+}
+
+/// Resolve a filed route to a path, from the departure airport to the
+/// arrival airport.
+///
+/// Direct legs (`DCT`), speed and level changes (like `N0450F350`), and
+/// flight rules changes are skipped, and any `/` suffix on a fix is
+/// dropped. A part of the route between two fixes is expanded as an
+/// airway if the navdata can, and otherwise resolved as a fix. Anything
+/// that can't be resolved is listed in [`RoutePath::unresolved`].
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::routes::{route_path, NavdataProvider, Waypoint};
+///
+/// struct J80;
+///
+/// impl NavdataProvider for J80 {
+///     fn resolve_fix(&self, ident: &str, _near: Option<(f64, f64)>) -> Option<(f64, f64)> {
+///         match ident {
+///             "MZB" => Some((32.78, -117.23)),
+///             "BLH" => Some((33.60, -114.76)),
+///             _ => None,
+///         }
+///     }
+///
+///     fn expand_airway(&self, airway: &str, from: &str, to: &str) -> Option<Vec<Waypoint>> {
+///         (airway == "J80" && from == "MZB" && to == "BLH").then(|| vec![
+///             Waypoint { ident: String::from("JLI"), latitude: 33.14, longitude: -116.59 },
+///             Waypoint { ident: String::from("BLH"), latitude: 33.60, longitude: -114.76 },
+///         ])
+///     }
+/// }
+///
+/// let path = route_path(None, "N0450F350 MZB J80 BLH/N0450F370 DCT HOGGZ", None, &J80);
+///
+/// let idents: Vec<_> = path.waypoints.iter().map(|w| w.ident.as_str()).collect();
+/// assert_eq!(idents, ["MZB", "JLI", "BLH"]);
+/// assert_eq!(path.unresolved, ["HOGGZ"]);
+/// ```
+#[must_use]
+pub fn route_path(
+    departure: Option<&str>,
+    route: &str,
+    arrival: Option<&str>,
+    navdata: &impl NavdataProvider,
+) -> RoutePath {
+    let mut path = RoutePath::default();
+    if let Some(departure) = departure {
+        push_airport(&mut path, departure, navdata);
+    }
+    let parts: Vec<&str> = route
+        .split_whitespace()
+        .map(|part| part.split('/').next().unwrap_or(part))
+        .filter(|part| !is_skipped(part))
+        .filter(|part| Some(*part) != departure && Some(*part) != arrival)
+        .collect();
+    for (index, part) in parts.iter().enumerate() {
+        let previous = path.waypoints.last();
+        if previous.is_some_and(|w| w.ident == *part) {
+            // the airway before already ended here
+            continue;
+        }
+        let airway = match (previous, parts.get(index + 1)) {
+            (Some(from), Some(to)) if index > 0 && from.ident == parts[index - 1] => {
+                navdata.expand_airway(part, &from.ident, to)
+            }
+            _ => None,
+        };
+        if let Some(fixes) = airway {
+            path.waypoints.extend(fixes);
+            continue;
+        }
+        let near = previous.map(|w| (w.latitude, w.longitude));
+        match navdata.resolve_fix(part, near) {
+            Some((latitude, longitude)) => path.waypoints.push(Waypoint {
+                ident: (*part).to_owned(),
+                latitude,
+                longitude,
+            }),
+            None => path.unresolved.push((*part).to_owned()),
+        }
+    }
+    if let Some(arrival) = arrival {
+        push_airport(&mut path, arrival, navdata);
+    }
+    path
+}
+
+/// Add an airport to the path, or to the unresolved parts.
+fn push_airport(path: &mut RoutePath, icao: &str, navdata: &impl NavdataProvider) {
+    match navdata.resolve_airport(icao) {
+        Some((latitude, longitude)) => path.waypoints.push(Waypoint {
+            ident: icao.to_owned(),
+            latitude,
+            longitude,
+        }),
+        None => path.unresolved.push(icao.to_owned()),
+    }
+}
+
+/// Whether a part of a route isn't a point: a direct leg, a change of
+/// flight rules, or a speed and level group.
+fn is_skipped(part: &str) -> bool {
+    if part.is_empty() || matches!(part, "DCT" | "IFR" | "VFR") {
+        return true;
+    }
+    let mut chars = part.chars();
+    let speed_digits = match chars.next() {
+        Some('N' | 'K') => 4,
+        Some('M') => 3,
+        _ => return false,
+    };
+    let rest = chars.as_str().as_bytes();
+    rest.len() > speed_digits
+        && rest[..speed_digits].iter().all(u8::is_ascii_digit)
+        && matches!(rest[speed_digits], b'F' | b'A' | b'S' | b'M' | b'V')
+}
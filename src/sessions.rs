@@ -0,0 +1,199 @@
+//! Building controller sessions from live snapshots or REST data.
+//!
+//! Connections to the network are brief to drop and reconnect, so counting
+//! every connection as a session over-counts sessions and under-counts
+//! hours. The [`SessionTracker`] and [`merge_sessions`] treat connections
+//! separated by less than a gap tolerance as a single session.
+//!
+//! Requires the `chrono` feature.
+
+use crate::{
+    models::{AtcSessionEntry, Observers, V3ResponseData},
+    timestamps,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A period of time a controller was connected under a callsign.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ControllerSession {
+    /// Controller's CID.
+    pub cid: u64,
+    /// Callsign the controller was connected as.
+    pub callsign: String,
+    /// When the session started.
+    pub start: DateTime<Utc>,
+    /// When the controller was last seen connected.
+    pub end: DateTime<Utc>,
+}
+
+impl ControllerSession {
+    /// Create a session from an ATC session returned by the REST API.
+    ///
+    /// Returns `None` if the entry's CID or timestamps can't be parsed.
+    #[must_use]
+    pub fn from_atc_session(entry: &AtcSessionEntry) -> Option<Self> {
+        Some(Self {
+            cid: entry.vatsim_id.parse().ok()?,
+            callsign: entry.callsign.clone(),
+            start: timestamps::parse(&entry.start)?,
+            end: timestamps::parse(&entry.end)?,
+        })
+    }
+
+    /// How long the session lasted.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// Merge sessions by the same controller on the same callsign that are
+/// separated by no more than the gap tolerance.
+///
+/// The returned sessions are sorted by start time.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::sessions::{merge_sessions, ControllerSession};
+/// use chrono::{Duration, TimeZone, Utc};
+///
+/// let session = |start_minute, end_minute| ControllerSession {
+///     cid: 1234567,
+///     callsign: String::from("SAN_TWR"),
+///     start: Utc.with_ymd_and_hms(2022, 8, 1, 18, start_minute, 0).unwrap(),
+///     end: Utc.with_ymd_and_hms(2022, 8, 1, 18, end_minute, 0).unwrap(),
+/// };
+/// let merged = merge_sessions(
+///     vec![session(0, 20), session(21, 40), session(50, 59)],
+///     Duration::minutes(2),
+/// );
+///
+/// assert_eq!(merged.len(), 2);
+/// assert_eq!(merged[0].duration(), Duration::minutes(40));
+/// ```
+#[must_use]
+pub fn merge_sessions(
+    mut sessions: Vec<ControllerSession>,
+    gap_tolerance: Duration,
+) -> Vec<ControllerSession> {
+    sessions.sort_by(|a, b| (a.cid, &a.callsign, a.start).cmp(&(b.cid, &b.callsign, b.start)));
+    let mut merged: Vec<ControllerSession> = Vec::with_capacity(sessions.len());
+    for session in sessions {
+        if let Some(last) = merged.last_mut() {
+            if last.cid == session.cid
+                && last.callsign == session.callsign
+                && session.start - last.end <= gap_tolerance
+            {
+                last.end = last.end.max(session.end);
+                continue;
+            }
+        }
+        merged.push(session);
+    }
+    merged.sort_by_key(|session| session.start);
+    merged
+}
+
+/// Builds controller sessions from a series of live snapshots.
+///
+/// Feed each snapshot to [`SessionTracker::update`] as it's fetched.
+/// A session ends once its controller has been missing from the snapshots
+/// for longer than the gap tolerance; a controller that reconnects within
+/// it continues their previous session.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{live_api::Vatsim, sessions::SessionTracker};
+/// use chrono::Duration;
+///
+/// # async fn _do() {
+/// let api = Vatsim::new().await.unwrap();
+/// let mut tracker = SessionTracker::new(Duration::minutes(2));
+/// loop {
+///     let data = api.get_v3_data().await.unwrap();
+///     for session in tracker.update(&data) {
+///         println!("{} controlled {} for {}", session.cid, session.callsign, session.duration());
+///     }
+///     // wait for the feed to update ...
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SessionTracker {
+    gap_tolerance: Duration,
+    observers: Observers,
+    open: HashMap<(u64, String), ControllerSession>,
+}
+
+impl SessionTracker {
+    /// Create a new tracker with the gap tolerance.
+    #[must_use]
+    pub fn new(gap_tolerance: Duration) -> Self {
+        Self {
+            gap_tolerance,
+            observers: Observers::Include,
+            open: HashMap::new(),
+        }
+    }
+
+    /// Set whether observers are tracked.
+    #[must_use]
+    pub fn observers(mut self, observers: Observers) -> Self {
+        self.observers = observers;
+        self
+    }
+
+    /// Update the tracked sessions from a new snapshot, returning any
+    /// sessions that have ended.
+    ///
+    /// The snapshot's `update_timestamp` is used as the current time;
+    /// snapshots whose timestamp can't be parsed are ignored.
+    pub fn update(&mut self, data: &V3ResponseData) -> Vec<ControllerSession> {
+        let Some(now) = timestamps::parse(&data.general.update_timestamp) else {
+            return Vec::new();
+        };
+        for controller in data.controllers_filtered(self.observers) {
+            let key = (controller.cid, controller.callsign.clone());
+            let _ = self
+                .open
+                .entry(key)
+                .and_modify(|session| session.end = now)
+                .or_insert_with(|| ControllerSession {
+                    cid: controller.cid,
+                    callsign: controller.callsign.clone(),
+                    start: timestamps::parse(&controller.logon_time).unwrap_or(now),
+                    end: now,
+                });
+        }
+        let gap_tolerance = self.gap_tolerance;
+        let ended: Vec<_> = self
+            .open
+            .iter()
+            .filter(|(_, session)| now - session.end > gap_tolerance)
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut ended: Vec<_> = ended
+            .into_iter()
+            .filter_map(|key| self.open.remove(&key))
+            .collect();
+        ended.sort_by_key(|session| session.start);
+        ended
+    }
+
+    /// Sessions that are still in progress.
+    pub fn open_sessions(&self) -> impl Iterator<Item = &ControllerSession> {
+        self.open.values()
+    }
+
+    /// End tracking, returning all sessions that were still in progress.
+    #[must_use]
+    pub fn finish(self) -> Vec<ControllerSession> {
+        let mut sessions: Vec<_> = self.open.into_values().collect();
+        sessions.sort_by_key(|session| session.start);
+        sessions
+    }
+}
@@ -0,0 +1,214 @@
+//! Finding pilots near a point without checking every pilot.
+//!
+//! [`PilotIndex`] bins pilots into a latitude/longitude grid once, so that
+//! repeated "who's within N miles of here" queries over a snapshot only
+//! look at the cells around each point.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::{live_api::Vatsim, spatial::PilotIndex};
+//!
+//! # async fn _do() {
+//! let api = Vatsim::new().await.unwrap();
+//! let data = api.get_v3_data().await.unwrap();
+//! let index = PilotIndex::new(&data.pilots);
+//! for pilot in index.within(32.7338, -117.1933, 30.0) {
+//!     println!("{} is near KSAN", pilot.callsign);
+//! }
+//! # }
+//! ```
+
+use crate::{distance::distance_nm, models::Pilot};
+use std::collections::HashMap;
+
+/// Width and height of the grid's cells by default, in degrees.
+const DEFAULT_CELL_DEGREES: f64 = 1.0;
+
+/// Pilots binned by position for proximity queries.
+#[derive(Debug, Clone)]
+pub struct PilotIndex<'a> {
+    cell_degrees: f64,
+    cells: HashMap<(i64, i64), Vec<&'a Pilot>>,
+    len: usize,
+}
+
+impl<'a> PilotIndex<'a> {
+    /// Index pilots in a grid of 1° cells.
+    #[must_use]
+    pub fn new(pilots: &'a [Pilot]) -> Self {
+        Self::with_cell_size(pilots, DEFAULT_CELL_DEGREES)
+    }
+
+    /// Index pilots in a grid of cells `cell_degrees` square.
+    ///
+    /// Smaller cells make queries with small radii faster, at the cost of
+    /// more cells to look at for larger ones. The size is shrunk to divide
+    /// evenly into 360°, and sizes that aren't a positive number are
+    /// replaced with 1°. Pilots with impossible coordinates
+    /// aren't indexed.
+    #[must_use]
+    pub fn with_cell_size(pilots: &'a [Pilot], cell_degrees: f64) -> Self {
+        let cell_degrees = if cell_degrees.is_finite() && cell_degrees > 0.0 {
+            360.0 / (360.0 / cell_degrees.min(360.0)).ceil()
+        } else {
+            DEFAULT_CELL_DEGREES
+        };
+        let mut index = Self {
+            cell_degrees,
+            cells: HashMap::new(),
+            len: 0,
+        };
+        for pilot in pilots {
+            if !(pilot.latitude.abs() <= 90.0 && pilot.longitude.abs() <= 180.0) {
+                continue;
+            }
+            let cell = (
+                index.row(pilot.latitude),
+                index.column(pilot.longitude).rem_euclid(index.columns()),
+            );
+            index.cells.entry(cell).or_default().push(pilot);
+            index.len += 1;
+        }
+        index
+    }
+
+    /// Pilots within `radius_nm` nautical miles of a point, in no
+    /// particular order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "fixtures")] {
+    /// use vatsim_utils::{fixtures, spatial::PilotIndex};
+    ///
+    /// let data = fixtures::v3_data();
+    /// let index = PilotIndex::new(&data.pilots);
+    /// let pilot = &data.pilots[0];
+    ///
+    /// let nearby = index.within(pilot.latitude, pilot.longitude, 1.0);
+    /// assert!(nearby.iter().any(|p| p.callsign == pilot.callsign));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn within(&self, latitude: f64, longitude: f64, radius_nm: f64) -> Vec<&'a Pilot> {
+        if radius_nm.is_nan() || radius_nm < 0.0 {
+            return Vec::new();
+        }
+        let lat_span = radius_nm / 60.0;
+        let cos = latitude.to_radians().cos().abs();
+        // close to the poles, every longitude can be in range
+        let lon_span = if cos * 60.0 * 180.0 > radius_nm {
+            radius_nm / (60.0 * cos)
+        } else {
+            180.0
+        };
+        let rows =
+            self.row((latitude - lat_span).max(-90.0))..=self.row((latitude + lat_span).min(90.0));
+        let first_column = self.column(longitude - lon_span);
+        let last_column = self.column(longitude + lon_span);
+        let columns = self.columns();
+        let column_count = (last_column - first_column + 1).min(columns);
+        let mut pilots = Vec::new();
+        for row in rows {
+            for column in first_column..first_column + column_count {
+                let Some(cell) = self.cells.get(&(row, column.rem_euclid(columns))) else {
+                    continue;
+                };
+                pilots.extend(cell.iter().copied().filter(|pilot| {
+                    distance_nm(latitude, longitude, pilot.latitude, pilot.longitude) <= radius_nm
+                }));
+            }
+        }
+        pilots
+    }
+
+    /// Pilots inside a latitude and longitude box, edges included, in no
+    /// particular order.
+    ///
+    /// Boxes that cross the antimeridian aren't supported.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "fixtures")] {
+    /// use vatsim_utils::{fixtures, spatial::PilotIndex};
+    ///
+    /// let data = fixtures::v3_data();
+    /// let index = PilotIndex::new(&data.pilots);
+    /// let pilot = &data.pilots[0];
+    ///
+    /// let inside = index.within_box(
+    ///     pilot.latitude - 0.1,
+    ///     pilot.longitude - 0.1,
+    ///     pilot.latitude + 0.1,
+    ///     pilot.longitude + 0.1,
+    /// );
+    /// assert!(inside.iter().any(|p| p.callsign == pilot.callsign));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn within_box(
+        &self,
+        min_lat: f64,
+        min_lon: f64,
+        max_lat: f64,
+        max_lon: f64,
+    ) -> Vec<&'a Pilot> {
+        if !(min_lat <= max_lat && min_lon <= max_lon) {
+            return Vec::new();
+        }
+        let rows = self.row(min_lat.max(-90.0))..=self.row(max_lat.min(90.0));
+        let columns = self.column(min_lon.max(-180.0))
+            ..=self.column(max_lon.min(180.0)).min(self.columns() - 1);
+        let mut pilots = Vec::new();
+        for row in rows {
+            for column in columns.clone() {
+                let Some(cell) = self.cells.get(&(row, column)) else {
+                    continue;
+                };
+                pilots.extend(cell.iter().copied().filter(|pilot| {
+                    (min_lat..=max_lat).contains(&pilot.latitude)
+                        && (min_lon..=max_lon).contains(&pilot.longitude)
+                }));
+            }
+        }
+        pilots
+    }
+
+    /// Every pilot in the index.
+    pub fn pilots(&self) -> impl Iterator<Item = &'a Pilot> + '_ {
+        self.cells.values().flatten().copied()
+    }
+
+    /// Number of pilots indexed.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no pilots are indexed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The grid row a latitude is in.
+    #[allow(clippy::cast_possible_truncation)]
+    fn row(&self, latitude: f64) -> i64 {
+        (latitude / self.cell_degrees).floor() as i64
+    }
+
+    /// The grid column a longitude is in, counting from the antimeridian
+    /// and not wrapped around it.
+    #[allow(clippy::cast_possible_truncation)]
+    fn column(&self, longitude: f64) -> i64 {
+        ((longitude + 180.0) / self.cell_degrees).floor() as i64
+    }
+
+    /// Number of columns around the globe.
+    #[allow(clippy::cast_possible_truncation)]
+    fn columns(&self) -> i64 {
+        (360.0 / self.cell_degrees).round() as i64
+    }
+}
@@ -0,0 +1,736 @@
+//! Statistics built from historical data from the REST API.
+//!
+//! Requires the `chrono` feature.
+
+#[cfg(feature = "async")]
+use crate::{errors::VatsimUtilError, rest_api};
+use crate::{
+    models::{AtcSessionEntry, ConnectionEntry},
+    timestamps,
+};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How far before a time range to fetch facility history from, so that
+/// sessions that started before the range but overlap it are included.
+#[cfg(feature = "async")]
+const SESSION_LOOKBACK_DAYS: i64 = 1;
+
+/// How much of a time range a position was staffed.
+///
+/// Percentages are from 0 to 100.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StaffingCoverage {
+    /// Start of the time range.
+    pub start: DateTime<Utc>,
+    /// End of the time range.
+    pub end: DateTime<Utc>,
+    /// Total time the position was staffed.
+    pub staffed_seconds: i64,
+    /// Percentage of the whole range that the position was staffed.
+    pub percentage: f64,
+    /// Percentage of each day that the position was staffed, in the
+    /// time zone the coverage was calculated in.
+    pub per_day: BTreeMap<NaiveDate, f64>,
+    /// Percentage of each hour of the day that the position was staffed,
+    /// across all days in the range and in the time zone the coverage was
+    /// calculated in; index 0 is 00:00-00:59.
+    pub per_hour_of_day: [f64; 24],
+}
+
+/// Calculate how much of the time range the sessions cover, with days
+/// and hours in UTC.
+///
+/// Overlapping sessions, like a controller reconnecting before their old
+/// connection timed out, are only counted once. Sessions that run past
+/// either end of the range only count the part inside it, and sessions
+/// whose timestamps can't be parsed are skipped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{rest_api::get_facility_history_all, stats::staffing_coverage};
+/// use chrono::{TimeZone, Utc};
+///
+/// # async fn _do() {
+/// let sessions: Vec<_> = get_facility_history_all("SAN_TWR", Some("2022-02-01"), None)
+///     .await
+///     .into_values()
+///     .unwrap()
+///     .into_iter()
+///     .flatten()
+///     .collect();
+/// let coverage = staffing_coverage(
+///     &sessions,
+///     Utc.with_ymd_and_hms(2022, 2, 1, 0, 0, 0).unwrap(),
+///     Utc.with_ymd_and_hms(2022, 3, 1, 0, 0, 0).unwrap(),
+/// );
+/// println!("Staffed {:.1}% of February", coverage.percentage);
+/// # }
+/// ```
+#[must_use]
+pub fn staffing_coverage(
+    sessions: &[AtcSessionEntry],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> StaffingCoverage {
+    staffing_coverage_in(sessions, start, end, &Utc)
+}
+
+/// Calculate how much of the time range the sessions cover, with days
+/// and hours in the time zone.
+///
+/// See [`staffing_coverage`] for details.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{rest_api::get_facility_history_all, stats::staffing_coverage_in};
+/// use chrono::{FixedOffset, TimeZone, Utc};
+///
+/// # async fn _do() {
+/// let sessions: Vec<_> = get_facility_history_all("SAN_TWR", Some("2022-02-01"), None)
+///     .await
+///     .into_values()
+///     .unwrap()
+///     .into_iter()
+///     .flatten()
+///     .collect();
+/// let coverage = staffing_coverage_in(
+///     &sessions,
+///     Utc.with_ymd_and_hms(2022, 2, 1, 8, 0, 0).unwrap(),
+///     Utc.with_ymd_and_hms(2022, 3, 1, 8, 0, 0).unwrap(),
+///     &FixedOffset::west_opt(8 * 3600).unwrap(),
+/// );
+/// let evening = &coverage.per_hour_of_day[18..22];
+/// println!("Staffed {:.1}% of evenings", evening.iter().sum::<f64>() / 4.0);
+/// # }
+/// ```
+#[must_use]
+pub fn staffing_coverage_in<Tz: TimeZone>(
+    sessions: &[AtcSessionEntry],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    time_zone: &Tz,
+) -> StaffingCoverage {
+    let intervals = merged_intervals(sessions, start, end);
+
+    let mut per_day_totals: BTreeMap<NaiveDate, (i64, i64)> = BTreeMap::new();
+    let mut per_hour_totals = [(0_i64, 0_i64); 24];
+    let mut slot_start = start;
+    while slot_start < end {
+        let local = slot_start.with_timezone(time_zone);
+        let hour_start = local
+            .with_minute(0)
+            .and_then(|t| t.with_second(0))
+            .and_then(|t| t.with_nanosecond(0))
+            .map_or(slot_start, |t| t.with_timezone(&Utc));
+        let slot_end = (hour_start + Duration::hours(1)).min(end);
+        let length = (slot_end - slot_start).num_seconds();
+        let staffed = overlap_seconds(&intervals, slot_start, slot_end);
+
+        let day = per_day_totals.entry(local.date_naive()).or_default();
+        day.0 += staffed;
+        day.1 += length;
+        let hour = &mut per_hour_totals[local.hour() as usize];
+        hour.0 += staffed;
+        hour.1 += length;
+
+        slot_start = slot_end;
+    }
+
+    let staffed_seconds = intervals.iter().map(|(s, e)| (*e - *s).num_seconds()).sum();
+    StaffingCoverage {
+        start,
+        end,
+        staffed_seconds,
+        percentage: percentage(staffed_seconds, (end - start).num_seconds()),
+        per_day: per_day_totals
+            .into_iter()
+            .map(|(day, (staffed, total))| (day, percentage(staffed, total)))
+            .collect(),
+        per_hour_of_day: per_hour_totals.map(|(staffed, total)| percentage(staffed, total)),
+    }
+}
+
+/// Fetch a facility's history over the time range and calculate how
+/// much of it was staffed.
+///
+/// Every session that overlaps the range is counted, clipped to it,
+/// including ones that started up to a day before it. See
+/// [`staffing_coverage`] for details.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::stats::facility_staffing_coverage;
+/// use chrono::{TimeZone, Utc};
+///
+/// # async fn _do() {
+/// let coverage = facility_staffing_coverage(
+///     "SAN_TWR",
+///     Utc.with_ymd_and_hms(2022, 2, 1, 0, 0, 0).unwrap(),
+///     Utc.with_ymd_and_hms(2022, 3, 1, 0, 0, 0).unwrap(),
+/// )
+/// .await
+/// .unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if any of the HTTP requests fail or if the
+/// returned data does not match the schemas of the models passed to
+/// the deserializer.
+#[cfg(feature = "async")]
+pub async fn facility_staffing_coverage(
+    specifier: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<StaffingCoverage, VatsimUtilError> {
+    let start_date = (start - Duration::days(SESSION_LOOKBACK_DAYS))
+        .format("%Y-%m-%d")
+        .to_string();
+    let sessions: Vec<_> = rest_api::get_facility_history_all(specifier, Some(&start_date), None)
+        .await
+        .into_values()?
+        .into_iter()
+        .flatten()
+        .filter(|session| {
+            match (
+                timestamps::parse(&session.start),
+                timestamps::parse(&session.end),
+            ) {
+                (Some(session_start), Some(session_end)) => {
+                    session_start < end && session_end > start
+                }
+                _ => false,
+            }
+        })
+        .collect();
+    Ok(staffing_coverage(&sessions, start, end))
+}
+
+/// A facility's activity on one day.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DailyActivity {
+    /// The day.
+    pub date: NaiveDate,
+    /// Minutes that at least one controller was connected, with
+    /// overlapping sessions only counted once.
+    pub staffed_minutes: i64,
+    /// Minutes that controllers were connected, adding up all sessions.
+    pub controller_minutes: i64,
+    /// Number of sessions that started on the day.
+    pub sessions: usize,
+    /// Number of different controllers connected during the day.
+    pub unique_controllers: usize,
+}
+
+/// Bucket ATC sessions into per-day activity, with days in UTC.
+///
+/// Sessions that span midnight count towards both days. The result has
+/// an entry for every day from the first session to the last, including
+/// days without any activity, so is ready to chart. Sessions whose
+/// timestamps can't be parsed are skipped.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::{models::AtcSessionEntry, stats::daily_activity};
+///
+/// // one session from 23:00 to 01:00 the next day
+/// # let session: AtcSessionEntry = serde_json::from_value(serde_json::json!({
+/// #     "connection_id": 1, "start": "2022-02-01T23:00:00", "end": "2022-02-02T01:00:00",
+/// #     "server": "USA-W", "vatsim_id": "1234567", "type": 1, "rating": 3,
+/// #     "callsign": "SAN_TWR", "minutes_on_callsign": "120", "total_minutes_on_callsign": 120.0,
+/// #     "total_aircraft_tracked": 0, "total_aircraft_seen": 0, "total_flights_amended": 0,
+/// #     "total_handoffs_initiated": 0, "total_handoffs_received": 0, "total_handoffs_refused": 0,
+/// #     "total_squawks_assigned": 0, "total_cruisealts_modified": 0, "total_tempalts_modified": 0,
+/// #     "total_scratchpadmods": 0, "aircrafttracked": 0, "aircraftseen": 0, "flightsamended": 0,
+/// #     "handoffsinitiated": 0, "handoffsreceived": 0, "handoffsrefused": 0, "squawksassigned": 0,
+/// #     "cruisealtsmodified": 0, "tempaltsmodified": 0, "scratchpadmods": 0
+/// # }))
+/// # .unwrap();
+/// let days = daily_activity(&[session]);
+/// assert_eq!(days.len(), 2);
+/// assert_eq!(days[0].staffed_minutes, 60);
+/// assert_eq!(days[1].unique_controllers, 1);
+/// ```
+#[must_use]
+pub fn daily_activity(sessions: &[AtcSessionEntry]) -> Vec<DailyActivity> {
+    daily_activity_in(sessions, &Utc)
+}
+
+/// Bucket ATC sessions into per-day activity, with days in the time zone.
+///
+/// See [`daily_activity`] for details. Days are split at local midnight,
+/// so a day with a daylight saving change is 23 or 25 hours long.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::{models::AtcSessionEntry, stats::daily_activity_in};
+/// use chrono::FixedOffset;
+///
+/// // one session from 23:00 to 01:00 the next day, UTC
+/// # let session: AtcSessionEntry = serde_json::from_value(serde_json::json!({
+/// #     "connection_id": 1, "start": "2022-02-01T23:00:00", "end": "2022-02-02T01:00:00",
+/// #     "server": "USA-W", "vatsim_id": "1234567", "type": 1, "rating": 3,
+/// #     "callsign": "SAN_TWR", "minutes_on_callsign": "120", "total_minutes_on_callsign": 120.0,
+/// #     "total_aircraft_tracked": 0, "total_aircraft_seen": 0, "total_flights_amended": 0,
+/// #     "total_handoffs_initiated": 0, "total_handoffs_received": 0, "total_handoffs_refused": 0,
+/// #     "total_squawks_assigned": 0, "total_cruisealts_modified": 0, "total_tempalts_modified": 0,
+/// #     "total_scratchpadmods": 0, "aircrafttracked": 0, "aircraftseen": 0, "flightsamended": 0,
+/// #     "handoffsinitiated": 0, "handoffsreceived": 0, "handoffsrefused": 0, "squawksassigned": 0,
+/// #     "cruisealtsmodified": 0, "tempaltsmodified": 0, "scratchpadmods": 0
+/// # }))
+/// # .unwrap();
+/// // which is 15:00 to 17:00 on one day in UTC-8
+/// let days = daily_activity_in(&[session], &FixedOffset::west_opt(8 * 3600).unwrap());
+/// assert_eq!(days.len(), 1);
+/// assert_eq!(days[0].staffed_minutes, 120);
+/// ```
+#[must_use]
+pub fn daily_activity_in<Tz: TimeZone>(
+    sessions: &[AtcSessionEntry],
+    time_zone: &Tz,
+) -> Vec<DailyActivity> {
+    let mut days: BTreeMap<NaiveDate, (i64, i64, usize, BTreeSet<&str>)> = BTreeMap::new();
+    for session in sessions {
+        let (Some(start), Some(end)) = (
+            timestamps::parse(&session.start),
+            timestamps::parse(&session.end),
+        ) else {
+            continue;
+        };
+        days.entry(start.with_timezone(time_zone).date_naive())
+            .or_default()
+            .2 += 1;
+        for (date, seconds) in split_by_day(start, end, time_zone) {
+            let day = days.entry(date).or_default();
+            day.1 += seconds;
+            let _ = day.3.insert(session.vatsim_id.as_str());
+        }
+    }
+    for (start, end) in
+        merged_intervals(sessions, DateTime::<Utc>::MIN_UTC, DateTime::<Utc>::MAX_UTC)
+    {
+        for (date, seconds) in split_by_day(start, end, time_zone) {
+            days.entry(date).or_default().0 += seconds;
+        }
+    }
+
+    let (Some(first), Some(last)) = (days.keys().next().copied(), days.keys().last().copied())
+    else {
+        return Vec::new();
+    };
+    first
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| {
+            let (staffed, controller, sessions, controllers) =
+                days.remove(&date).unwrap_or_default();
+            DailyActivity {
+                date,
+                staffed_minutes: staffed / 60,
+                controller_minutes: controller / 60,
+                sessions,
+                unique_controllers: controllers.len(),
+            }
+        })
+        .collect()
+}
+
+/// Fetch a facility's history, from all pages, and bucket it into
+/// per-day activity.
+///
+/// See [`daily_activity`] for details.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::stats::facility_daily_activity;
+///
+/// # async fn _do() {
+/// let days = facility_daily_activity("SAN_TWR", Some("2022-02-01"), None)
+///     .await
+///     .unwrap();
+/// for day in days {
+///     println!("{}: {} minutes", day.date, day.staffed_minutes);
+/// }
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if any of the HTTP requests fail or if the
+/// returned data does not match the schemas of the models passed to
+/// the deserializer.
+#[cfg(feature = "async")]
+pub async fn facility_daily_activity(
+    specifier: &str,
+    start: Option<&str>,
+    date: Option<&str>,
+) -> Result<Vec<DailyActivity>, VatsimUtilError> {
+    let sessions: Vec<_> = rest_api::get_facility_history_all(specifier, start, date)
+        .await
+        .into_values()?
+        .into_iter()
+        .flatten()
+        .collect();
+    Ok(daily_activity(&sessions))
+}
+
+/// Fetch a facility's history, from all pages, and bucket it into
+/// per-day activity with days in the time zone.
+///
+/// See [`daily_activity_in`] for details.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::stats::facility_daily_activity_in;
+/// use chrono::FixedOffset;
+///
+/// # async fn _do() {
+/// let days = facility_daily_activity_in(
+///     "SAN_TWR",
+///     Some("2022-02-01"),
+///     None,
+///     &FixedOffset::west_opt(8 * 3600).unwrap(),
+/// )
+/// .await
+/// .unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if any of the HTTP requests fail or if the
+/// returned data does not match the schemas of the models passed to
+/// the deserializer.
+#[cfg(feature = "async")]
+pub async fn facility_daily_activity_in<Tz: TimeZone>(
+    specifier: &str,
+    start: Option<&str>,
+    date: Option<&str>,
+    time_zone: &Tz,
+) -> Result<Vec<DailyActivity>, VatsimUtilError> {
+    let sessions: Vec<_> = rest_api::get_facility_history_all(specifier, start, date)
+        .await
+        .into_values()?
+        .into_iter()
+        .flatten()
+        .collect();
+    Ok(daily_activity_in(&sessions, time_zone))
+}
+
+/// Totals of controlling activity over a set of ATC sessions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PositionTotals {
+    /// Number of sessions.
+    pub sessions: usize,
+    /// Total time spent on the position(s).
+    pub time: Duration,
+    /// Number of aircraft tracked.
+    pub aircraft_tracked: u64,
+    /// Number of aircraft seen.
+    pub aircraft_seen: u64,
+    /// Number of flight plans amended.
+    pub flights_amended: u64,
+    /// Number of handoffs initiated.
+    pub handoffs_initiated: u64,
+    /// Number of handoffs received.
+    pub handoffs_received: u64,
+    /// Number of handoffs refused.
+    pub handoffs_refused: u64,
+    /// Number of squawk codes assigned.
+    pub squawks_assigned: u64,
+}
+
+impl PositionTotals {
+    /// Add a session's numbers to the totals.
+    fn add(&mut self, session: &AtcSessionEntry) {
+        self.sessions += 1;
+        self.time += session_time(session);
+        self.aircraft_tracked += session.aircraft_tracked;
+        self.aircraft_seen += session.aircraft_seen;
+        self.flights_amended += session.flights_amended;
+        self.handoffs_initiated += session.handoffs_initiated;
+        self.handoffs_received += session.handoffs_received;
+        self.handoffs_refused += session.handoffs_refused;
+        self.squawks_assigned += session.squawks_assigned;
+    }
+}
+
+/// ATC session totals, grouped a couple of ways.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AtcSessionTotals {
+    /// Totals across all sessions.
+    pub overall: PositionTotals,
+    /// Totals per callsign, like "`SAN_TWR`".
+    pub by_callsign: BTreeMap<String, PositionTotals>,
+    /// Totals per facility type, which is the callsign's suffix, like
+    /// "TWR" or "CTR".
+    pub by_facility_type: BTreeMap<String, PositionTotals>,
+}
+
+/// Total up the activity in the ATC sessions.
+///
+/// Session time comes from each session's minutes on the callsign,
+/// falling back to the difference between its start and end times.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{rest_api::get_atc_sessions, stats::atc_session_totals};
+///
+/// # async fn _do() {
+/// let sessions = get_atc_sessions(1234567890, None, None, None, None).await.unwrap();
+/// let totals = atc_session_totals(&sessions.results);
+/// for (callsign, position) in &totals.by_callsign {
+///     println!(
+///         "{callsign}: {} minutes, {} aircraft",
+///         position.time.num_minutes(),
+///         position.aircraft_tracked
+///     );
+/// }
+/// # }
+/// ```
+#[must_use]
+pub fn atc_session_totals(sessions: &[AtcSessionEntry]) -> AtcSessionTotals {
+    let mut totals = AtcSessionTotals::default();
+    for session in sessions {
+        totals.overall.add(session);
+        totals
+            .by_callsign
+            .entry(session.callsign.clone())
+            .or_default()
+            .add(session);
+        let facility_type = session
+            .callsign
+            .rsplit_once('_')
+            .map_or(session.callsign.as_str(), |(_, suffix)| suffix);
+        totals
+            .by_facility_type
+            .entry(facility_type.to_owned())
+            .or_default()
+            .add(session);
+    }
+    totals
+}
+
+/// Length of a session.
+fn session_time(session: &AtcSessionEntry) -> Duration {
+    if let Ok(minutes) = session.minutes_on_callsign.trim().parse::<f64>() {
+        #[allow(clippy::cast_possible_truncation)]
+        return Duration::seconds((minutes * 60.0).round() as i64);
+    }
+    match (
+        timestamps::parse(&session.start),
+        timestamps::parse(&session.end),
+    ) {
+        (Some(start), Some(end)) if end > start => end - start,
+        _ => Duration::zero(),
+    }
+}
+
+/// Aggregate numbers about a user's connection history.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ConnectionInsights {
+    /// Number of connections.
+    pub connections: usize,
+    /// Total hours connected.
+    pub total_hours: f64,
+    /// Hours connected per year, by the year each connection started in.
+    pub hours_by_year: BTreeMap<i32, f64>,
+    /// Hours connected per connection type.
+    pub hours_by_type: BTreeMap<u16, f64>,
+    /// The longest connection, and its length in hours.
+    pub longest: Option<(ConnectionEntry, f64)>,
+    /// The earliest connection.
+    pub first: Option<ConnectionEntry>,
+    /// The latest connection.
+    pub most_recent: Option<ConnectionEntry>,
+}
+
+/// Calculate aggregate numbers from a user's connections.
+///
+/// Connections that haven't ended yet count towards the first and most
+/// recent connections, but not towards any hours. Connections whose
+/// timestamps can't be parsed are skipped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::{rest_api::get_connections_all, stats::connection_insights};
+///
+/// # async fn _do() {
+/// let connections: Vec<_> = get_connections_all(1234567890)
+///     .await
+///     .into_values()
+///     .unwrap()
+///     .into_iter()
+///     .flatten()
+///     .collect();
+/// let insights = connection_insights(&connections);
+/// println!("{:.1} hours connected", insights.total_hours);
+/// # }
+/// ```
+#[must_use]
+pub fn connection_insights(connections: &[ConnectionEntry]) -> ConnectionInsights {
+    let mut insights = ConnectionInsights::default();
+    let mut first: Option<(DateTime<Utc>, &ConnectionEntry)> = None;
+    let mut most_recent: Option<(DateTime<Utc>, &ConnectionEntry)> = None;
+    for connection in connections {
+        let Some(start) = timestamps::parse(&connection.start) else {
+            continue;
+        };
+        insights.connections += 1;
+        if first.is_none_or(|(t, _)| start < t) {
+            first = Some((start, connection));
+        }
+        if most_recent.is_none_or(|(t, _)| start > t) {
+            most_recent = Some((start, connection));
+        }
+        let Some(end) = connection.end.as_deref().and_then(timestamps::parse) else {
+            continue;
+        };
+        let hours = hours(end - start);
+        insights.total_hours += hours;
+        *insights.hours_by_year.entry(start.year()).or_default() += hours;
+        *insights
+            .hours_by_type
+            .entry(connection.connection_type)
+            .or_default() += hours;
+        if insights.longest.as_ref().is_none_or(|(_, h)| hours > *h) {
+            insights.longest = Some((connection.clone(), hours));
+        }
+    }
+    insights.first = first.map(|(_, c)| c.clone());
+    insights.most_recent = most_recent.map(|(_, c)| c.clone());
+    insights
+}
+
+/// Fetch all of a user's connections and calculate aggregate numbers.
+///
+/// See [`connection_insights`] for details.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vatsim_utils::stats::member_connection_insights;
+///
+/// # async fn _do() {
+/// let insights = member_connection_insights(1234567890).await.unwrap();
+/// println!("{:.1} hours connected", insights.total_hours);
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function can fail if any of the HTTP requests fail or if the
+/// returned data does not match the schemas of the models passed to
+/// the deserializer.
+#[cfg(feature = "async")]
+pub async fn member_connection_insights(cid: u64) -> Result<ConnectionInsights, VatsimUtilError> {
+    let connections: Vec<_> = rest_api::get_connections_all(cid)
+        .await
+        .into_values()?
+        .into_iter()
+        .flatten()
+        .collect();
+    Ok(connection_insights(&connections))
+}
+
+/// Parse the sessions' times, clip them to the range, and merge
+/// overlapping ones, returning them sorted.
+fn merged_intervals(
+    sessions: &[AtcSessionEntry],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut intervals: Vec<_> = sessions
+        .iter()
+        .filter_map(|session| {
+            let s = timestamps::parse(&session.start)?.max(start);
+            let e = timestamps::parse(&session.end)?.min(end);
+            (s < e).then_some((s, e))
+        })
+        .collect();
+    intervals.sort();
+    let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::with_capacity(intervals.len());
+    for (s, e) in intervals {
+        match merged.last_mut() {
+            Some(last) if s <= last.1 => last.1 = last.1.max(e),
+            _ => merged.push((s, e)),
+        }
+    }
+    merged
+}
+
+/// Split the time range at each midnight in the time zone, giving the
+/// seconds of it in each local day.
+fn split_by_day<Tz: TimeZone>(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    time_zone: &Tz,
+) -> Vec<(NaiveDate, i64)> {
+    let mut parts = Vec::new();
+    let mut part_start = start;
+    while part_start < end {
+        let date = part_start.with_timezone(time_zone).date_naive();
+        let midnight = date
+            .succ_opt()
+            .and_then(|d| start_of_day(d, time_zone))
+            .unwrap_or(end);
+        let part_end = midnight.min(end);
+        parts.push((date, (part_end - part_start).num_seconds()));
+        part_start = part_end;
+    }
+    parts
+}
+
+/// When the day starts in the time zone, which isn't always midnight if
+/// a daylight saving change skips it.
+fn start_of_day<Tz: TimeZone>(date: NaiveDate, time_zone: &Tz) -> Option<DateTime<Utc>> {
+    (0..24).find_map(|hour| {
+        let local = date.and_hms_opt(hour, 0, 0)?;
+        time_zone
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+    })
+}
+
+/// Seconds of the sorted intervals that fall within the slot.
+fn overlap_seconds(
+    intervals: &[(DateTime<Utc>, DateTime<Utc>)],
+    slot_start: DateTime<Utc>,
+    slot_end: DateTime<Utc>,
+) -> i64 {
+    let first = intervals.partition_point(|(_, e)| *e <= slot_start);
+    intervals[first..]
+        .iter()
+        .take_while(|(s, _)| *s < slot_end)
+        .map(|(s, e)| ((*e).min(slot_end) - (*s).max(slot_start)).num_seconds())
+        .sum()
+}
+
+/// Length of the duration in fractional hours.
+#[allow(clippy::cast_precision_loss)]
+fn hours(duration: Duration) -> f64 {
+    duration.num_seconds() as f64 / 3600.0
+}
+
+/// Part as a percentage of the total.
+#[allow(clippy::cast_precision_loss)]
+fn percentage(part: i64, total: i64) -> f64 {
+    if total <= 0 {
+        0.0
+    } else {
+        part as f64 / total as f64 * 100.0
+    }
+}
@@ -0,0 +1,223 @@
+//! Sunrise, sunset, and civil twilight, for telling day operations from
+//! night ones.
+//!
+//! Times are calculated with the [NOAA]'s simplified solar position
+//! equations, which are accurate to within a couple of minutes away from
+//! the poles. Refraction is accounted for at sunrise and sunset; terrain
+//! and the observer's elevation aren't.
+//!
+//! [NOAA]: https://gml.noaa.gov/grad/solcalc/calcdetails.html
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::SystemTime;
+//! use vatsim_utils::{distance::AirportDatabase, sun::{daylight, Daylight}};
+//!
+//! let db = AirportDatabase::from_csv("KSAN,32.7338,-117.1933").unwrap();
+//! let (lat, lon) = db.location("KSAN").unwrap();
+//! if daylight(lat, lon, SystemTime::now()) == Daylight::Night {
+//!     println!("night operations at KSAN");
+//! }
+//! ```
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Julian date of the Unix epoch.
+const UNIX_EPOCH_JULIAN: f64 = 2_440_587.5;
+
+/// Julian date of the J2000 epoch, which the equations count from.
+const J2000_JULIAN: f64 = 2_451_545.0;
+
+/// Elevation of the sun's center at sunrise and sunset, in degrees,
+/// allowing for refraction and the size of its disc.
+const SUNRISE_ELEVATION: f64 = -0.833;
+
+/// Elevation of the sun's center at the start and end of civil twilight,
+/// in degrees.
+const CIVIL_TWILIGHT_ELEVATION: f64 = -6.0;
+
+/// Tilt of the Earth's axis, in degrees.
+const OBLIQUITY: f64 = 23.4397;
+
+/// Whether it's day, twilight, or night.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Daylight {
+    /// The sun is up.
+    Day,
+    /// The sun is down, but less than 6° below the horizon.
+    CivilTwilight,
+    /// The sun is more than 6° below the horizon.
+    Night,
+}
+
+/// The times of the sun's events over a UTC day.
+///
+/// Events that don't happen that day, like sunset during the polar
+/// summer, are `None`, as are all of them for a point that isn't on the
+/// globe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SunTimes {
+    /// Start of morning civil twilight.
+    pub civil_dawn: Option<SystemTime>,
+    /// Sunrise.
+    pub sunrise: Option<SystemTime>,
+    /// When the sun is highest.
+    pub solar_noon: Option<SystemTime>,
+    /// Sunset.
+    pub sunset: Option<SystemTime>,
+    /// End of evening civil twilight.
+    pub civil_dusk: Option<SystemTime>,
+}
+
+/// Calculate the times of sunrise, sunset, and civil twilight at a point
+/// on the UTC day containing `date`.
+///
+/// Latitude and longitude are in degrees, with east positive. The events
+/// are those around the point's solar noon, so far from the prime
+/// meridian they can fall on the UTC day before or after. A latitude
+/// outside ±90 degrees or a longitude outside ±180 degrees, or either
+/// not being finite, gives no times at all.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use vatsim_utils::sun::sun_times;
+///
+/// // 2024-06-20 at KSAN: sunrise 05:41 and sunset 20:00 local time (UTC-7)
+/// let date = UNIX_EPOCH + Duration::from_secs(1_718_841_600);
+/// let times = sun_times(32.7338, -117.1933, date);
+/// let minutes_into_day = |time: std::time::SystemTime| {
+///     time.duration_since(date).unwrap().as_secs() / 60
+/// };
+///
+/// assert!(minutes_into_day(times.sunrise.unwrap()).abs_diff(12 * 60 + 41) <= 3);
+/// assert!(minutes_into_day(times.sunset.unwrap()).abs_diff(27 * 60) <= 3);
+/// assert!(times.civil_dawn.unwrap() < times.sunrise.unwrap());
+///
+/// let nowhere = sun_times(f64::NAN, -117.1933, date);
+/// assert_eq!(nowhere.solar_noon, None);
+/// assert_eq!(nowhere.sunrise, None);
+/// ```
+#[must_use]
+pub fn sun_times(latitude: f64, longitude: f64, date: SystemTime) -> SunTimes {
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return SunTimes {
+            civil_dawn: None,
+            sunrise: None,
+            solar_noon: None,
+            sunset: None,
+            civil_dusk: None,
+        };
+    }
+    let day = match date.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs() / 86_400,
+        Err(_) => 0,
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let noon = day as f64 + 0.5 + UNIX_EPOCH_JULIAN - J2000_JULIAN;
+    let mean_noon = (noon + 0.0008).round() - longitude / 360.0;
+    let (mean_anomaly, ecliptic_longitude) = solar_longitude(mean_noon);
+    let transit =
+        mean_noon + 0.0053 * mean_anomaly.sin() - 0.0069 * (2.0 * ecliptic_longitude).sin();
+    let declination = (ecliptic_longitude.sin() * OBLIQUITY.to_radians().sin()).asin();
+    let event = |elevation: f64, sign: f64| {
+        let cos_hour_angle = (elevation.to_radians().sin()
+            - latitude.to_radians().sin() * declination.sin())
+            / (latitude.to_radians().cos() * declination.cos());
+        (-1.0..=1.0)
+            .contains(&cos_hour_angle)
+            .then(|| to_system_time(transit + sign * cos_hour_angle.acos().to_degrees() / 360.0))
+            .flatten()
+    };
+    SunTimes {
+        civil_dawn: event(CIVIL_TWILIGHT_ELEVATION, -1.0),
+        sunrise: event(SUNRISE_ELEVATION, -1.0),
+        solar_noon: to_system_time(transit),
+        sunset: event(SUNRISE_ELEVATION, 1.0),
+        civil_dusk: event(CIVIL_TWILIGHT_ELEVATION, 1.0),
+    }
+}
+
+/// Calculate the sun's elevation above the horizon at a point and time,
+/// in degrees, without refraction.
+#[must_use]
+pub fn sun_elevation(latitude: f64, longitude: f64, at: SystemTime) -> f64 {
+    let days = to_j2000_days(at);
+    let (_, ecliptic_longitude) = solar_longitude(days);
+    let obliquity = OBLIQUITY.to_radians();
+    let declination = (ecliptic_longitude.sin() * obliquity.sin()).asin();
+    let right_ascension = f64::atan2(
+        ecliptic_longitude.sin() * obliquity.cos(),
+        ecliptic_longitude.cos(),
+    );
+    let sidereal = (280.1470 + 360.985_623_5 * days).to_radians();
+    let hour_angle = sidereal + longitude.to_radians() - right_ascension;
+    let latitude = latitude.to_radians();
+    (latitude.sin() * declination.sin() + latitude.cos() * declination.cos() * hour_angle.cos())
+        .asin()
+        .to_degrees()
+}
+
+/// Whether it's day, civil twilight, or night at a point and time.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use vatsim_utils::sun::{daylight, Daylight};
+///
+/// // 2024-06-20 at KSAN, at 12:00 and 23:00 local time (UTC-7)
+/// let noon = UNIX_EPOCH + Duration::from_secs(1_718_841_600 + 19 * 3600);
+/// let late = UNIX_EPOCH + Duration::from_secs(1_718_841_600 + 30 * 3600);
+///
+/// assert_eq!(daylight(32.7338, -117.1933, noon), Daylight::Day);
+/// assert_eq!(daylight(32.7338, -117.1933, late), Daylight::Night);
+/// ```
+#[must_use]
+pub fn daylight(latitude: f64, longitude: f64, at: SystemTime) -> Daylight {
+    let elevation = sun_elevation(latitude, longitude, at);
+    if elevation > SUNRISE_ELEVATION {
+        Daylight::Day
+    } else if elevation > CIVIL_TWILIGHT_ELEVATION {
+        Daylight::CivilTwilight
+    } else {
+        Daylight::Night
+    }
+}
+
+/// The sun's mean anomaly and ecliptic longitude, in radians, at a time
+/// in days since J2000.
+fn solar_longitude(days: f64) -> (f64, f64) {
+    let mean_anomaly = (357.5291 + 0.985_600_28 * days)
+        .rem_euclid(360.0)
+        .to_radians();
+    let center = 1.9148 * mean_anomaly.sin()
+        + 0.02 * (2.0 * mean_anomaly).sin()
+        + 0.0003 * (3.0 * mean_anomaly).sin();
+    let ecliptic_longitude = (mean_anomaly.to_degrees() + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    (mean_anomaly, ecliptic_longitude)
+}
+
+/// Days since J2000 at a time.
+fn to_j2000_days(at: SystemTime) -> f64 {
+    let seconds = match at.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs_f64(),
+        Err(before) => -before.duration().as_secs_f64(),
+    };
+    seconds / 86_400.0 + UNIX_EPOCH_JULIAN - J2000_JULIAN
+}
+
+/// The time at a number of days since J2000, if it's finite and
+/// representable.
+fn to_system_time(days: f64) -> Option<SystemTime> {
+    let seconds = (days + J2000_JULIAN - UNIX_EPOCH_JULIAN) * 86_400.0;
+    if seconds >= 0.0 {
+        UNIX_EPOCH.checked_add(Duration::try_from_secs_f64(seconds).ok()?)
+    } else {
+        UNIX_EPOCH.checked_sub(Duration::try_from_secs_f64(-seconds).ok()?)
+    }
+}
@@ -0,0 +1,262 @@
+//! A fake VATSIM server for integration tests that run offline.
+//!
+//! [`MockVatsim`] starts a local HTTP server that serves canned responses
+//! for the status file, the live data feeds, and the REST API. Point the
+//! crate at it with [`MockVatsim::install`], and replace any of the canned
+//! responses with your own data as needed.
+//!
+//! Requires the `testing` feature, and must be run within a [tokio] runtime.
+//!
+//! Note that [`install`](MockVatsim::install) changes the endpoints for
+//! the whole process, so tests that use different mock servers shouldn't
+//! run at the same time.
+//!
+//! [tokio]: https://docs.rs/tokio/latest/tokio/
+//!
+//! # Example
+//!
+//! ```rust
+//! use vatsim_utils::{fixtures, live_api::Vatsim, testing::MockVatsim};
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let mock = MockVatsim::start().await;
+//! mock.install();
+//!
+//! let api = Vatsim::new().await.unwrap();
+//! let data = api.get_v3_data().await.unwrap();
+//! assert_eq!(data.pilots.len(), fixtures::v3_data().pilots.len());
+//!
+//! // replace the live data with your own
+//! let mut quiet = fixtures::v3_data();
+//! quiet.pilots.clear();
+//! mock.set_v3_data(&quiet).await;
+//! assert!(api.get_v3_data().await.unwrap().pilots.is_empty());
+//! # }
+//! ```
+
+use crate::{
+    fixtures,
+    models::{TransceiverResponseEntry, V3ResponseData},
+    urls::{self, Endpoints},
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use wiremock::{
+    matchers::{method, path, path_regex},
+    Mock, MockServer, Request, ResponseTemplate,
+};
+
+/// Priority of user-supplied responses, so they're used over the
+/// canned ones.
+const OVERRIDE_PRIORITY: u8 = 1;
+
+/// A local server serving fake VATSIM API responses.
+#[derive(Debug)]
+pub struct MockVatsim {
+    server: MockServer,
+}
+
+impl MockVatsim {
+    /// Start a new server with the canned responses.
+    ///
+    /// The live data and REST API responses are those in [`fixtures`],
+    /// except for facility history, which is empty. The slurper reports
+    /// the users in the V3 fixture as online, and the METAR service has
+    /// the same clear-skies METAR for every four-letter station.
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+        let uri = server.uri();
+        let canned = [
+            ("/status.json".to_owned(), status(&uri)),
+            ("/v3/sweatbox-servers.json".to_owned(), sweatbox_servers()),
+        ];
+        for (p, body) in canned {
+            Mock::given(method("GET"))
+                .and(path(p))
+                .respond_with(ResponseTemplate::new(200).set_body_json(body))
+                .mount(&server)
+                .await;
+        }
+        let fixtures = [
+            (r"^/v3/vatsim-data\.json$", fixtures::V3_DATA),
+            (
+                r"^/v3/transceivers-data\.json$",
+                fixtures::TRANSCEIVERS_DATA,
+            ),
+            (r"^/api/regions/$", fixtures::REGIONS),
+            (r"^/api/divisions/$", fixtures::DIVISIONS),
+            (r"^/api/facilities/$", fixtures::FACILITIES),
+            (r"^/api/ratings/\d+/$", fixtures::RATINGS),
+            (r"^/api/ratings/\d+/rating_times$", fixtures::RATING_TIMES),
+            (r"^/api/ratings/\d+/connections$", fixtures::CONNECTIONS),
+            (r"^/api/ratings/\d+/atcsessions/.*$", fixtures::ATC_SESSIONS),
+            (r"^/api/ratings/\d+/flight_plans$", fixtures::FLIGHT_PLANS),
+        ];
+        for (p, body) in fixtures {
+            Mock::given(method("GET"))
+                .and(path_regex(p))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path_regex(r"^/api/facilities/.+$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(empty_page()))
+            .mount(&server)
+            .await;
+        let online = slurper_lines(&fixtures::v3_data());
+        Mock::given(method("GET"))
+            .and(path("/slurper/users/info/"))
+            .respond_with(move |request: &Request| {
+                let cid = request
+                    .url
+                    .query_pairs()
+                    .find(|(key, _)| key == "cid")
+                    .map(|(_, value)| value.into_owned())
+                    .unwrap_or_default();
+                let body: Vec<&str> = online
+                    .iter()
+                    .filter(|line| line.split(',').next() == Some(cid.as_str()))
+                    .map(String::as_str)
+                    .collect();
+                let body = body.join("\n");
+                ResponseTemplate::new(200).set_body_string(body)
+            })
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/metar"))
+            .respond_with(|request: &Request| {
+                let body = request
+                    .url
+                    .query_pairs()
+                    .find(|(key, _)| key == "id")
+                    .filter(|(_, id)| id.len() == 4)
+                    .map(|(_, id)| metar(&id))
+                    .unwrap_or_default();
+                ResponseTemplate::new(200).set_body_string(body)
+            })
+            .mount(&server)
+            .await;
+        Self { server }
+    }
+
+    /// The base URL of the server, like `http://127.0.0.1:1234`.
+    #[must_use]
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Endpoints for all of the VATSIM services pointing at this server.
+    #[must_use]
+    pub fn endpoints(&self) -> Endpoints {
+        let uri = self.uri();
+        Endpoints {
+            status: format!("{uri}/status.json"),
+            api: uri.clone(),
+            metar: format!("{uri}/metar"),
+            events: format!("{uri}/events"),
+            bookings: format!("{uri}/bookings"),
+            auth: uri.clone(),
+            slurper: format!("{uri}/slurper"),
+        }
+    }
+
+    /// Point the crate at this server; see [`urls::set_endpoints`].
+    pub fn install(&self) {
+        urls::set_endpoints(self.endpoints());
+    }
+
+    /// Serve the data from the V3 live feed instead of the canned data.
+    pub async fn set_v3_data(&self, data: &V3ResponseData) {
+        self.set_json("/v3/vatsim-data.json", data).await;
+    }
+
+    /// Serve the data from the transceivers feed instead of the canned data.
+    pub async fn set_transceivers_data(&self, data: &[TransceiverResponseEntry]) {
+        self.set_json("/v3/transceivers-data.json", &data).await;
+    }
+
+    /// Serve the JSON from the path, replacing any canned response.
+    pub async fn set_json<T: Serialize + ?Sized>(&self, p: &str, body: &T) {
+        Mock::given(method("GET"))
+            .and(path(p))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .with_priority(OVERRIDE_PRIORITY)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Respond to requests to the path with the status code, like to
+    /// test error handling.
+    pub async fn set_status(&self, p: &str, status: u16) {
+        Mock::given(method("GET"))
+            .and(path(p))
+            .respond_with(ResponseTemplate::new(status))
+            .with_priority(OVERRIDE_PRIORITY)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// The underlying [`wiremock`] server, for setting up anything else.
+    #[must_use]
+    pub fn server(&self) -> &MockServer {
+        &self.server
+    }
+}
+
+/// Canned status file, pointing at the server.
+fn status(uri: &str) -> Value {
+    json!({
+        "data": {
+            "v3": [format!("{uri}/v3/vatsim-data.json")],
+            "transceivers": [format!("{uri}/v3/transceivers-data.json")],
+            "servers": [],
+            "servers_sweatbox": [format!("{uri}/v3/sweatbox-servers.json")],
+            "servers_all": []
+        },
+        "user": [],
+        "metar": [format!("{uri}/metar")]
+    })
+}
+
+/// The sweatbox servers from the V3 fixture.
+fn sweatbox_servers() -> Value {
+    let servers: Vec<_> = fixtures::v3_data()
+        .servers
+        .into_iter()
+        .filter(|s| s.is_sweatbox)
+        .collect();
+    json!(servers)
+}
+
+/// Slurper lines for everyone connected in the data.
+fn slurper_lines(data: &V3ResponseData) -> Vec<String> {
+    let pilots = data.pilots.iter().map(|p| {
+        format!(
+            "{},{},pilot,{},{}",
+            p.cid, p.callsign, p.latitude, p.longitude
+        )
+    });
+    let controllers = data
+        .controllers
+        .iter()
+        .map(|c| format!("{},{},atc,0,0", c.cid, c.callsign));
+    pilots.chain(controllers).collect()
+}
+
+/// Canned METAR for the station.
+fn metar(station: &str) -> String {
+    format!("{station} 181830Z 28010KT 10SM CLR 18/09 A3001")
+}
+
+/// An empty page of results.
+fn empty_page() -> Value {
+    json!({
+        "count": 0,
+        "next": null,
+        "previous": null,
+        "results": []
+    })
+}
@@ -0,0 +1,84 @@
+//! Parsing the timestamp strings found in the API models.
+//!
+//! The models keep timestamps as the strings the APIs return, since the
+//! formats vary between endpoints; the V3 feed includes a UTC offset, while
+//! the REST API's often don't. The functions here handle both.
+//!
+//! [`parse`] returns a [chrono] date & time and requires the `chrono`
+//! feature; [`parse_offset_date_time`] returns a [time] one and requires
+//! the `time` feature. Both accept the same formats.
+//!
+//! [chrono]: https://docs.rs/chrono/latest/chrono/
+//! [time]: https://docs.rs/time/latest/time/
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, NaiveDateTime, Utc};
+#[cfg(feature = "time")]
+use time::{
+    format_description::{well_known::Rfc3339, FormatItem},
+    macros::format_description,
+    OffsetDateTime, PrimitiveDateTime,
+};
+
+/// Formats of timestamps without an offset, for the `time` crate.
+#[cfg(feature = "time")]
+const NAIVE_FORMATS: [&[FormatItem<'static>]; 2] = [
+    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]]"),
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second][optional [.[subsecond]]]"),
+];
+
+/// Parse a timestamp from the APIs into a UTC date & time.
+///
+/// Timestamps without an offset are assumed to be in UTC.
+/// Returns `None` if the string can't be parsed.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::timestamps::parse;
+///
+/// let with_offset = parse("2022-08-05T00:17:10.6394573Z").unwrap();
+/// let without_offset = parse("2022-08-05T00:17:10").unwrap();
+///
+/// assert_eq!(with_offset.timestamp(), without_offset.timestamp());
+/// assert!(parse("yesterday").is_none());
+/// ```
+#[cfg(feature = "chrono")]
+#[must_use]
+pub fn parse(timestamp: &str) -> Option<DateTime<Utc>> {
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(timestamp) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.f"))
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// Parse a timestamp from the APIs into a UTC [`OffsetDateTime`].
+///
+/// Timestamps without an offset are assumed to be in UTC.
+/// Returns `None` if the string can't be parsed.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::timestamps::parse_offset_date_time;
+///
+/// let with_offset = parse_offset_date_time("2022-08-05T00:17:10.6394573Z").unwrap();
+/// let without_offset = parse_offset_date_time("2022-08-05T00:17:10").unwrap();
+///
+/// assert_eq!(with_offset.unix_timestamp(), without_offset.unix_timestamp());
+/// assert!(parse_offset_date_time("yesterday").is_none());
+/// ```
+#[cfg(feature = "time")]
+#[must_use]
+pub fn parse_offset_date_time(timestamp: &str) -> Option<OffsetDateTime> {
+    if let Ok(parsed) = OffsetDateTime::parse(timestamp, &Rfc3339) {
+        return Some(parsed.to_offset(time::UtcOffset::UTC));
+    }
+    NAIVE_FORMATS
+        .iter()
+        .find_map(|format| PrimitiveDateTime::parse(timestamp, format).ok())
+        .map(PrimitiveDateTime::assume_utc)
+}
@@ -0,0 +1,766 @@
+//! Following pilots' flights across snapshots of live data.
+//!
+//! Feed each snapshot to [`FlightTracker::update`] as it's fetched. The
+//! tracker keeps a short history of each pilot's positions, and uses it to
+//! work out which [`FlightPhase`] they're in, and to spot go-arounds and
+//! diversions, which are collected as [`FlightEvent`]s.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::{live_api::Vatsim, tracking::FlightTracker};
+//!
+//! # async fn _do() {
+//! let api = Vatsim::new().await.unwrap();
+//! let mut tracker = FlightTracker::new();
+//! loop {
+//!     let data = api.get_v3_data().await.unwrap();
+//!     let _ = tracker.update(&data);
+//!     for flight in tracker.flights() {
+//!         println!("{}: {:?}", flight.callsign, flight.phase);
+//!     }
+//!     // wait for the feed to update ...
+//! }
+//! # }
+//! ```
+
+use crate::{
+    distance::{distance_nm, initial_bearing, AirportDatabase},
+    models::{Pilot, V3ResponseData},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, SystemTime},
+};
+
+/// Groundspeed, in knots, above which a pilot is taken to be airborne.
+pub(crate) const AIRBORNE_GROUNDSPEED_KTS: i64 = 50;
+
+/// Groundspeed, in knots, above which a pilot on the ground is moving.
+const TAXI_GROUNDSPEED_KTS: i64 = 5;
+
+/// Vertical speed, in feet per minute, beyond which a pilot is climbing
+/// or descending rather than level.
+const LEVEL_VERTICAL_SPEED_FPM: f64 = 300.0;
+
+/// Distance from the destination, in nautical miles, within which a
+/// descending pilot is on approach.
+const APPROACH_DISTANCE_NM: f64 = 20.0;
+
+/// How far back rates of change are measured over, to smooth out the
+/// feed's rounding.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Furthest a holding aircraft strays from its latest position, in
+/// nautical miles.
+const HOLD_MAX_SPAN_NM: f64 = 12.0;
+
+/// Furthest a holding aircraft's altitude strays from its latest
+/// altitude, in feet.
+const HOLD_ALTITUDE_TOLERANCE_FT: i64 = 300;
+
+/// Degrees turned in one direction to count as holding: a full circuit.
+const HOLD_MIN_TURN_DEGREES: f64 = 360.0;
+
+/// Distance from the destination, in nautical miles, that a flight on
+/// approach has to come within before climbing away counts as a
+/// go-around. On a normal approach that's around 1,500 feet above the
+/// field.
+const GO_AROUND_DISTANCE_NM: f64 = 5.0;
+
+/// Altitude, in feet, that a flight on approach has to descend below
+/// within [`GO_AROUND_DISTANCE_NM`] of the destination before climbing
+/// away counts as a go-around, so that climbing from an intermediate
+/// approach altitude doesn't. This is above sea level, as the airport
+/// database doesn't have elevations, so it's high enough to allow for
+/// most airports.
+const GO_AROUND_MAX_ALTITUDE_FT: i64 = 3_000;
+
+/// Furthest a flight can be from an airport, in nautical miles, when it
+/// lands for it to count as landing there.
+const LANDING_AIRPORT_DISTANCE_NM: f64 = 5.0;
+
+/// Where a flight is, from the gate to the gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlightPhase {
+    /// On the ground and stationary, before departing.
+    Preflight,
+    /// Moving on the ground before departing.
+    Taxi,
+    /// Airborne and climbing.
+    Climb,
+    /// Airborne and level after climbing.
+    Cruise,
+    /// Airborne and descending.
+    Descent,
+    /// Descending close to the destination.
+    Approach,
+    /// Back on the ground after flying.
+    Landed,
+}
+
+/// Whether a flight is climbing, descending, or neither, for showing an
+/// arrow next to its altitude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerticalTrend {
+    /// Climbing faster than 300 feet per minute.
+    Climbing,
+    /// Within 300 feet per minute of level.
+    Level,
+    /// Descending faster than 300 feet per minute.
+    Descending,
+}
+
+/// Something notable a tracked flight did on arrival.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum FlightEvent {
+    /// The flight descended low and close to its destination on approach
+    /// and then climbed away.
+    GoAround {
+        /// Pilot's CID.
+        cid: u64,
+        /// Callsign the pilot is connected as.
+        callsign: String,
+        /// The airport the flight was approaching.
+        airport: String,
+    },
+    /// The flight landed at an airport other than the one in its flight
+    /// plan.
+    Diverted {
+        /// Pilot's CID.
+        cid: u64,
+        /// Callsign the pilot is connected as.
+        callsign: String,
+        /// The arrival airport from the flight plan.
+        filed: String,
+        /// The airport the flight landed at.
+        airport: String,
+    },
+}
+
+/// A pilot's position at the time of a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct TrackPoint {
+    /// When the snapshot was updated.
+    pub time: SystemTime,
+    /// Latitude, in degrees.
+    pub latitude: f64,
+    /// Longitude, in degrees.
+    pub longitude: f64,
+    /// Altitude, in feet.
+    pub altitude: i64,
+    /// Groundspeed, in knots.
+    pub groundspeed: i64,
+    /// Heading, in degrees.
+    pub heading: i64,
+}
+
+impl TrackPoint {
+    /// The pilot's position at the time.
+    #[must_use]
+    pub fn from_pilot(pilot: &Pilot, time: SystemTime) -> Self {
+        Self {
+            time,
+            latitude: pilot.latitude,
+            longitude: pilot.longitude,
+            altitude: pilot.altitude,
+            groundspeed: pilot.groundspeed,
+            heading: pilot.heading,
+        }
+    }
+}
+
+/// A holding pattern a flight is flying.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Hold {
+    /// `(latitude, longitude)` of the center of the pattern, as an
+    /// estimate of the holding fix, which is within a few miles of it.
+    pub fix: (f64, f64),
+    /// Altitude the flight is holding at, in feet.
+    pub altitude: i64,
+    /// How long the flight has been in the pattern, as far back as its
+    /// history goes.
+    pub duration: Duration,
+    /// Number of full circuits flown.
+    pub circuits: f64,
+}
+
+/// A pilot being followed by a [`FlightTracker`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TrackedFlight {
+    /// Pilot's CID.
+    pub cid: u64,
+    /// Callsign the pilot is connected as.
+    pub callsign: String,
+    /// Departure airport from the flight plan, if one was filed.
+    pub departure: Option<String>,
+    /// Arrival airport from the flight plan, if one was filed.
+    pub arrival: Option<String>,
+    /// The flight's current phase.
+    pub phase: FlightPhase,
+    /// Positions from the most recent snapshots, oldest first.
+    pub history: VecDeque<TrackPoint>,
+}
+
+impl TrackedFlight {
+    /// The most recent position.
+    #[must_use]
+    pub fn latest(&self) -> Option<&TrackPoint> {
+        self.history.back()
+    }
+
+    /// Vertical speed, in feet per minute, estimated from the positions
+    /// over the last minute.
+    ///
+    /// The feed doesn't include vertical speed, so this lags the pilot's
+    /// actual rate a little. Returns `None` until there are two positions.
+    #[must_use]
+    pub fn vertical_speed(&self) -> Option<f64> {
+        vertical_speed_fpm(self.rate_start()?, self.latest()?)
+    }
+
+    /// Whether the flight is climbing, descending, or level, from its
+    /// [vertical speed](Self::vertical_speed).
+    #[must_use]
+    pub fn vertical_trend(&self) -> Option<VerticalTrend> {
+        let vs = self.vertical_speed()?;
+        Some(if vs > LEVEL_VERTICAL_SPEED_FPM {
+            VerticalTrend::Climbing
+        } else if vs < -LEVEL_VERTICAL_SPEED_FPM {
+            VerticalTrend::Descending
+        } else {
+            VerticalTrend::Level
+        })
+    }
+
+    /// Ground track, the true course over the ground in degrees, from the
+    /// positions over the last minute.
+    ///
+    /// Unlike the reported heading, this includes the effect of wind.
+    /// Returns `None` until the flight has moved between two positions.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::{collections::VecDeque, time::{Duration, UNIX_EPOCH}};
+    /// use vatsim_utils::tracking::{FlightPhase, TrackPoint, TrackedFlight, VerticalTrend};
+    ///
+    /// let point = |seconds, latitude, altitude| TrackPoint {
+    ///     time: UNIX_EPOCH + Duration::from_secs(seconds),
+    ///     latitude,
+    ///     longitude: -117.0,
+    ///     altitude,
+    ///     groundspeed: 250,
+    ///     heading: 0,
+    /// };
+    /// let flight = TrackedFlight {
+    ///     cid: 1234567,
+    ///     callsign: String::from("AAL123"),
+    ///     departure: None,
+    ///     arrival: None,
+    ///     phase: FlightPhase::Climb,
+    ///     history: VecDeque::from([point(0, 32.0, 5000), point(30, 32.1, 6000)]),
+    /// };
+    ///
+    /// assert_eq!(flight.vertical_speed(), Some(2000.0));
+    /// assert_eq!(flight.vertical_trend(), Some(VerticalTrend::Climbing));
+    /// assert_eq!(flight.ground_track().map(f64::round), Some(0.0));
+    /// ```
+    #[must_use]
+    pub fn ground_track(&self) -> Option<f64> {
+        let (from, to) = (self.rate_start()?, self.latest()?);
+        if distance_nm(from.latitude, from.longitude, to.latitude, to.longitude) < 0.01 {
+            return None;
+        }
+        Some(initial_bearing(
+            from.latitude,
+            from.longitude,
+            to.latitude,
+            to.longitude,
+        ))
+    }
+
+    /// The holding pattern the flight is flying, if it's in one.
+    ///
+    /// A flight is holding if its most recent positions are all within a
+    /// small area at roughly the same altitude, and it's turned through a
+    /// full circuit in one direction in that time. Flights are only
+    /// recognized once they've flown a full circuit, and the duration is
+    /// limited by the [history kept](FlightTracker::max_history).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::{collections::VecDeque, time::{Duration, UNIX_EPOCH}};
+    /// use vatsim_utils::{
+    ///     distance::{destination_point, haversine},
+    ///     tracking::{FlightPhase, TrackPoint, TrackedFlight},
+    /// };
+    ///
+    /// // flying circles around a fix, turning 30 degrees every 15 seconds
+    /// let history: VecDeque<_> = (0..30)
+    ///     .map(|i| {
+    ///         let heading = (i * 30) % 360;
+    ///         let (latitude, longitude) =
+    ///             destination_point(32.0, -117.0, f64::from(heading - 90), 2.0);
+    ///         TrackPoint {
+    ///             time: UNIX_EPOCH + Duration::from_secs(u64::try_from(i * 15).unwrap()),
+    ///             latitude,
+    ///             longitude,
+    ///             altitude: 8000,
+    ///             groundspeed: 200,
+    ///             heading: i64::from(heading),
+    ///         }
+    ///     })
+    ///     .collect();
+    /// let flight = TrackedFlight {
+    ///     cid: 1234567,
+    ///     callsign: String::from("AAL123"),
+    ///     departure: None,
+    ///     arrival: None,
+    ///     phase: FlightPhase::Descent,
+    ///     history,
+    /// };
+    ///
+    /// let hold = flight.holding().unwrap();
+    /// assert_eq!(hold.altitude, 8000);
+    /// assert!(hold.circuits > 2.0);
+    /// assert!(haversine(hold.fix.0, hold.fix.1, 32.0, -117.0) < 1.0);
+    /// ```
+    #[must_use]
+    pub fn holding(&self) -> Option<Hold> {
+        let latest = self.latest()?;
+        if latest.groundspeed <= AIRBORNE_GROUNDSPEED_KTS {
+            return None;
+        }
+        let pattern: Vec<&TrackPoint> = self
+            .history
+            .iter()
+            .rev()
+            .take_while(|point| {
+                point.groundspeed > AIRBORNE_GROUNDSPEED_KTS
+                    && point.altitude.abs_diff(latest.altitude)
+                        <= HOLD_ALTITUDE_TOLERANCE_FT.unsigned_abs()
+                    && distance_nm(
+                        point.latitude,
+                        point.longitude,
+                        latest.latitude,
+                        latest.longitude,
+                    ) <= HOLD_MAX_SPAN_NM
+            })
+            .collect();
+        let oldest = pattern.last()?;
+        // the pattern is newest first, so reverse each pair's turn
+        let turned: i64 = pattern
+            .windows(2)
+            .map(|pair| {
+                let delta = (pair[0].heading - pair[1].heading).rem_euclid(360);
+                if delta > 180 {
+                    delta - 360
+                } else {
+                    delta
+                }
+            })
+            .sum();
+        #[allow(clippy::cast_precision_loss)]
+        let circuits = turned.unsigned_abs() as f64 / HOLD_MIN_TURN_DEGREES;
+        if circuits < 1.0 {
+            return None;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let count = pattern.len() as f64;
+        let fix = (
+            pattern.iter().map(|p| p.latitude).sum::<f64>() / count,
+            pattern.iter().map(|p| p.longitude).sum::<f64>() / count,
+        );
+        Some(Hold {
+            fix,
+            altitude: latest.altitude,
+            duration: latest.time.duration_since(oldest.time).unwrap_or_default(),
+            circuits,
+        })
+    }
+
+    /// The oldest position within the rate window of the latest one, or
+    /// the one before the latest if they're further apart.
+    fn rate_start(&self) -> Option<&TrackPoint> {
+        let latest = self.latest()?;
+        let mut earlier = self.history.iter().rev().skip(1);
+        let previous = earlier.next()?;
+        Some(
+            earlier
+                .take_while(|point| {
+                    latest
+                        .time
+                        .duration_since(point.time)
+                        .is_ok_and(|age| age <= RATE_WINDOW)
+                })
+                .last()
+                .unwrap_or(previous),
+        )
+    }
+
+    /// Whether the flight has been in the air while tracked.
+    fn has_flown(&self) -> bool {
+        matches!(
+            self.phase,
+            FlightPhase::Climb
+                | FlightPhase::Cruise
+                | FlightPhase::Descent
+                | FlightPhase::Approach
+                | FlightPhase::Landed
+        )
+    }
+
+    /// The event for the flight moving from the `previous` phase to its
+    /// current one, if there is one.
+    fn arrival_event(
+        &self,
+        previous: FlightPhase,
+        airports: &AirportDatabase,
+    ) -> Option<FlightEvent> {
+        let filed = self.arrival.as_ref()?;
+        match (previous, self.phase) {
+            (FlightPhase::Approach, FlightPhase::Climb) => {
+                let (lat, lon) = airports.location(filed)?;
+                let came_close = self.history.iter().any(|point| {
+                    point.altitude <= GO_AROUND_MAX_ALTITUDE_FT
+                        && distance_nm(point.latitude, point.longitude, lat, lon)
+                            <= GO_AROUND_DISTANCE_NM
+                });
+                came_close.then(|| FlightEvent::GoAround {
+                    cid: self.cid,
+                    callsign: self.callsign.clone(),
+                    airport: filed.clone(),
+                })
+            }
+            (
+                FlightPhase::Climb
+                | FlightPhase::Cruise
+                | FlightPhase::Descent
+                | FlightPhase::Approach,
+                FlightPhase::Landed,
+            ) => {
+                let latest = self.latest()?;
+                let (airport, distance) = airports.nearest(latest.latitude, latest.longitude)?;
+                (distance <= LANDING_AIRPORT_DISTANCE_NM && !airport.eq_ignore_ascii_case(filed))
+                    .then(|| FlightEvent::Diverted {
+                        cid: self.cid,
+                        callsign: self.callsign.clone(),
+                        filed: filed.clone(),
+                        airport: airport.to_owned(),
+                    })
+            }
+            _ => None,
+        }
+    }
+
+    /// The flight's next phase, after `point` was added to the history.
+    fn next_phase(&self, point: &TrackPoint, to_destination_nm: Option<f64>) -> FlightPhase {
+        if point.groundspeed <= AIRBORNE_GROUNDSPEED_KTS {
+            return if self.has_flown() {
+                FlightPhase::Landed
+            } else if point.groundspeed > TAXI_GROUNDSPEED_KTS {
+                FlightPhase::Taxi
+            } else {
+                FlightPhase::Preflight
+            };
+        }
+        let vertical_speed = self
+            .rate_start()
+            .and_then(|start| vertical_speed_fpm(start, point));
+        let near_destination = to_destination_nm.is_some_and(|nm| nm <= APPROACH_DISTANCE_NM);
+        match (self.phase, vertical_speed) {
+            (_, Some(vs)) if vs > LEVEL_VERTICAL_SPEED_FPM => FlightPhase::Climb,
+            (FlightPhase::Approach, _) => FlightPhase::Approach,
+            (_, Some(vs)) if vs < -LEVEL_VERTICAL_SPEED_FPM => {
+                if near_destination {
+                    FlightPhase::Approach
+                } else {
+                    FlightPhase::Descent
+                }
+            }
+            (FlightPhase::Descent, _) if near_destination => FlightPhase::Approach,
+            (FlightPhase::Descent, _) => FlightPhase::Descent,
+            (FlightPhase::Preflight | FlightPhase::Taxi | FlightPhase::Landed, _) => {
+                if self.history.len() > 1 {
+                    FlightPhase::Climb
+                } else {
+                    // first seen in the air, so with no trend to go on
+                    FlightPhase::Cruise
+                }
+            }
+            (FlightPhase::Climb | FlightPhase::Cruise, _) => FlightPhase::Cruise,
+        }
+    }
+}
+
+/// Vertical speed between two points, in feet per minute, if they're at
+/// different times.
+#[allow(clippy::cast_precision_loss)]
+fn vertical_speed_fpm(from: &TrackPoint, to: &TrackPoint) -> Option<f64> {
+    let elapsed = to.time.duration_since(from.time).ok()?.as_secs_f64();
+    if elapsed <= 0.0 {
+        return None;
+    }
+    Some((to.altitude - from.altitude) as f64 * 60.0 / elapsed)
+}
+
+/// Follows pilots' flights across snapshots.
+///
+/// Flights are identified by the pilot's CID and callsign. A flight that's
+/// missing from snapshots for longer than the gap tolerance is finished.
+///
+/// Go-arounds and diversions are recognized as flights arrive, and held
+/// until they're collected with [`FlightTracker::take_events`]. Both need
+/// the [locations of airports](FlightTracker::airports): go-arounds to
+/// know where the destination is, and diversions to know which airport a
+/// flight landed at.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "fixtures")] {
+/// use vatsim_utils::{fixtures, tracking::{FlightPhase, FlightTracker}};
+///
+/// let mut tracker = FlightTracker::new();
+/// let mut data = fixtures::v3_data();
+/// data.general.update = String::from("20240302180000");
+/// data.pilots[0].groundspeed = 250;
+/// let _ = tracker.update(&data);
+///
+/// data.general.update = String::from("20240302180100");
+/// data.pilots[0].altitude += 2000;
+/// let _ = tracker.update(&data);
+///
+/// let pilot = &data.pilots[0];
+/// let flight = tracker.flight(pilot.cid, &pilot.callsign).unwrap();
+/// assert_eq!(flight.phase, FlightPhase::Climb);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FlightTracker {
+    gap_tolerance: Duration,
+    max_history: usize,
+    airports: AirportDatabase,
+    flights: HashMap<(u64, String), TrackedFlight>,
+    events: Vec<FlightEvent>,
+}
+
+impl Default for FlightTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FlightTracker {
+    /// Create a new tracker, with a gap tolerance of two minutes and the
+    /// last 60 positions of each flight kept.
+    ///
+    /// With the `airports` feature, the bundled airport data is used to
+    /// tell when flights are close to their destination.
+    #[must_use]
+    pub fn new() -> Self {
+        #[cfg(feature = "airports")]
+        let airports = AirportDatabase::bundled();
+        #[cfg(not(feature = "airports"))]
+        let airports = AirportDatabase::default();
+        Self {
+            gap_tolerance: Duration::from_secs(120),
+            max_history: 60,
+            airports,
+            flights: HashMap::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Set how long a flight can be missing from snapshots before it's
+    /// finished.
+    #[must_use]
+    pub fn gap_tolerance(mut self, gap_tolerance: Duration) -> Self {
+        self.gap_tolerance = gap_tolerance;
+        self
+    }
+
+    /// Set how many positions are kept for each flight.
+    #[must_use]
+    pub fn max_history(mut self, max_history: usize) -> Self {
+        self.max_history = max_history.max(1);
+        self
+    }
+
+    /// Set the airport locations used to tell when flights are close to
+    /// their destination and where they landed. Without them, flights are
+    /// never classed as on [approach](FlightPhase::Approach), and no
+    /// [events](FlightEvent) are recognized.
+    #[must_use]
+    pub fn airports(mut self, airports: AirportDatabase) -> Self {
+        self.airports = airports;
+        self
+    }
+
+    /// Update the tracked flights from a new snapshot, returning any
+    /// flights that have finished.
+    ///
+    /// The snapshot's `update` time is used as the current time;
+    /// snapshots whose time can't be parsed are ignored.
+    pub fn update(&mut self, data: &V3ResponseData) -> Vec<TrackedFlight> {
+        let Some(now) = data.general.updated() else {
+            return Vec::new();
+        };
+        for pilot in &data.pilots {
+            let point = TrackPoint::from_pilot(pilot, now);
+            let flight = self
+                .flights
+                .entry((pilot.cid, pilot.callsign.clone()))
+                .or_insert_with(|| TrackedFlight {
+                    cid: pilot.cid,
+                    callsign: pilot.callsign.clone(),
+                    departure: None,
+                    arrival: None,
+                    phase: FlightPhase::Preflight,
+                    history: VecDeque::new(),
+                });
+            if flight.latest().is_some_and(|latest| latest.time >= now) {
+                continue;
+            }
+            flight.departure = pilot.departure().map(str::to_owned);
+            flight.arrival = pilot.arrival().map(str::to_owned);
+            flight.history.push_back(point);
+            while flight.history.len() > self.max_history {
+                let _ = flight.history.pop_front();
+            }
+            let to_destination = flight
+                .arrival
+                .as_deref()
+                .and_then(|arrival| self.airports.location(arrival))
+                .map(|(lat, lon)| distance_nm(point.latitude, point.longitude, lat, lon));
+            let previous = flight.phase;
+            flight.phase = flight.next_phase(&point, to_destination);
+            if let Some(event) = flight.arrival_event(previous, &self.airports) {
+                self.events.push(event);
+            }
+        }
+        let gap_tolerance = self.gap_tolerance;
+        let finished: Vec<_> = self
+            .flights
+            .iter()
+            .filter(|(_, flight)| {
+                flight.latest().is_some_and(|latest| {
+                    now.duration_since(latest.time)
+                        .is_ok_and(|missing| missing > gap_tolerance)
+                })
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        finished
+            .into_iter()
+            .filter_map(|key| self.flights.remove(&key))
+            .collect()
+    }
+
+    /// Take the go-arounds and diversions recognized since the last call,
+    /// oldest first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "fixtures")] {
+    /// use vatsim_utils::{
+    ///     distance::AirportDatabase,
+    ///     fixtures,
+    ///     tracking::{FlightEvent, FlightTracker},
+    /// };
+    ///
+    /// let airports = AirportDatabase::from_csv("KLAX,33.9416,-118.4085\nKONT,34.0560,-117.6012").unwrap();
+    /// let mut tracker = FlightTracker::new().airports(airports);
+    /// let mut data = fixtures::v3_data();
+    /// data.pilots.truncate(1);
+    /// data.pilots[0].flight_plan.as_mut().unwrap().arrival = String::from("KLAX");
+    ///
+    /// // cruising, then landing at Ontario instead of Los Angeles
+    /// for (update, altitude, groundspeed) in [
+    ///     ("20240302180000", 10000, 250),
+    ///     ("20240302180100", 10000, 250),
+    ///     ("20240302180200", 1000, 20),
+    /// ] {
+    ///     data.general.update = String::from(update);
+    ///     data.pilots[0].latitude = 34.0560;
+    ///     data.pilots[0].longitude = -117.6012;
+    ///     data.pilots[0].altitude = altitude;
+    ///     data.pilots[0].groundspeed = groundspeed;
+    ///     let _ = tracker.update(&data);
+    /// }
+    ///
+    /// let events = tracker.take_events();
+    /// assert!(matches!(
+    ///     &events[..],
+    ///     [FlightEvent::Diverted { filed, airport, .. }] if filed == "KLAX" && airport == "KONT",
+    /// ));
+    /// assert!(tracker.take_events().is_empty());
+    /// # }
+    /// ```
+    ///
+    /// A flight only goes around if it gets low near the airport before
+    /// climbing away; climbing from higher up on approach doesn't count.
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "fixtures")] {
+    /// use vatsim_utils::{
+    ///     distance::AirportDatabase,
+    ///     fixtures,
+    ///     tracking::{FlightEvent, FlightTracker},
+    /// };
+    ///
+    /// let track = |altitudes: [i64; 4]| {
+    ///     let airports = AirportDatabase::from_csv("KLAX,33.9416,-118.4085").unwrap();
+    ///     let mut tracker = FlightTracker::new().airports(airports);
+    ///     let mut data = fixtures::v3_data();
+    ///     data.pilots.truncate(1);
+    ///     data.pilots[0].flight_plan.as_mut().unwrap().arrival = String::from("KLAX");
+    ///     data.pilots[0].latitude = 33.95;
+    ///     data.pilots[0].longitude = -118.45;
+    ///     data.pilots[0].groundspeed = 150;
+    ///     for (minute, altitude) in altitudes.into_iter().enumerate() {
+    ///         data.general.update = format!("2024030218{minute:02}00");
+    ///         data.pilots[0].altitude = altitude;
+    ///         let _ = tracker.update(&data);
+    ///     }
+    ///     tracker.take_events()
+    /// };
+    ///
+    /// assert!(matches!(
+    ///     &track([6000, 4000, 1000, 3000])[..],
+    ///     [FlightEvent::GoAround { airport, .. }] if airport == "KLAX",
+    /// ));
+    /// assert!(track([8000, 6000, 4000, 6000]).is_empty());
+    /// # }
+    /// ```
+    pub fn take_events(&mut self) -> Vec<FlightEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Flights that are being tracked.
+    pub fn flights(&self) -> impl Iterator<Item = &TrackedFlight> {
+        self.flights.values()
+    }
+
+    /// Flights flying holding patterns, with their holds.
+    ///
+    /// See [`TrackedFlight::holding`] for how holds are detected.
+    pub fn holding(&self) -> impl Iterator<Item = (&TrackedFlight, Hold)> {
+        self.flights
+            .values()
+            .filter_map(|flight| Some((flight, flight.holding()?)))
+    }
+
+    /// The tracked flight for the pilot, if there is one.
+    #[must_use]
+    pub fn flight(&self, cid: u64, callsign: &str) -> Option<&TrackedFlight> {
+        self.flights.get(&(cid, callsign.to_owned()))
+    }
+}
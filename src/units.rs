@@ -0,0 +1,123 @@
+//! Typed quantities for distances, speeds, and angles.
+//!
+//! Elsewhere, the crate uses plain numbers in aviation units: nautical miles,
+//! feet, knots, and degrees. The functions and accessors here return [`uom`]
+//! quantities instead, which can be converted to any other unit and can't be
+//! mixed up with each other.
+//!
+//! Requires the `uom` feature.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::live_api::Vatsim;
+//! use uom::si::{length::meter, velocity::kilometer_per_hour};
+//!
+//! # async fn _do() {
+//! let api = Vatsim::new().await.unwrap();
+//! let data = api.get_v3_data().await.unwrap();
+//! for pilot in &data.pilots {
+//!     println!(
+//!         "{} is at {:.0} m, doing {:.0} km/h",
+//!         pilot.callsign,
+//!         pilot.altitude_length().get::<meter>(),
+//!         pilot.groundspeed_velocity().get::<kilometer_per_hour>(),
+//!     );
+//! }
+//! # }
+//! ```
+
+use crate::models::{Controller, Pilot};
+use uom::si::{angle::degree, length::foot, length::nautical_mile, velocity::knot};
+
+pub use uom::si::f64::{Angle, Length, Velocity};
+
+/// Great-circle distance between two (lat & long) points.
+///
+/// Unlike [`haversine`](crate::distance::haversine), the distance isn't
+/// rounded.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::units::distance;
+/// use uom::si::length::kilometer;
+///
+/// let distance = distance(32.7338, -117.1933, 33.9416, -118.4085);
+///
+/// assert_eq!(distance.get::<kilometer>().round() as i64, 175);
+/// ```
+#[must_use]
+pub fn distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> Length {
+    Length::new::<nautical_mile>(crate::distance::distance_nm(lat1, lon1, lat2, lon2))
+}
+
+/// The initial bearing (true, 0..360 degrees) to follow along a great
+/// circle from the first point to the second; see
+/// [`initial_bearing`](crate::distance::initial_bearing).
+#[must_use]
+pub fn initial_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> Angle {
+    Angle::new::<degree>(crate::distance::initial_bearing(lat1, lon1, lat2, lon2))
+}
+
+/// The point reached by travelling from a starting point along a great
+/// circle for the distance on the initial bearing (true); see
+/// [`destination_point`](crate::distance::destination_point).
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::units::{destination_point, distance, Angle, Length};
+/// use uom::si::{angle::degree, length::{kilometer, nautical_mile}};
+///
+/// let (lat, lon) = destination_point(
+///     32.7338,
+///     -117.1933,
+///     Angle::new::<degree>(90.0),
+///     Length::new::<kilometer>(50.0),
+/// );
+/// let travelled = distance(32.7338, -117.1933, lat, lon);
+///
+/// assert_eq!(travelled.get::<nautical_mile>().round() as i64, 27);
+/// ```
+#[must_use]
+pub fn destination_point(lat: f64, lon: f64, bearing: Angle, distance: Length) -> (f64, f64) {
+    crate::distance::destination_point(
+        lat,
+        lon,
+        bearing.get::<degree>(),
+        distance.get::<nautical_mile>(),
+    )
+}
+
+impl Pilot {
+    /// The pilot's altitude.
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn altitude_length(&self) -> Length {
+        Length::new::<foot>(self.altitude as f64)
+    }
+
+    /// The pilot's groundspeed.
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn groundspeed_velocity(&self) -> Velocity {
+        Velocity::new::<knot>(self.groundspeed as f64)
+    }
+
+    /// The pilot's heading.
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn heading_angle(&self) -> Angle {
+        Angle::new::<degree>(self.heading as f64)
+    }
+}
+
+impl Controller {
+    /// The controller's visibility range.
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn visual_range_length(&self) -> Length {
+        Length::new::<nautical_mile>(self.visual_range as f64)
+    }
+}
@@ -0,0 +1,170 @@
+//! The base URLs of every VATSIM service that this crate talks to.
+//!
+//! The official URLs are used by default. Override them with
+//! [`set_endpoints`] to point the crate at a mirror, a mock server
+//! in tests, or a local caching proxy; every function that makes a
+//! request reads the current values when it's called.
+//!
+//! # Example
+//!
+//! ```rust
+//! use vatsim_utils::urls::{endpoints, reset_endpoints, set_endpoints, Endpoints};
+//!
+//! set_endpoints(Endpoints::default().api("http://localhost:8080/"));
+//! assert_eq!(endpoints().api, "http://localhost:8080");
+//!
+//! reset_endpoints();
+//! assert_eq!(endpoints().api, "https://api.vatsim.net");
+//! ```
+
+use crate::config;
+use std::sync::{LazyLock, PoisonError, RwLock};
+
+/// Currently-configured endpoints.
+static ENDPOINTS: LazyLock<RwLock<Endpoints>> = LazyLock::new(|| RwLock::new(Endpoints::default()));
+
+/// Base URLs for the VATSIM services.
+///
+/// Other than `status`, which is the full URL of the status JSON file,
+/// these are the URLs that paths are appended to, and never end
+/// with a slash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoints {
+    /// The status file that lists the live data feed URLs.
+    pub status: String,
+    /// The core REST API.
+    pub api: String,
+    /// The METAR service.
+    pub metar: String,
+    /// The events API.
+    pub events: String,
+    /// The ATC bookings API.
+    pub bookings: String,
+    /// VATSIM Connect, for authentication.
+    pub auth: String,
+    /// The slurper, for quick checks of whether a user is online.
+    pub slurper: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            status: String::from("https://status.vatsim.net/status.json"),
+            api: String::from("https://api.vatsim.net"),
+            metar: String::from("https://metar.vatsim.net"),
+            events: String::from("https://my.vatsim.net/api/v2/events"),
+            bookings: String::from("https://atc-bookings.vatsim.net/api"),
+            auth: String::from("https://auth.vatsim.net"),
+            slurper: String::from("https://slurper.vatsim.net"),
+        }
+    }
+}
+
+impl Endpoints {
+    /// Set the status file URL.
+    #[must_use]
+    pub fn status(mut self, url: &str) -> Self {
+        url.clone_into(&mut self.status);
+        self
+    }
+
+    /// Set the REST API base URL.
+    #[must_use]
+    pub fn api(mut self, url: &str) -> Self {
+        self.api = base(url);
+        self
+    }
+
+    /// Set the METAR service base URL.
+    #[must_use]
+    pub fn metar(mut self, url: &str) -> Self {
+        self.metar = base(url);
+        self
+    }
+
+    /// Set the events API base URL.
+    #[must_use]
+    pub fn events(mut self, url: &str) -> Self {
+        self.events = base(url);
+        self
+    }
+
+    /// Set the ATC bookings API base URL.
+    #[must_use]
+    pub fn bookings(mut self, url: &str) -> Self {
+        self.bookings = base(url);
+        self
+    }
+
+    /// Set the VATSIM Connect base URL.
+    #[must_use]
+    pub fn auth(mut self, url: &str) -> Self {
+        self.auth = base(url);
+        self
+    }
+
+    /// Set the slurper base URL.
+    #[must_use]
+    pub fn slurper(mut self, url: &str) -> Self {
+        self.slurper = base(url);
+        self
+    }
+
+    /// Read the base URLs from the environment variables listed in the
+    /// [config module docs](crate::config), using the official URLs for
+    /// any that aren't set.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let mut endpoints = Self::default();
+        if let Some(url) = config::var("VATSIM_STATUS_URL") {
+            endpoints = endpoints.status(&url);
+        }
+        if let Some(url) = config::var("VATSIM_API_URL") {
+            endpoints = endpoints.api(&url);
+        }
+        if let Some(url) = config::var("VATSIM_METAR_URL") {
+            endpoints = endpoints.metar(&url);
+        }
+        if let Some(url) = config::var("VATSIM_EVENTS_URL") {
+            endpoints = endpoints.events(&url);
+        }
+        if let Some(url) = config::var("VATSIM_BOOKINGS_URL") {
+            endpoints = endpoints.bookings(&url);
+        }
+        if let Some(url) = config::var("VATSIM_AUTH_URL") {
+            endpoints = endpoints.auth(&url);
+        }
+        if let Some(url) = config::var("VATSIM_SLURPER_URL") {
+            endpoints = endpoints.slurper(&url);
+        }
+        endpoints
+    }
+}
+
+/// Get a copy of the currently-configured endpoints.
+#[must_use]
+pub fn endpoints() -> Endpoints {
+    ENDPOINTS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Replace the configured endpoints.
+///
+/// This affects all later requests made by the crate, from any thread.
+/// Note that [`crate::live_api::Vatsim`] instances read the status file
+/// when they're created, so existing instances aren't affected.
+pub fn set_endpoints(endpoints: Endpoints) {
+    *ENDPOINTS.write().unwrap_or_else(PoisonError::into_inner) = endpoints;
+}
+
+/// Restore the official endpoints.
+pub fn reset_endpoints() {
+    set_endpoints(Endpoints::default());
+}
+
+/// Strip any trailing slashes from a base URL.
+fn base(url: &str) -> String {
+    url.trim_end_matches('/').to_owned()
+}
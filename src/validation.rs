@@ -0,0 +1,272 @@
+//! Data-quality checks for snapshots of live data.
+//!
+//! The feed occasionally has problems - a pilot reported twice after a
+//! reconnect, a position of `NaN`, a client count that doesn't match the
+//! lists - and code that monitors the feed wants to know about them
+//! without tripping over them. [`V3ResponseData::validate`] runs every
+//! check and returns a [`ValidationReport`] listing what it found.
+//!
+//! Pilots with impossible positions, speeds, or altitudes can also be
+//! dropped or clamped into range with [`sanitize_pilots`], so that mapping
+//! code downstream doesn't have to guard against them.
+
+use crate::models::{Pilot, V3ResponseData};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Lowest pilot altitude, in feet, that's considered plausible; the
+/// lowest airports are around 1,300 feet below sea level.
+pub const MIN_PLAUSIBLE_ALTITUDE: i64 = -2_000;
+
+/// Highest pilot groundspeed, in knots, that's considered plausible;
+/// well above anything flown on the network.
+pub const MAX_PLAUSIBLE_GROUNDSPEED: i64 = 2_500;
+
+/// A problem found in a snapshot.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub enum ValidationIssue {
+    /// A CID connected as more than one pilot, or as more than one
+    /// controller.
+    DuplicateCid {
+        /// The CID.
+        cid: u64,
+        /// The callsigns it's connected as.
+        callsigns: Vec<String>,
+    },
+    /// A callsign used by more than one connection, across the pilots,
+    /// controllers, and ATIS stations.
+    DuplicateCallsign {
+        /// The callsign.
+        callsign: String,
+        /// Number of connections using it.
+        count: usize,
+    },
+    /// A pilot whose position is `NaN`, infinite, or off the globe.
+    InvalidCoordinates {
+        /// The pilot's callsign.
+        callsign: String,
+        /// The reported latitude.
+        latitude: f64,
+        /// The reported longitude.
+        longitude: f64,
+    },
+    /// A pilot lower than [`MIN_PLAUSIBLE_ALTITUDE`].
+    ImplausibleAltitude {
+        /// The pilot's callsign.
+        callsign: String,
+        /// The reported altitude, in feet.
+        altitude: i64,
+    },
+    /// A pilot with a negative groundspeed or one faster than
+    /// [`MAX_PLAUSIBLE_GROUNDSPEED`].
+    ImplausibleGroundspeed {
+        /// The pilot's callsign.
+        callsign: String,
+        /// The reported groundspeed, in knots.
+        groundspeed: i64,
+    },
+    /// `general.connected_clients` doesn't match the number of pilots,
+    /// controllers, and ATIS stations in the snapshot.
+    ClientCountMismatch {
+        /// The count from `general.connected_clients`.
+        reported: i64,
+        /// The number of connections in the lists.
+        counted: usize,
+    },
+}
+
+/// The result of checking a snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct ValidationReport {
+    /// The problems found, in the order of the lists they came from.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Whether no problems were found.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl V3ResponseData {
+    /// Check the snapshot for duplicate CIDs and callsigns, invalid pilot
+    /// positions, altitudes, and groundspeeds, and a client count that
+    /// doesn't match the lists.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "fixtures")] {
+    /// use vatsim_utils::{fixtures, validation::ValidationIssue};
+    ///
+    /// let mut data = fixtures::v3_data();
+    /// assert!(data.validate().is_valid());
+    ///
+    /// data.pilots[0].latitude = f64::NAN;
+    /// let report = data.validate();
+    /// assert!(matches!(
+    ///     report.issues[0],
+    ///     ValidationIssue::InvalidCoordinates { .. }
+    /// ));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn validate(&self) -> ValidationReport {
+        let mut issues = Vec::new();
+
+        let pilots = self.pilots.iter().map(|p| (p.cid, &p.callsign));
+        let controllers = self.controllers.iter().map(|c| (c.cid, &c.callsign));
+        for (cid, callsigns) in duplicate_cids(pilots).chain(duplicate_cids(controllers)) {
+            issues.push(ValidationIssue::DuplicateCid { cid, callsigns });
+        }
+
+        let mut callsigns: BTreeMap<&str, usize> = BTreeMap::new();
+        for callsign in self
+            .pilots
+            .iter()
+            .map(|p| &p.callsign)
+            .chain(self.controllers.iter().map(|c| &c.callsign))
+            .chain(self.atis.iter().map(|a| &a.callsign))
+        {
+            *callsigns.entry(callsign).or_default() += 1;
+        }
+        for (callsign, count) in callsigns.into_iter().filter(|(_, count)| *count > 1) {
+            issues.push(ValidationIssue::DuplicateCallsign {
+                callsign: callsign.to_owned(),
+                count,
+            });
+        }
+
+        issues.extend(self.pilots.iter().flat_map(pilot_issues));
+
+        let counted = self.pilots.len() + self.controllers.len() + self.atis.len();
+        if usize::try_from(self.general.connected_clients).ok() != Some(counted) {
+            issues.push(ValidationIssue::ClientCountMismatch {
+                reported: self.general.connected_clients,
+                counted,
+            });
+        }
+
+        ValidationReport { issues }
+    }
+
+    /// Deal with pilots with impossible positions, groundspeeds, or
+    /// altitudes according to the policy, returning what was found.
+    ///
+    /// See [`sanitize_pilots`] for details.
+    pub fn sanitize(&mut self, policy: SanitizePolicy) -> Vec<ValidationIssue> {
+        sanitize_pilots(&mut self.pilots, policy)
+    }
+}
+
+/// What to do with pilots that have impossible values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SanitizePolicy {
+    /// Remove the pilots.
+    Drop,
+    /// Bring the values into range: latitudes to ±90, groundspeeds to
+    /// between 0 and [`MAX_PLAUSIBLE_GROUNDSPEED`], and altitudes to
+    /// [`MIN_PLAUSIBLE_ALTITUDE`]. Longitudes are wrapped around to
+    /// within ±180, so 190 becomes -170, the same meridian. Positions
+    /// that aren't numbers at all, or longitudes that are infinite, can't
+    /// be brought into range, so those pilots are removed.
+    Clamp,
+    /// Leave the pilots as they are, only reporting them.
+    Flag,
+}
+
+/// Deal with pilots with impossible positions, groundspeeds, or altitudes
+/// according to the policy, returning what was found in them before any
+/// changes.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "fixtures")] {
+/// use vatsim_utils::{fixtures, validation::{sanitize_pilots, SanitizePolicy}};
+///
+/// let mut pilots = fixtures::v3_data().pilots;
+/// pilots[0].latitude = 91.5;
+/// pilots[1].groundspeed = 10_000;
+/// pilots[2].longitude = 190.0;
+///
+/// let issues = sanitize_pilots(&mut pilots, SanitizePolicy::Clamp);
+/// assert_eq!(issues.len(), 3);
+/// assert_eq!(pilots[0].latitude, 90.0);
+/// assert_eq!(pilots[1].groundspeed, 2_500);
+/// assert_eq!(pilots[2].longitude, -170.0);
+/// # }
+/// ```
+pub fn sanitize_pilots(pilots: &mut Vec<Pilot>, policy: SanitizePolicy) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    pilots.retain_mut(|pilot| {
+        let found = pilot_issues(pilot);
+        if found.is_empty() {
+            return true;
+        }
+        issues.extend(found);
+        match policy {
+            SanitizePolicy::Drop => false,
+            SanitizePolicy::Flag => true,
+            SanitizePolicy::Clamp => {
+                if pilot.latitude.is_nan() || !pilot.longitude.is_finite() {
+                    return false;
+                }
+                pilot.latitude = pilot.latitude.clamp(-90.0, 90.0);
+                if !(-180.0..=180.0).contains(&pilot.longitude) {
+                    pilot.longitude = (pilot.longitude + 180.0).rem_euclid(360.0) - 180.0;
+                }
+                pilot.groundspeed = pilot.groundspeed.clamp(0, MAX_PLAUSIBLE_GROUNDSPEED);
+                pilot.altitude = pilot.altitude.max(MIN_PLAUSIBLE_ALTITUDE);
+                true
+            }
+        }
+    });
+    issues
+}
+
+/// Problems with a single pilot's values.
+fn pilot_issues(pilot: &Pilot) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    if !valid_coordinates(pilot.latitude, pilot.longitude) {
+        issues.push(ValidationIssue::InvalidCoordinates {
+            callsign: pilot.callsign.clone(),
+            latitude: pilot.latitude,
+            longitude: pilot.longitude,
+        });
+    }
+    if !(0..=MAX_PLAUSIBLE_GROUNDSPEED).contains(&pilot.groundspeed) {
+        issues.push(ValidationIssue::ImplausibleGroundspeed {
+            callsign: pilot.callsign.clone(),
+            groundspeed: pilot.groundspeed,
+        });
+    }
+    if pilot.altitude < MIN_PLAUSIBLE_ALTITUDE {
+        issues.push(ValidationIssue::ImplausibleAltitude {
+            callsign: pilot.callsign.clone(),
+            altitude: pilot.altitude,
+        });
+    }
+    issues
+}
+
+/// Whether the position is a real point on the globe.
+fn valid_coordinates(latitude: f64, longitude: f64) -> bool {
+    (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)
+}
+
+/// CIDs that appear more than once, with their callsigns, sorted by CID.
+fn duplicate_cids<'a>(
+    connections: impl Iterator<Item = (u64, &'a String)>,
+) -> impl Iterator<Item = (u64, Vec<String>)> {
+    let mut by_cid: BTreeMap<u64, Vec<String>> = BTreeMap::new();
+    for (cid, callsign) in connections {
+        by_cid.entry(cid).or_default().push(callsign.clone());
+    }
+    by_cid
+        .into_iter()
+        .filter(|(_, callsigns)| callsigns.len() > 1)
+}
@@ -0,0 +1,298 @@
+//! Sending [`NetworkEvent`]s to HTTP endpoints as they happen.
+//!
+//! Configure a [`WebhookDispatcher`] with one or more [`Webhook`]s, each
+//! with its own [`EventFilter`], and pass it the events returned from
+//! [`diff`](crate::diff::diff). Every matching event is `POST`ed to the
+//! webhook's URL as JSON.
+//!
+//! If a webhook has a secret set, requests include a `X-Vatsim-Signature`
+//! header containing `sha256=` followed by the hex-encoded HMAC-SHA256 of
+//! the request body, so receivers can verify where the request came from.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vatsim_utils::{
+//!     diff::{diff, EventKind},
+//!     live_api::Vatsim,
+//!     webhooks::{EventFilter, Webhook, WebhookDispatcher},
+//! };
+//!
+//! # async fn _do() {
+//! let dispatcher = WebhookDispatcher::new().webhook(
+//!     Webhook::new("https://example.com/hook")
+//!         .secret("hunter2")
+//!         .filter(
+//!             EventFilter::default()
+//!                 .kind(EventKind::ControllerConnected)
+//!                 .callsign_prefix("SAN_"),
+//!         ),
+//! );
+//! let api = Vatsim::new().await.unwrap();
+//! let previous = api.get_v3_data().await.unwrap();
+//! // wait for the feed to update ...
+//! let current = api.get_v3_data().await.unwrap();
+//! let failures = dispatcher.dispatch(&diff(&previous, &current)).await;
+//! # }
+//! ```
+
+use crate::{
+    config,
+    diff::{EventKind, NetworkEvent},
+    errors::{Endpoint, VatsimUtilError},
+    http,
+};
+use hmac::{Hmac, Mac};
+use log::{debug, warn};
+use reqwest::Client;
+use sha2::Sha256;
+use std::{fmt::Write, time::Duration};
+
+/// Header containing the request body's signature.
+const SIGNATURE_HEADER: &str = "X-Vatsim-Signature";
+
+/// Rules for which events are sent to a webhook.
+///
+/// Each rule that has any values set must match for an event to
+/// be sent; a default filter matches every event.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Kinds of events to match.
+    pub kinds: Vec<EventKind>,
+    /// Callsign prefixes to match, like "SAN_".
+    pub callsign_prefixes: Vec<String>,
+    /// User CIDs to match.
+    pub cids: Vec<u64>,
+}
+
+impl EventFilter {
+    /// Add an event kind to match.
+    #[must_use]
+    pub fn kind(mut self, kind: EventKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+
+    /// Add a callsign prefix to match.
+    #[must_use]
+    pub fn callsign_prefix(mut self, prefix: &str) -> Self {
+        self.callsign_prefixes.push(prefix.to_owned());
+        self
+    }
+
+    /// Add a CID to match.
+    #[must_use]
+    pub fn cid(mut self, cid: u64) -> Self {
+        self.cids.push(cid);
+        self
+    }
+
+    /// Whether the event passes this filter.
+    #[must_use]
+    pub fn matches(&self, event: &NetworkEvent) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&event.kind()))
+            && (self.callsign_prefixes.is_empty()
+                || self
+                    .callsign_prefixes
+                    .iter()
+                    .any(|prefix| event.callsign().starts_with(prefix.as_str())))
+            && (self.cids.is_empty() || self.cids.contains(&event.cid()))
+    }
+}
+
+/// A single URL to send events to.
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    /// URL to `POST` events to.
+    pub url: String,
+    /// Which events to send.
+    pub filter: EventFilter,
+    /// Optional secret used to sign request bodies.
+    pub secret: Option<String>,
+}
+
+impl Webhook {
+    /// Create a new webhook that receives all events.
+    #[must_use]
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            filter: EventFilter::default(),
+            secret: None,
+        }
+    }
+
+    /// Set the filter for which events to send.
+    #[must_use]
+    pub fn filter(mut self, filter: EventFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Set the secret used to sign request bodies.
+    #[must_use]
+    pub fn secret(mut self, secret: &str) -> Self {
+        self.secret = Some(secret.to_owned());
+        self
+    }
+}
+
+/// An event that could not be delivered to a webhook.
+#[derive(Debug)]
+pub struct DeliveryFailure {
+    /// URL of the webhook.
+    pub url: String,
+    /// The event that failed to send.
+    pub event: NetworkEvent,
+    /// The error from the final attempt.
+    pub error: VatsimUtilError,
+}
+
+/// Sends events to configured webhooks.
+#[derive(Debug)]
+pub struct WebhookDispatcher {
+    client: Client,
+    webhooks: Vec<Webhook>,
+    max_attempts: u32,
+    retry_delay: Duration,
+}
+
+impl Default for WebhookDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebhookDispatcher {
+    /// Create a new dispatcher with no webhooks.
+    ///
+    /// By default, each delivery is attempted up to 3 times, waiting
+    /// 1 second before the first retry and doubling after that.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the HTTP user agent cannot be constructed, which
+    /// should never happen.
+    #[must_use]
+    pub fn new() -> Self {
+        let client = config::build_client();
+        Self {
+            client,
+            webhooks: Vec::new(),
+            max_attempts: 3,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+
+    /// Add a webhook.
+    #[must_use]
+    pub fn webhook(mut self, webhook: Webhook) -> Self {
+        self.webhooks.push(webhook);
+        self
+    }
+
+    /// Set the retry behavior.
+    ///
+    /// `max_attempts` includes the first attempt, so a value of 1
+    /// disables retries. `retry_delay` is doubled after each retry.
+    #[must_use]
+    pub fn retries(mut self, max_attempts: u32, retry_delay: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Send the events to each webhook whose filter they match.
+    ///
+    /// Delivery to all webhooks is attempted even if some fail; the
+    /// failures are returned.
+    pub async fn dispatch(&self, events: &[NetworkEvent]) -> Vec<DeliveryFailure> {
+        let mut failures = Vec::new();
+        for webhook in &self.webhooks {
+            for event in events.iter().filter(|e| webhook.filter.matches(e)) {
+                if let Err(error) = self.deliver(webhook, event).await {
+                    warn!("Could not send event to webhook {}: {error}", webhook.url);
+                    failures.push(DeliveryFailure {
+                        url: webhook.url.clone(),
+                        event: event.clone(),
+                        error,
+                    });
+                }
+            }
+        }
+        failures
+    }
+
+    /// Send a single event to a webhook, retrying if needed.
+    async fn deliver(
+        &self,
+        webhook: &Webhook,
+        event: &NetworkEvent,
+    ) -> Result<(), VatsimUtilError> {
+        let body = serde_json::to_vec(event)?;
+        let signature = webhook.secret.as_ref().map(|s| sign(s, &body));
+        let mut delay = self.retry_delay;
+        let mut attempt = 1;
+        loop {
+            debug!("Sending event to {} (attempt {attempt})", webhook.url);
+            let mut req = self
+                .client
+                .post(&webhook.url)
+                .header("Content-Type", "application/json")
+                .body(body.clone());
+            if let Some(sig) = &signature {
+                req = req.header(SIGNATURE_HEADER, sig);
+            }
+            let mut wait = delay;
+            let error = match http::send(Endpoint::Webhook, req).await {
+                Ok(_) => return Ok(()),
+                Err(error) if !error.is_retryable() => return Err(error),
+                Err(error) => error,
+            };
+            if let VatsimUtilError::RateLimited {
+                retry_after: Some(retry_after),
+                ..
+            } = error
+            {
+                wait = wait.max(retry_after);
+            }
+            if attempt >= self.max_attempts {
+                return Err(error);
+            }
+            futures_timer::Delay::new(wait).await;
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+}
+
+/// Compute the `X-Vatsim-Signature` header value for a request body
+/// signed with the webhook's secret, such as for a receiver to compare
+/// against the header it was sent.
+///
+/// # Example
+///
+/// ```rust
+/// use vatsim_utils::webhooks::sign;
+///
+/// assert_eq!(
+///     sign("Jefe", b"what do ya want for nothing?"),
+///     "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+/// );
+/// ```
+///
+/// # Panics
+///
+/// Will panic if the HMAC can't be created from the secret, which
+/// should never happen, as any length of key is allowed.
+#[must_use]
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key size");
+    mac.update(body);
+    let mut out = String::from("sha256=");
+    for byte in mac.finalize().into_bytes() {
+        let _ = write!(out, "{byte:02x}");
+    }
+    out
+}